@@ -0,0 +1,259 @@
+//! Build script companion of `zgen-abi`, generating typed bindings from ABI files at compile time.
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     zgen_abi_build::generate("abi/", std::env::var("OUT_DIR").unwrap()).unwrap();
+//! }
+//!
+//! // src/lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+//! ```
+//!
+//! Every ABI becomes a module of `sol!` declarations named after its file, e.g. `abi/IERC20.json`
+//! is `ierc20::TransferCall`, so the crate needs `zgen-abi` with the `derive` feature.
+
+use zgen_abi::{Abi, OwnedAbi, Param};
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Generates a module for each ABI and artifact json file in `abi_dir` and its subdirectories,
+/// written into `out_dir` next to a `bindings.rs` declaring all of them.
+///
+/// Cargo reruns the build script only when the directory changes, and files whose content is the
+/// same are not rewritten, which keeps unchanged bindings from triggering a rebuild. Returns the
+/// paths of the generated module files.
+pub fn generate<P: AsRef<Path>, Q: AsRef<Path>>(
+    abi_dir: P,
+    out_dir: Q,
+) -> Result<Vec<PathBuf>, String> {
+    let (abi_dir, out_dir) = (abi_dir.as_ref(), out_dir.as_ref());
+    println!("cargo:rerun-if-changed={}", abi_dir.display());
+
+    let mut files = Vec::new();
+    json_files(abi_dir, &mut files)?;
+    files.sort();
+
+    let mut modules = BTreeMap::new();
+    for file in files {
+        let module = module_name(&file);
+        if let Some(other) = modules.insert(module.clone(), file.clone()) {
+            return Err(format!(
+                "Both {} and {} generate the module {}.",
+                other.display(),
+                file.display(),
+                module
+            ));
+        }
+    }
+
+    let mut bindings = String::from("// Generated by zgen-abi-build, do not edit.\n");
+    let mut generated = Vec::with_capacity(modules.len());
+    for (module, file) in modules.iter() {
+        let json = fs::read_to_string(file)
+            .map_err(|e| format!("Couldn't read {}: {}", file.display(), e))?;
+        let abi = parse_abi(&json).map_err(|e| format!("{}: {}", file.display(), e))?;
+        let path = out_dir.join(format!("{}.rs", module));
+        write_if_changed(&path, &to_sol(&abi, file))?;
+        generated.push(path);
+        // paths in `include!` are relative to the including file, both are in `out_dir`
+        let _ = write!(
+            bindings,
+            "\npub mod {} {{\n    include!(\"{}.rs\");\n}}\n",
+            module, module
+        );
+    }
+    write_if_changed(&out_dir.join("bindings.rs"), &bindings)?;
+    Ok(generated)
+}
+
+fn json_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Couldn't read {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_dir() {
+            json_files(&path, files)?;
+        } else if path.extension().is_some_and(|e| e == "json") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// a plain ABI array, or a solc, Hardhat or Foundry artifact with an `abi` field
+fn parse_abi(json: &str) -> Result<OwnedAbi, String> {
+    Abi::from_str(json).map(Abi::into_owned)
+}
+
+// `UniswapV2Pair` is `uniswap_v2_pair`, `IERC20` is `ierc20`
+fn module_name(file: &Path) -> String {
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    let chars: Vec<char> = stem.chars().collect();
+    let mut module = String::with_capacity(chars.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next_is_lower)
+            {
+                module.push('_');
+            }
+        }
+        match c {
+            c if c.is_ascii_alphanumeric() => module.push(c.to_ascii_lowercase()),
+            _ => module.push('_'),
+        }
+    }
+    if module.is_empty() || module.starts_with(|c: char| c.is_ascii_digit()) {
+        module.insert(0, '_');
+    }
+    module
+}
+
+// the ABI as a `sol!` block, anonymous events are left out since their logs have no event topic
+fn to_sol(abi: &Abi, file: &Path) -> String {
+    let mut sol = format!(
+        "// Generated by zgen-abi-build from {}, do not edit.\n\n::zgen_abi::sol! {{\n",
+        file.display()
+    );
+    for function in abi.functions.iter() {
+        let mutability = match function.state_mutability.as_ref() {
+            "nonpayable" => String::new(),
+            mutability => format!(" {}", mutability),
+        };
+        let _ = write!(
+            sol,
+            "    function {}({}) external{}",
+            function.name,
+            params(&function.inputs),
+            mutability
+        );
+        if !function.outputs.is_empty() {
+            let _ = write!(sol, " returns ({})", params(&function.outputs));
+        }
+        sol.push_str(";\n");
+    }
+    for event in abi.events.iter().filter(|e| !e.anonymous) {
+        let _ = writeln!(sol, "    event {}({});", event.name, params(&event.inputs));
+    }
+    sol.push_str("}\n");
+    sol
+}
+
+fn params(params: &[Param]) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .map(|param| {
+            let mut declaration = param.canonical_type().into_owned();
+            if param.indexed {
+                declaration.push_str(" indexed");
+            }
+            if !param.name.is_empty() {
+                declaration.push(' ');
+                declaration.push_str(&param.name);
+            }
+            declaration
+        })
+        .collect();
+    params.join(", ")
+}
+
+// returns whether the file was written, an unchanged file keeps its modification time
+fn write_if_changed(path: &Path, contents: &str) -> Result<bool, String> {
+    if fs::read_to_string(path).is_ok_and(|current| current == contents) {
+        return Ok(false);
+    }
+    fs::write(path, contents).map_err(|e| format!("Couldn't write {}: {}", path.display(), e))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ERC20: &str = r#"[
+        {"type":"function","name":"transfer","stateMutability":"nonpayable",
+         "inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],
+         "outputs":[{"name":"","type":"bool"}]},
+        {"type":"function","name":"balanceOf","stateMutability":"view",
+         "inputs":[{"name":"","type":"address"}],"outputs":[{"name":"","type":"uint256"}]},
+        {"type":"event","name":"Transfer","anonymous":false,"inputs":[
+         {"name":"from","type":"address","indexed":true},
+         {"name":"to","type":"address","indexed":true},
+         {"name":"value","type":"uint256","indexed":false}]},
+        {"type":"event","name":"Anonymous","anonymous":true,"inputs":[]}
+    ]"#;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("zgen-abi-build-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn to_sol_test() {
+        let abi = parse_abi(ERC20).unwrap();
+        assert_eq!(
+            to_sol(&abi, Path::new("abi/IERC20.json")),
+            concat!(
+                "// Generated by zgen-abi-build from abi/IERC20.json, do not edit.\n\n",
+                "::zgen_abi::sol! {\n",
+                "    function transfer(address to, uint256 amount) external returns (bool);\n",
+                "    function balanceOf(address) external view returns (uint256);\n",
+                "    event Transfer(address indexed from, address indexed to, uint256 value);\n",
+                "}\n"
+            )
+        );
+        // artifacts hold the ABI next to the bytecode
+        let artifact = format!(r#"{{"abi":{},"bytecode":"0x"}}"#, ERC20);
+        assert_eq!(parse_abi(&artifact), Ok(abi));
+    }
+
+    #[test]
+    fn module_name_test() {
+        for (file, module) in [
+            ("abi/IERC20.json", "ierc20"),
+            ("UniswapV2Pair.json", "uniswap_v2_pair"),
+            ("ERC20Permit.json", "erc20_permit"),
+            ("out/Safe.sol/Safe.json", "safe"),
+            ("1inch-router.json", "_1inch_router"),
+        ] {
+            assert_eq!(module_name(Path::new(file)), module);
+        }
+    }
+
+    #[test]
+    fn generate_test() {
+        let (abi_dir, out_dir) = (temp_dir("abi"), temp_dir("out"));
+        fs::create_dir_all(abi_dir.join("tokens")).unwrap();
+        fs::write(abi_dir.join("tokens/ERC20.json"), ERC20).unwrap();
+        fs::write(abi_dir.join("Empty.json"), "[]").unwrap();
+        fs::write(abi_dir.join("README.md"), "not an ABI").unwrap();
+
+        let generated = generate(&abi_dir, &out_dir).unwrap();
+        assert_eq!(
+            generated,
+            vec![out_dir.join("empty.rs"), out_dir.join("erc20.rs")]
+        );
+        let bindings = fs::read_to_string(out_dir.join("bindings.rs")).unwrap();
+        assert!(bindings.contains("pub mod erc20 {\n    include!(\"erc20.rs\");\n}"));
+
+        // unchanged bindings are not rewritten
+        let contents = fs::read_to_string(&generated[1]).unwrap();
+        assert!(!write_if_changed(&generated[1], &contents).unwrap());
+        assert!(write_if_changed(&generated[1], "").unwrap());
+
+        fs::write(abi_dir.join("erc20.json"), ERC20).unwrap();
+        assert!(generate(&abi_dir, &out_dir).is_err());
+        fs::remove_dir_all(abi_dir).unwrap();
+        fs::remove_dir_all(out_dir).unwrap();
+    }
+}
@@ -0,0 +1,17 @@
+use syn::{Attribute, Ident, LitStr};
+
+// the `name` of a `#[attribute(name = "…")]` attribute, defaulting to the item's own name
+pub(crate) fn renamed(attrs: &[Attribute], attribute: &str, ident: &Ident) -> syn::Result<String> {
+    let mut name = ident.to_string();
+    for attr in attrs.iter().filter(|a| a.path().is_ident(attribute)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("expected `name = \"…\"`"))
+            }
+        })?;
+    }
+    Ok(name)
+}
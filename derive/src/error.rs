@@ -0,0 +1,152 @@
+use crate::attr::renamed;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields};
+
+// a single custom error, the struct itself or one variant of an enum
+struct Case<'a> {
+    path: TokenStream,
+    name: String,
+    fields: &'a Fields,
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "EthError can't be derived for generic types",
+        ));
+    }
+    let cases = match &input.data {
+        Data::Struct(data) => vec![Case {
+            path: quote! { Self },
+            name: renamed(&input.attrs, "eth_error", ident)?,
+            fields: &data.fields,
+        }],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_ident = &variant.ident;
+                Ok(Case {
+                    path: quote! { Self::#variant_ident },
+                    name: renamed(&variant.attrs, "eth_error", variant_ident)?,
+                    fields: &variant.fields,
+                })
+            })
+            .collect::<syn::Result<_>>()?,
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "EthError can only be derived for structs and enums",
+            ))
+        }
+    };
+
+    let signatures: Vec<TokenStream> = cases.iter().map(signature).collect();
+    let encode_arms = cases.iter().zip(&signatures).map(|(case, signature)| {
+        let bindings = bindings(case.fields);
+        let pattern = pattern(case, &bindings);
+        quote! {
+            #pattern => ::zgen_abi::__private::encode_error(
+                &#signature,
+                &[#(::zgen_abi::AbiType::into_token(::std::clone::Clone::clone(#bindings))),*],
+            )
+        }
+    });
+    let decode_branches = cases.iter().zip(&signatures).map(|(case, signature)| {
+        let types = case.fields.iter().map(|f| &f.ty);
+        let construct = construct(case);
+        quote! {
+            if selector == ::zgen_abi::selector(&#signature) {
+                #[allow(unused_mut, unused_variables)]
+                let mut tokens = ::zgen_abi::decode(
+                    &[#(<#types as ::zgen_abi::AbiType>::param_type()),*],
+                    &revert_data[4..],
+                )?
+                .into_iter();
+                return ::std::result::Result::Ok(#construct);
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl ::zgen_abi::EthError for #ident {
+            fn signatures() -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![#(#signatures),*]
+            }
+
+            fn encode(&self) -> ::std::vec::Vec<u8> {
+                match self {
+                    #(#encode_arms,)*
+                }
+            }
+
+            fn decode(
+                revert_data: &[u8],
+            ) -> ::std::result::Result<Self, ::std::string::String> {
+                let selector = ::zgen_abi::Selector::from_calldata(revert_data)
+                    .ok_or_else(|| "Revert data is shorter than a selector.".to_owned())?;
+                #(#decode_branches)*
+                ::std::result::Result::Err(::std::format!(
+                    "Revert data of unknown error {}.",
+                    selector
+                ))
+            }
+        }
+    })
+}
+
+// expression of the canonical signature of the error
+fn signature(case: &Case) -> TokenStream {
+    let name = &case.name;
+    let types = case.fields.iter().map(|f| &f.ty);
+    quote! {
+        {
+            let types: ::std::vec::Vec<::std::string::String> = ::std::vec![
+                #(<#types as ::zgen_abi::AbiType>::param_type().name_as_str().into_owned()),*
+            ];
+            ::std::format!("{}({})", #name, types.join(","))
+        }
+    }
+}
+
+// names bound to the fields when matching on `&self`
+fn bindings(fields: &Fields) -> Vec<syn::Ident> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("field_{}", i),
+        })
+        .collect()
+}
+
+fn pattern(case: &Case, bindings: &[syn::Ident]) -> TokenStream {
+    let path = &case.path;
+    match case.fields {
+        Fields::Named(_) => quote! { #path { #(#bindings),* } },
+        Fields::Unnamed(_) => quote! { #path(#(#bindings),*) },
+        Fields::Unit => quote! { #path },
+    }
+}
+
+// the value built from the next decoded tokens
+fn construct(case: &Case) -> TokenStream {
+    let path = &case.path;
+    let values = case.fields.iter().map(|field| {
+        let ty = &field.ty;
+        quote! { <#ty as ::zgen_abi::AbiType>::from_token(tokens.next().unwrap())? }
+    });
+    match case.fields {
+        Fields::Named(fields) => {
+            let members = fields.named.iter().map(|f| &f.ident);
+            quote! { #path { #(#members: #values),* } }
+        }
+        Fields::Unnamed(_) => quote! { #path(#(#values),*) },
+        Fields::Unit => quote! { #path },
+    }
+}
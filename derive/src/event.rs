@@ -0,0 +1,103 @@
+use crate::attr::renamed;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{Data, DeriveInput, Field, Fields, Token};
+
+// events have at most 4 topics, one of which is the event topic
+const MAX_INDEXED: usize = 3;
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let fields = named_fields(input, "EthEvent")?;
+    let name = renamed(&input.attrs, "event", &input.ident)?;
+
+    let indexed: Vec<&Field> = fields.iter().filter(|f| is_indexed(f)).collect();
+    if indexed.len() > MAX_INDEXED {
+        return Err(syn::Error::new_spanned(
+            indexed[MAX_INDEXED],
+            "events have at most 3 indexed parameters",
+        ));
+    }
+    let indexed_count = indexed.len();
+    let types = fields.iter().map(|f| &f.ty);
+    let data_types = fields.iter().filter(|f| !is_indexed(f)).map(|f| &f.ty);
+
+    // fields are initialized in declaration order, taking the next topic or data value
+    let mut topic = 0_usize;
+    let inits = fields.iter().map(|field| {
+        let (member, ty) = (&field.ident, &field.ty);
+        if is_indexed(field) {
+            topic += 1;
+            quote! { #member: ::zgen_abi::__private::decode_topic::<#ty>(&log.topics[#topic])? }
+        } else {
+            quote! {
+                #member: <#ty as ::zgen_abi::AbiType>::from_token(data.next().unwrap())?
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl ::zgen_abi::EthEvent for #ident {
+            const INDEXED: usize = #indexed_count;
+
+            fn signature() -> ::std::string::String {
+                let types: ::std::vec::Vec<::std::string::String> = ::std::vec![
+                    #(<#types as ::zgen_abi::AbiType>::param_type().name_as_str().into_owned()),*
+                ];
+                ::std::format!("{}({})", #name, types.join(","))
+            }
+
+            fn decode(
+                log: &::zgen_abi::Log,
+            ) -> ::std::result::Result<Self, ::std::string::String> {
+                if !<Self as ::zgen_abi::EthEvent>::matches(log) {
+                    return ::std::result::Result::Err(
+                        ::std::format!("Log is not a {} event.", #name),
+                    );
+                }
+                #[allow(unused_mut, unused_variables)]
+                let mut data = ::zgen_abi::decode(
+                    &[#(<#data_types as ::zgen_abi::AbiType>::param_type()),*],
+                    &log.data,
+                )?
+                .into_iter();
+                ::std::result::Result::Ok(Self { #(#inits),* })
+            }
+        }
+    })
+}
+
+// the fields of a non-generic struct with named fields
+pub(crate) fn named_fields<'a>(
+    input: &'a DeriveInput,
+    derive: &str,
+) -> syn::Result<&'a Punctuated<Field, Token![,]>> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            format!("{} can't be derived for generic types", derive),
+        ));
+    }
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(&fields.named),
+            _ => Err(syn::Error::new_spanned(
+                &input.ident,
+                format!(
+                    "{} can only be derived for structs with named fields",
+                    derive
+                ),
+            )),
+        },
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            format!("{} can only be derived for structs", derive),
+        )),
+    }
+}
+
+fn is_indexed(field: &Field) -> bool {
+    field.attrs.iter().any(|a| a.path().is_ident("indexed"))
+}
@@ -0,0 +1,54 @@
+//! Derive macros of `zgen-abi`, re-exported by it with the `derive` feature.
+//!
+//! The generated code refers to the `zgen_abi` crate, which has to be a dependency of the crate
+//! using the derives.
+
+mod attr;
+mod error;
+mod event;
+mod sol;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Implements `zgen_abi::EthEvent` for a struct with named fields, the event parameters.
+///
+/// Indexed parameters are marked with `#[indexed]`, the event is renamed with
+/// `#[event(name = "…")]`.
+#[proc_macro_derive(EthEvent, attributes(event, indexed))]
+pub fn derive_eth_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    event::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `zgen_abi::EthError` for a struct, a single custom error, or an enum of errors.
+///
+/// The fields are the error parameters, errors are named after the struct or variant unless
+/// renamed with `#[eth_error(name = "…")]`.
+#[proc_macro_derive(EthError, attributes(eth_error))]
+pub fn derive_eth_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    error::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Declares typed calls and events from Solidity-like declarations, without ABI json files.
+///
+/// ```ignore
+/// sol! {
+///     function swap(uint256 amount0Out, uint256 amount1Out, address to, bytes data) external;
+///     event Sync(uint112 reserve0, uint112 reserve1);
+/// }
+/// ```
+///
+/// A function `swap` becomes a `SwapCall` struct implementing `zgen_abi::EthCall`, an event a
+/// struct of the same name implementing `zgen_abi::EthEvent`. Unnamed parameters are the fields
+/// `_0`, `_1` and so on, indexed strings, bytes, arrays and tuples are their `[u8; 32]` hash.
+/// Overloads are numbered in declaration order, e.g. `SafeTransferFrom2Call`.
+#[proc_macro]
+pub fn sol(input: TokenStream) -> TokenStream {
+    sol::expand(parse_macro_input!(input as sol::Items)).into()
+}
@@ -0,0 +1,447 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{bracketed, parenthesized, token, Ident, LitInt, Token};
+
+use std::collections::HashMap;
+
+// the declarations of a `sol!` block
+pub(crate) struct Items(Vec<Item>);
+
+enum Item {
+    Function {
+        name: Ident,
+        inputs: Vec<Param>,
+        outputs: Vec<Param>,
+    },
+    Event {
+        name: Ident,
+        params: Vec<Param>,
+    },
+}
+
+struct Param {
+    kind: SolType,
+    indexed: bool,
+    name: Option<Ident>,
+}
+
+enum SolType {
+    Address,
+    Bool,
+    String,
+    Bytes,
+    FixedBytes(usize),
+    Uint(usize),
+    Int(usize),
+    Array(Box<SolType>),
+    FixedArray(Box<SolType>, usize),
+    Tuple(Vec<SolType>),
+}
+
+impl Parse for Items {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            items.push(input.parse()?);
+        }
+        Ok(Self(items))
+    }
+}
+
+impl Parse for Item {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let keyword = Ident::parse_any(input)?;
+        let item = if keyword == "function" {
+            let name = Ident::parse_any(input)?;
+            let inputs = params(input)?;
+            // visibility and state mutability don't change the ABI
+            let mut outputs = Vec::new();
+            while !input.peek(Token![;]) {
+                if Ident::parse_any(input)? == "returns" {
+                    outputs = params(input)?;
+                } else if input.peek(token::Paren) {
+                    // e.g. `override(A, B)`
+                    input.parse::<proc_macro2::Group>()?;
+                }
+            }
+            Item::Function {
+                name,
+                inputs,
+                outputs,
+            }
+        } else if keyword == "event" {
+            let name = Ident::parse_any(input)?;
+            let params = params(input)?;
+            if params.iter().filter(|p| p.indexed).count() > 3 {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "events have at most 3 indexed parameters",
+                ));
+            }
+            Item::Event { name, params }
+        } else {
+            return Err(syn::Error::new(
+                keyword.span(),
+                "expected a `function` or an `event`",
+            ));
+        };
+        input.parse::<Token![;]>()?;
+        Ok(item)
+    }
+}
+
+// a parenthesized parameter list like `(address indexed owner, uint256)`
+fn params(input: ParseStream) -> syn::Result<Vec<Param>> {
+    let content;
+    parenthesized!(content in input);
+    let mut params = Vec::new();
+    while !content.is_empty() {
+        let kind = content.parse()?;
+        let mut indexed = false;
+        let mut name = None;
+        while !content.is_empty() && !content.peek(Token![,]) {
+            let ident = Ident::parse_any(&content)?;
+            match ident.to_string().as_str() {
+                "indexed" => indexed = true,
+                "memory" | "calldata" | "storage" => {}
+                _ => name = Some(ident),
+            }
+        }
+        params.push(Param {
+            kind,
+            indexed,
+            name,
+        });
+        if !content.is_empty() {
+            content.parse::<Token![,]>()?;
+        }
+    }
+    Ok(params)
+}
+
+impl Parse for SolType {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut kind = if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let components = content.parse_terminated(SolType::parse, Token![,])?;
+            if components.is_empty() {
+                return Err(content.error("tuples have at least one component"));
+            }
+            SolType::Tuple(components.into_iter().collect())
+        } else {
+            let ident = Ident::parse_any(input)?;
+            elementary(&ident.to_string())
+                .ok_or_else(|| syn::Error::new(ident.span(), "unknown Solidity type"))?
+        };
+        while input.peek(token::Bracket) {
+            let content;
+            bracketed!(content in input);
+            kind = if content.is_empty() {
+                SolType::Array(Box::new(kind))
+            } else {
+                let size: LitInt = content.parse()?;
+                match size.base10_parse()? {
+                    0 => return Err(syn::Error::new(size.span(), "arrays can't be empty")),
+                    size => SolType::FixedArray(Box::new(kind), size),
+                }
+            };
+        }
+        Ok(kind)
+    }
+}
+
+fn elementary(name: &str) -> Option<SolType> {
+    let bits = |bits: &str| match bits {
+        "" => Some(256),
+        bits => bits
+            .parse::<usize>()
+            .ok()
+            .filter(|b| b % 8 == 0 && (8..=256).contains(b)),
+    };
+    Some(match name {
+        "address" => SolType::Address,
+        "bool" => SolType::Bool,
+        "string" => SolType::String,
+        "bytes" => SolType::Bytes,
+        name => {
+            if let Some(size) = name.strip_prefix("bytes") {
+                SolType::FixedBytes(size.parse().ok().filter(|s| (1..=32).contains(s))?)
+            } else if let Some(size) = name.strip_prefix("uint") {
+                SolType::Uint(bits(size)?)
+            } else if let Some(size) = name.strip_prefix("int") {
+                SolType::Int(bits(size)?)
+            } else {
+                return None;
+            }
+        }
+    })
+}
+
+impl SolType {
+    fn canonical(&self) -> String {
+        match self {
+            SolType::Address => "address".to_owned(),
+            SolType::Bool => "bool".to_owned(),
+            SolType::String => "string".to_owned(),
+            SolType::Bytes => "bytes".to_owned(),
+            SolType::FixedBytes(size) => format!("bytes{}", size),
+            SolType::Uint(bits) => format!("uint{}", bits),
+            SolType::Int(bits) => format!("int{}", bits),
+            SolType::Array(element) => format!("{}[]", element.canonical()),
+            SolType::FixedArray(element, size) => format!("{}[{}]", element.canonical(), size),
+            SolType::Tuple(components) => format!("({})", canonical_list(components.iter())),
+        }
+    }
+
+    // the Rust type holding values of the type, integers of native widths use the native type and
+    // others the range checked `Uint` and `Int`, byte arrays are `bytes` so `uint8` array elements
+    // are `Uint<8>`
+    fn rust_type(&self, element: bool) -> TokenStream {
+        match self {
+            SolType::Address => quote! { ::zgen_abi::Address },
+            SolType::Bool => quote! { bool },
+            SolType::String => quote! { ::std::string::String },
+            SolType::Bytes => quote! { ::std::vec::Vec<u8> },
+            SolType::FixedBytes(size) => quote! { [u8; #size] },
+            SolType::Uint(8) if element => quote! { ::zgen_abi::Uint<8> },
+            SolType::Uint(bits) => match bits {
+                8 => quote! { u8 },
+                16 => quote! { u16 },
+                32 => quote! { u32 },
+                64 => quote! { u64 },
+                128 => quote! { u128 },
+                256 => quote! { ::zgen_abi::U256 },
+                bits => quote! { ::zgen_abi::Uint<#bits> },
+            },
+            SolType::Int(bits) => match bits {
+                8 => quote! { i8 },
+                16 => quote! { i16 },
+                32 => quote! { i32 },
+                64 => quote! { i64 },
+                128 => quote! { i128 },
+                256 => quote! { ::zgen_abi::I256 },
+                bits => quote! { ::zgen_abi::Int<#bits> },
+            },
+            SolType::Array(element) => {
+                let element = element.rust_type(true);
+                quote! { ::std::vec::Vec<#element> }
+            }
+            SolType::FixedArray(element, size) => {
+                let element = element.rust_type(true);
+                quote! { [#element; #size] }
+            }
+            SolType::Tuple(components) => {
+                let components = components.iter().map(|c| c.rust_type(false));
+                quote! { (#(#components,)*) }
+            }
+        }
+    }
+
+    // indexed parameters of other types are stored as their hash
+    fn is_value_type(&self) -> bool {
+        !matches!(
+            self,
+            SolType::String
+                | SolType::Bytes
+                | SolType::Array(_)
+                | SolType::FixedArray(..)
+                | SolType::Tuple(_)
+        )
+    }
+}
+
+fn canonical_list<'a>(types: impl Iterator<Item = &'a SolType>) -> String {
+    types.map(SolType::canonical).collect::<Vec<_>>().join(",")
+}
+
+impl Param {
+    // Solidity names that are Rust keywords are raw identifiers, `self` and the like can't be
+    fn field(&self, i: usize) -> Ident {
+        match &self.name {
+            Some(name) if syn::parse2::<Ident>(quote! { #name }).is_ok() => name.clone(),
+            Some(name)
+                if !matches!(
+                    name.to_string().as_str(),
+                    "self" | "Self" | "super" | "crate"
+                ) =>
+            {
+                Ident::new_raw(&name.to_string(), name.span())
+            }
+            _ => format_ident!("_{}", i),
+        }
+    }
+
+    fn rust_type(&self) -> TokenStream {
+        if self.indexed && !self.kind.is_value_type() {
+            quote! { [u8; 32] }
+        } else {
+            self.kind.rust_type(false)
+        }
+    }
+}
+
+// `fooBar` is `FooBar`
+fn pascal_case(name: &Ident) -> String {
+    let name = name.unraw().to_string();
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => name,
+    }
+}
+
+pub(crate) fn expand(items: Items) -> TokenStream {
+    // overloads are numbered in declaration order, e.g. `SafeTransferFromCall` and
+    // `SafeTransferFrom2Call`
+    let mut overloads = HashMap::<String, usize>::new();
+    let mut overloaded = |kind: &str, name: &Ident| {
+        let count = overloads
+            .entry(format!("{} {}", kind, name.unraw()))
+            .or_insert(0);
+        *count += 1;
+        match *count {
+            1 => String::new(),
+            count => count.to_string(),
+        }
+    };
+    items
+        .0
+        .iter()
+        .map(|item| match item {
+            Item::Function {
+                name,
+                inputs,
+                outputs,
+            } => {
+                let ident = format!("{}{}Call", pascal_case(name), overloaded("function", name));
+                function(&Ident::new(&ident, name.span()), name, inputs, outputs)
+            }
+            Item::Event { name, params } => {
+                let ident = format!("{}{}", name.unraw(), overloaded("event", name));
+                event(&Ident::new(&ident, name.span()), name, params)
+            }
+        })
+        .collect()
+}
+
+fn function(ident: &Ident, name: &Ident, inputs: &[Param], outputs: &[Param]) -> TokenStream {
+    let signature = format!(
+        "{}({})",
+        name.unraw(),
+        canonical_list(inputs.iter().map(|p| &p.kind))
+    );
+    let fields: Vec<Ident> = inputs.iter().enumerate().map(|(i, p)| p.field(i)).collect();
+    let types = inputs.iter().map(Param::rust_type);
+    let input_types = inputs.iter().map(|p| p.kind.canonical());
+    let output_types = outputs.iter().map(|p| p.kind.canonical());
+    let returns = match outputs {
+        [output] => output.rust_type(),
+        outputs => {
+            let outputs = outputs.iter().map(Param::rust_type);
+            quote! { (#(#outputs,)*) }
+        }
+    };
+
+    quote! {
+        #[allow(non_snake_case)]
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct #ident {
+            #(pub #fields: #types,)*
+        }
+
+        impl ::zgen_abi::EthCall for #ident {
+            const SIGNATURE: &'static str = #signature;
+
+            type Return = #returns;
+
+            fn input_types() -> ::std::vec::Vec<::zgen_abi::ParamType> {
+                ::zgen_abi::__private::param_types(&[#(#input_types),*])
+            }
+
+            fn output_types() -> ::std::vec::Vec<::zgen_abi::ParamType> {
+                ::zgen_abi::__private::param_types(&[#(#output_types),*])
+            }
+
+            fn into_tokens(self) -> ::std::vec::Vec<::zgen_abi::EthereumTypes> {
+                ::std::vec![#(::zgen_abi::AbiType::into_token(self.#fields)),*]
+            }
+
+            #[allow(unused_mut, unused_variables)]
+            fn from_tokens(
+                tokens: ::std::vec::Vec<::zgen_abi::EthereumTypes>,
+            ) -> ::std::result::Result<Self, ::std::string::String> {
+                let mut tokens = tokens.into_iter();
+                ::std::result::Result::Ok(Self {
+                    #(#fields: ::zgen_abi::AbiType::from_token(
+                        tokens.next().ok_or_else(|| "Missing argument.".to_owned())?,
+                    )?,)*
+                })
+            }
+        }
+    }
+}
+
+fn event(ident: &Ident, name: &Ident, params: &[Param]) -> TokenStream {
+    let signature = format!(
+        "{}({})",
+        name.unraw(),
+        canonical_list(params.iter().map(|p| &p.kind))
+    );
+    let fields: Vec<Ident> = params.iter().enumerate().map(|(i, p)| p.field(i)).collect();
+    let types: Vec<TokenStream> = params.iter().map(Param::rust_type).collect();
+    let indexed_count = params.iter().filter(|p| p.indexed).count();
+    let data_types = params
+        .iter()
+        .filter(|p| !p.indexed)
+        .map(|p| p.kind.canonical());
+
+    // fields are initialized in declaration order, taking the next topic or data value
+    let mut topic = 0_usize;
+    let inits = params
+        .iter()
+        .zip(&fields)
+        .zip(&types)
+        .map(|((param, field), ty)| {
+            if param.indexed {
+                topic += 1;
+                quote! { #field: ::zgen_abi::__private::decode_topic::<#ty>(&log.topics[#topic])? }
+            } else {
+                quote! { #field: ::zgen_abi::AbiType::from_token(data.next().unwrap())? }
+            }
+        });
+    let error = format!("Log is not a {} event.", name.unraw());
+
+    quote! {
+        #[allow(non_snake_case)]
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct #ident {
+            #(pub #fields: #types,)*
+        }
+
+        impl ::zgen_abi::EthEvent for #ident {
+            const INDEXED: usize = #indexed_count;
+
+            fn signature() -> ::std::string::String {
+                #signature.to_owned()
+            }
+
+            fn decode(
+                log: &::zgen_abi::Log,
+            ) -> ::std::result::Result<Self, ::std::string::String> {
+                if !<Self as ::zgen_abi::EthEvent>::matches(log) {
+                    return ::std::result::Result::Err(#error.to_owned());
+                }
+                #[allow(unused_mut, unused_variables)]
+                let mut data = ::zgen_abi::decode(
+                    &::zgen_abi::__private::param_types(&[#(#data_types),*]),
+                    &log.data,
+                )?
+                .into_iter();
+                ::std::result::Result::Ok(Self { #(#inits),* })
+            }
+        }
+    }
+}
@@ -0,0 +1,568 @@
+use crate::{
+    decode, parse_abi, read_abi_file, registry, transaction_with_abi, EthereumTypes, Log,
+    ParamType, Selector,
+};
+
+use serde::de::{self, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use std::borrow::Cow;
+use std::fmt;
+use std::path::Path;
+
+/// A contract ABI, borrowing its names and type strings from the parsed json wherever possible.
+///
+/// Use [`Abi::into_owned`] to detach it from the source buffer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Abi<'a> {
+    pub functions: Vec<Function<'a>>,
+    pub events: Vec<Event<'a>>,
+}
+
+/// An [`Abi`] owning all of its strings.
+pub type OwnedAbi = Abi<'static>;
+
+/// A `function` entry of the ABI.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Function<'a> {
+    pub name: Cow<'a, str>,
+    pub inputs: Vec<Param<'a>>,
+    pub outputs: Vec<Param<'a>>,
+    pub state_mutability: Cow<'a, str>,
+    /// NatSpec `@notice`, see [`Abi::attach_natspec`].
+    pub notice: Option<String>,
+    /// NatSpec `@dev`, see [`Abi::attach_natspec`].
+    pub details: Option<String>,
+}
+
+/// An `event` entry of the ABI.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event<'a> {
+    pub name: Cow<'a, str>,
+    pub inputs: Vec<Param<'a>>,
+    pub anonymous: bool,
+    /// NatSpec `@notice`, see [`Abi::attach_natspec`].
+    pub notice: Option<String>,
+    /// NatSpec `@dev`, see [`Abi::attach_natspec`].
+    pub details: Option<String>,
+}
+
+/// An input or output parameter of a function or event.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Param<'a> {
+    #[serde(borrow, default)]
+    pub name: Cow<'a, str>,
+    #[serde(borrow, rename = "type")]
+    pub kind: Cow<'a, str>,
+    /// Only meaningful for event parameters.
+    #[serde(default)]
+    pub indexed: bool,
+    /// Member parameters of `tuple` types.
+    #[serde(borrow, default)]
+    pub components: Vec<Param<'a>>,
+    /// NatSpec `@param` or `@return` description, see [`Abi::attach_natspec`].
+    #[serde(skip)]
+    pub doc: Option<String>,
+}
+
+// a raw entry of the ABI json array, only used to dispatch on the entry type
+#[derive(Deserialize)]
+struct Entry<'a> {
+    // the ABI specification defaults missing entry types to "function"
+    #[serde(borrow, rename = "type", default = "function_kind")]
+    kind: Cow<'a, str>,
+    #[serde(borrow, default)]
+    name: Cow<'a, str>,
+    #[serde(borrow, default)]
+    inputs: Vec<Param<'a>>,
+    #[serde(borrow, default)]
+    outputs: Vec<Param<'a>>,
+    #[serde(borrow, rename = "stateMutability", default)]
+    state_mutability: Option<Cow<'a, str>>,
+    // pre 0.4.16 compilers only emit these instead of the state mutability
+    #[serde(default)]
+    constant: bool,
+    #[serde(default)]
+    payable: bool,
+    #[serde(default)]
+    anonymous: bool,
+}
+
+fn function_kind() -> Cow<'static, str> {
+    Cow::Borrowed("function")
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for Abi<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = Vec::<Entry<'a>>::deserialize(deserializer)?;
+        let mut abi = Abi::default();
+        for entry in entries {
+            match entry.kind.as_ref() {
+                "function" => {
+                    let legacy = match (entry.constant, entry.payable) {
+                        (true, _) => "view",
+                        (false, true) => "payable",
+                        (false, false) => "nonpayable",
+                    };
+                    let state_mutability = entry.state_mutability.unwrap_or(Cow::Borrowed(legacy));
+                    abi.functions.push(Function {
+                        name: entry.name,
+                        inputs: entry.inputs,
+                        outputs: entry.outputs,
+                        state_mutability,
+                        notice: None,
+                        details: None,
+                    })
+                }
+                "event" => abi.events.push(Event {
+                    name: entry.name,
+                    inputs: entry.inputs,
+                    anonymous: entry.anonymous,
+                    notice: None,
+                    details: None,
+                }),
+                // constructor, fallback, receive and error entries are not used yet
+                _ => {}
+            }
+        }
+        Ok(abi)
+    }
+}
+
+// a plain ABI array or a solc, Hardhat or Foundry artifact holding it in its `abi` field, every
+// json parser of the crate goes through this so all load paths accept the same files
+pub(crate) struct AbiJson<'a>(pub(crate) Abi<'a>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for AbiJson<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AbiJsonVisitor<'a>(std::marker::PhantomData<Abi<'a>>);
+
+        #[derive(Deserialize)]
+        #[serde(field_identifier, rename_all = "lowercase")]
+        enum Field {
+            Abi,
+            #[serde(other)]
+            Other,
+        }
+
+        impl<'de: 'a, 'a> Visitor<'de> for AbiJsonVisitor<'a> {
+            type Value = AbiJson<'a>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an ABI array or an artifact with an `abi` field")
+            }
+
+            fn visit_seq<S: SeqAccess<'de>>(self, seq: S) -> Result<Self::Value, S::Error> {
+                Abi::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(AbiJson)
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+                let mut abi = None;
+                while let Some(field) = map.next_key()? {
+                    match field {
+                        Field::Abi if abi.is_some() => {
+                            return Err(de::Error::duplicate_field("abi"))
+                        }
+                        Field::Abi => abi = Some(map.next_value()?),
+                        Field::Other => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                abi.map(AbiJson)
+                    .ok_or_else(|| de::Error::missing_field("abi"))
+            }
+        }
+
+        deserializer.deserialize_any(AbiJsonVisitor(std::marker::PhantomData))
+    }
+}
+
+impl OwnedAbi {
+    /// Reads and parses an ABI json file, or the `abi` of a compiler artifact, once for encoding
+    /// any number of calls.
+    ///
+    /// The file is loaded like [`transaction`](crate::transaction) loads it, memory-mapped with
+    /// the `mmap` feature and parsed in place with the `simd-json` feature, and added to the
+    /// [`registry`](crate::registry) the same way. Only the names and types of the parsed ABI are
+    /// copied out of the file buffer.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let mut json = read_abi_file(path)?;
+        let abi = parse_abi(&mut json)?;
+        registry::register_loaded(path, &abi);
+        Ok(abi.into_owned())
+    }
+}
+
+impl<'a> Abi<'a> {
+    /// Parses an ABI json array, or the `abi` of a compiler artifact, borrowing from `json`.
+    // not `FromStr`, which can't borrow from the parsed string
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(json: &'a str) -> Result<Self, String> {
+        serde_json::from_str(json)
+            .map(|json: AbiJson<'a>| json.0)
+            .map_err(|e| format!("Couldn't parse json: {}", e))
+    }
+
+    /// Encodes a call of the function `name`, like [`transaction`](crate::transaction) without
+    /// reading and parsing the ABI again.
+    pub fn encode<A: AsRef<[EthereumTypes]>>(
+        &self,
+        name: &str,
+        arguments: A,
+    ) -> Result<Vec<u8>, String> {
+        transaction_with_abi(self, name, arguments)
+    }
+
+    /// Returns the first function with the given name.
+    pub fn function(&self, name: &str) -> Option<&Function<'a>> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    /// Returns the first event with the given name.
+    pub fn event(&self, name: &str) -> Option<&Event<'a>> {
+        self.events.iter().find(|e| e.name == name)
+    }
+
+    /// Appends the functions and events of `other` that aren't declared yet, identified by their
+    /// selector and topic, e.g. to combine the facet ABIs of a diamond into one for decoding.
+    pub fn merge(&mut self, other: Abi<'a>) {
+        for function in other.functions {
+            let selector = function.selector();
+            if !self.functions.iter().any(|f| f.selector() == selector) {
+                self.functions.push(function);
+            }
+        }
+        for event in other.events {
+            let topic = event.topic();
+            if !self.events.iter().any(|e| e.topic() == topic) {
+                self.events.push(event);
+            }
+        }
+    }
+
+    /// Copies every borrowed string such that the ABI no longer depends on the source json.
+    pub fn into_owned(self) -> OwnedAbi {
+        Abi {
+            functions: self
+                .functions
+                .into_iter()
+                .map(Function::into_owned)
+                .collect(),
+            events: self.events.into_iter().map(Event::into_owned).collect(),
+        }
+    }
+}
+
+impl<'a> Function<'a> {
+    /// Canonical signature of the function, e.g. `transfer(address,uint256)`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, &self.inputs)
+    }
+
+    pub fn selector(&self) -> Selector {
+        let types: Vec<Cow<str>> = self.inputs.iter().map(Param::canonical_type).collect();
+        crate::selector::function_selector(&self.name, types.iter().map(|t| t.as_ref()))
+    }
+
+    /// Decodes the arguments of a call, failing if the selector doesn't match.
+    pub fn decode_input(&self, calldata: &[u8]) -> Result<Vec<EthereumTypes>, String> {
+        match Selector::from_calldata(calldata) {
+            Some(selector) if selector == self.selector() => {
+                decode(&param_types(&self.inputs)?, &calldata[4..])
+            }
+            _ => Err(format!("Calldata is not a call of {}.", self.signature())),
+        }
+    }
+
+    pub fn decode_output(&self, data: &[u8]) -> Result<Vec<EthereumTypes>, String> {
+        decode(&param_types(&self.outputs)?, data)
+    }
+
+    pub fn into_owned(self) -> Function<'static> {
+        Function {
+            name: owned(self.name),
+            inputs: self.inputs.into_iter().map(Param::into_owned).collect(),
+            outputs: self.outputs.into_iter().map(Param::into_owned).collect(),
+            state_mutability: owned(self.state_mutability),
+            notice: self.notice,
+            details: self.details,
+        }
+    }
+}
+
+impl<'a> Event<'a> {
+    /// Canonical signature of the event, e.g. `Transfer(address,address,uint256)`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, &self.inputs)
+    }
+
+    /// The `topic0` of the event's logs, i.e. the keccak hash of its canonical signature.
+    pub fn topic(&self) -> [u8; 32] {
+        crate::selector::event_topic(&self.signature())
+    }
+
+    /// Decodes the parameters of a log in declaration order, failing if the topics don't match.
+    ///
+    /// Indexed strings, bytes, arrays and tuples are only stored as their hash, which is returned
+    /// as a `bytes32` value instead.
+    pub fn decode_log(&self, log: &Log) -> Result<Vec<EthereumTypes>, String> {
+        let indexed = self.inputs.iter().filter(|p| p.indexed).count();
+        let topics = match self.anonymous {
+            true if log.topics.len() == indexed => &log.topics[..],
+            false if log.topics.len() == 1 + indexed && log.topics[0] == self.topic() => {
+                &log.topics[1..]
+            }
+            _ => return Err(format!("Log is not a {} event.", self.name)),
+        };
+        let data_params: Vec<Param> = self.inputs.iter().filter(|p| !p.indexed).cloned().collect();
+        let mut data = decode(&param_types(&data_params)?, &log.data)?.into_iter();
+        let mut topics = topics.iter();
+
+        let mut values = Vec::with_capacity(self.inputs.len());
+        for param in self.inputs.iter() {
+            if !param.indexed {
+                values.push(data.next().unwrap());
+                continue;
+            }
+            let topic = topics.next().unwrap();
+            let kind = param_type(param)?;
+            let hashed = matches!(
+                kind,
+                ParamType::String
+                    | ParamType::Bytes
+                    | ParamType::Array(_)
+                    | ParamType::FixedArray(..)
+                    | ParamType::Tuple(_)
+            );
+            if hashed {
+                values.push(EthereumTypes::FixedBytes(topic.to_vec()));
+            } else {
+                values.extend(decode(&[kind], topic)?);
+            }
+        }
+        Ok(values)
+    }
+
+    pub fn into_owned(self) -> Event<'static> {
+        Event {
+            name: owned(self.name),
+            inputs: self.inputs.into_iter().map(Param::into_owned).collect(),
+            anonymous: self.anonymous,
+            notice: self.notice,
+            details: self.details,
+        }
+    }
+}
+
+impl<'a> Param<'a> {
+    /// Type of the parameter as it appears in canonical signatures, with tuples expanded into
+    /// their component types, e.g. `(address,uint256)[]` instead of `tuple[]`.
+    pub fn canonical_type(&self) -> Cow<'_, str> {
+        match self.kind.strip_prefix("tuple") {
+            Some(suffix) => Cow::Owned(signature("", &self.components) + suffix),
+            None => Cow::Borrowed(&self.kind),
+        }
+    }
+
+    pub fn into_owned(self) -> Param<'static> {
+        Param {
+            name: owned(self.name),
+            kind: owned(self.kind),
+            indexed: self.indexed,
+            components: self.components.into_iter().map(Param::into_owned).collect(),
+            doc: self.doc,
+        }
+    }
+}
+
+// `name(type1,type2,...)` of the given parameters
+fn signature(name: &str, params: &[Param]) -> String {
+    let mut signature = name.to_owned() + "(";
+    for (i, param) in params.iter().enumerate() {
+        if i > 0 {
+            signature.push(',');
+        }
+        signature.push_str(&param.canonical_type());
+    }
+    signature.push(')');
+    signature
+}
+
+fn param_type(param: &Param) -> Result<ParamType, String> {
+    let name = param.canonical_type();
+    ParamType::from_name(&name).ok_or_else(|| format!("Unsupported parameter type {}.", name))
+}
+
+pub(crate) fn param_types(params: &[Param]) -> Result<Vec<ParamType>, String> {
+    params.iter().map(param_type).collect()
+}
+
+#[inline]
+fn owned(s: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(s.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrowed_parse_test() {
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        let abi: Abi = serde_json::from_str(&json).unwrap();
+        assert_eq!(abi.functions.len(), 9);
+        assert_eq!(abi.events.len(), 2);
+
+        let transfer = abi.function("transfer").unwrap();
+        assert!(matches!(transfer.name, Cow::Borrowed("transfer")));
+        assert_eq!(transfer.inputs[1].kind, "uint256");
+        assert_eq!(transfer.state_mutability, "nonpayable");
+        assert!(abi.event("Transfer").unwrap().inputs[0].indexed);
+
+        assert_eq!(transfer.signature(), "transfer(address,uint256)");
+        assert_eq!(transfer.selector(), [0xa9, 0x05, 0x9c, 0xbb]);
+
+        let owned: OwnedAbi = abi.clone().into_owned();
+        drop(json);
+        assert_eq!(owned.function("transfer").unwrap().inputs.len(), 2);
+    }
+
+    #[test]
+    fn encode_test() {
+        let abi = Abi::from_file("src/rust_abi.json").unwrap();
+        let arguments = [crate::Address([0x11; 20]).into(), crate::U256::ONE.into()];
+        let calldata = abi.encode("transfer", arguments.clone()).unwrap();
+        assert_eq!(
+            Ok(calldata.clone()),
+            crate::transaction(Path::new("src/rust_abi.json"), "transfer", arguments)
+        );
+        assert_eq!(calldata[..4], [0xa9, 0x05, 0x9c, 0xbb]);
+        assert!(abi.encode("transfer", [crate::U256::ONE.into()]).is_err());
+        assert_eq!(
+            abi.encode("transfer", [crate::Address([0x11; 20]).into()]),
+            Err("Function transfer takes 2 input arguments, found 1.".to_owned())
+        );
+        assert!(abi.encode("missing", []).is_err());
+        assert!(Abi::from_file("src/missing.json").is_err());
+
+        // artifacts are unwrapped like by `include_abi!`
+        let artifact = r#"{"abi":[{"name":"ping","inputs":[]}],"bytecode":"0x"}"#;
+        let abi = Abi::from_str(artifact).unwrap();
+        assert_eq!(
+            abi.encode("ping", []),
+            Ok(crate::selector("ping()").0.to_vec())
+        );
+        assert!(Abi::from_str("{").is_err());
+        assert!(Abi::from_str(r#"{"bytecode":"0x"}"#).is_err());
+    }
+
+    #[test]
+    fn artifact_file_test() {
+        // files are parsed like embedded and in-memory json, artifacts included
+        let path = std::env::temp_dir().join("abi_artifact_file_test.json");
+        let artifact = r#"{"bytecode":"0x","abi":[{"name":"artifactPing","inputs":[]}]}"#;
+        std::fs::write(&path, artifact).unwrap();
+        let ping = crate::selector("artifactPing()").0.to_vec();
+        assert_eq!(
+            Abi::from_file(&path).unwrap().encode("artifactPing", []),
+            Ok(ping.clone())
+        );
+        assert_eq!(crate::transaction(&path, "artifactPing", []), Ok(ping));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn tuple_signature_test() {
+        let json = r#"[{
+            "type": "function",
+            "name": "multicall",
+            "inputs": [{
+                "name": "calls",
+                "type": "tuple[]",
+                "components": [
+                    { "name": "target", "type": "address" },
+                    { "name": "data", "type": "bytes" }
+                ]
+            }]
+        }]"#;
+        let abi: Abi = serde_json::from_str(json).unwrap();
+        assert_eq!(abi.functions[0].signature(), "multicall((address,bytes)[])");
+    }
+
+    #[test]
+    fn decode_input_test() {
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        let abi: Abi = serde_json::from_str(&json).unwrap();
+        let transfer = abi.function("transfer").unwrap();
+        let arguments = vec![
+            EthereumTypes::Address([0x11; 20]),
+            crate::U256::from(7_u64).into(),
+        ];
+        let mut calldata = transfer.selector().0.to_vec();
+        calldata.extend_from_slice(&crate::encode(&arguments));
+        assert_eq!(transfer.decode_input(&calldata), Ok(arguments));
+        assert!(abi
+            .function("approve")
+            .unwrap()
+            .decode_input(&calldata)
+            .is_err());
+        assert_eq!(
+            abi.function("decimals")
+                .unwrap()
+                .decode_output(&[0x12; 32][..]),
+            Err("Encoded uint8 is out of range.".to_owned())
+        );
+    }
+
+    #[test]
+    fn decode_log_test() {
+        let json = r#"[{"type":"event","name":"Named","anonymous":false,"inputs":[
+            {"name":"name","type":"string","indexed":true},
+            {"name":"owner","type":"address","indexed":true},
+            {"name":"value","type":"uint256","indexed":false}
+        ]}]"#;
+        let abi: Abi = serde_json::from_str(json).unwrap();
+        let event = &abi.events[0];
+        let mut log = Log {
+            address: crate::Address::ZERO,
+            topics: vec![
+                event.topic(),
+                [0xcc; 32],
+                crate::Address([0x11; 20]).to_word(),
+            ],
+            data: crate::U256::ONE.0.to_vec(),
+        };
+        assert_eq!(
+            event.decode_log(&log),
+            Ok(vec![
+                EthereumTypes::FixedBytes(vec![0xcc; 32]),
+                EthereumTypes::Address([0x11; 20]),
+                crate::U256::ONE.into(),
+            ])
+        );
+        log.topics.pop();
+        assert!(event.decode_log(&log).is_err());
+    }
+
+    #[test]
+    fn merge_test() {
+        let mut abi: Abi = serde_json::from_str(
+            r#"[{"name":"transfer","inputs":[{"type":"address"},{"type":"uint256"}]}]"#,
+        )
+        .unwrap();
+        let other: Abi = serde_json::from_str(
+            r#"[
+                {"name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}]},
+                {"name":"transfer","inputs":[{"type":"address"}]},
+                {"type":"event","name":"Transfer","inputs":[]}
+            ]"#,
+        )
+        .unwrap();
+        abi.merge(other);
+        assert_eq!(abi.functions.len(), 2);
+        assert_eq!(abi.functions[0].inputs[0].name, "");
+        assert_eq!(abi.events.len(), 1);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::rlp;
+use crate::storage::{allowance_slot, balance_slot};
+use crate::{hex, parse_address, Address, U256};
+
+use serde_json::{json, Value};
+
+/// Gas charged per address of an access list, see EIP-2930.
+pub const ACCESS_LIST_ADDRESS_GAS: u64 = 2400;
+/// Gas charged per storage key of an access list, see EIP-2930.
+pub const ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1900;
+
+/// The access list of EIP-2930 and EIP-1559 transactions, pre-warming the listed accounts and
+/// storage slots.
+///
+/// ```ignore
+/// let access_list = AccessList::new()
+///     .erc20_balance(token, &owner, U256::ZERO)
+///     .erc20_allowance(token, &owner, &router, U256::ONE);
+/// ```
+///
+/// Accounts keep the order they are first added in, each of them listed once with each of its
+/// keys listed once.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessList(pub Vec<AccessListItem>);
+
+/// An account of an [`AccessList`] and its storage keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessListItem {
+    pub address: Address,
+    pub storage_keys: Vec<U256>,
+}
+
+impl AccessList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn item(&mut self, address: Address) -> &mut AccessListItem {
+        let index = match self.0.iter().position(|item| item.address == address) {
+            Some(index) => index,
+            None => {
+                self.0.push(AccessListItem {
+                    address,
+                    storage_keys: Vec::new(),
+                });
+                self.0.len() - 1
+            }
+        };
+        &mut self.0[index]
+    }
+
+    /// Adds an account without storage keys, e.g. a contract that is only called.
+    pub fn address(mut self, address: Address) -> Self {
+        self.item(address);
+        self
+    }
+
+    /// Adds a storage slot, e.g. one computed with the [`storage`](crate::storage) helpers.
+    pub fn slot(self, address: Address, slot: U256) -> Self {
+        self.slots(address, [slot])
+    }
+
+    pub fn slots<I: IntoIterator<Item = U256>>(mut self, address: Address, slots: I) -> Self {
+        let item = self.item(address);
+        for slot in slots {
+            if !item.storage_keys.contains(&slot) {
+                item.storage_keys.push(slot);
+            }
+        }
+        self
+    }
+
+    /// Adds the `balanceOf[owner]` slot of a token keeping its balances at `slot`.
+    pub fn erc20_balance(self, token: Address, owner: &Address, slot: U256) -> Self {
+        self.slot(token, balance_slot(owner, slot))
+    }
+
+    /// Adds the `allowance[owner][spender]` slot of a token keeping its allowances at `slot`.
+    pub fn erc20_allowance(
+        self,
+        token: Address,
+        owner: &Address,
+        spender: &Address,
+        slot: U256,
+    ) -> Self {
+        self.slot(token, allowance_slot(owner, spender, slot))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Intrinsic gas the access list adds to a transaction.
+    pub fn gas(&self) -> u64 {
+        self.0
+            .iter()
+            .map(|item| {
+                ACCESS_LIST_ADDRESS_GAS
+                    + ACCESS_LIST_STORAGE_KEY_GAS * item.storage_keys.len() as u64
+            })
+            .sum()
+    }
+
+    /// The JSON-RPC representation, the `accessList` of transactions and of the result of
+    /// `eth_createAccessList`.
+    pub fn to_json(&self) -> Value {
+        let items: Vec<Value> = self
+            .0
+            .iter()
+            .map(|item| {
+                let keys: Vec<String> = item
+                    .storage_keys
+                    .iter()
+                    .map(|key| hex::encode_prefixed(key.0))
+                    .collect();
+                json!({"address": hex::encode_prefixed(item.address), "storageKeys": keys})
+            })
+            .collect();
+        Value::Array(items)
+    }
+
+    /// Parses the JSON-RPC representation, e.g. the `accessList` returned by
+    /// `eth_createAccessList`.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let items = value
+            .as_array()
+            .ok_or_else(|| "An access list is a json array.".to_owned())?;
+        items.iter().try_fold(Self::new(), |list, item| {
+            let address = item
+                .get("address")
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("Access list item without an address: {}", item))?;
+            let keys = match item.get("storageKeys") {
+                Some(Value::Array(keys)) => keys
+                    .iter()
+                    .map(|key| {
+                        key.as_str()
+                            .ok_or_else(|| format!("Invalid storage key {}.", key))
+                            .and_then(|key| {
+                                hex::decode_to_array(key)
+                                    .map(U256)
+                                    .map_err(|e| format!("Invalid storage key {}: {}", key, e))
+                            })
+                    })
+                    .collect::<Result<Vec<_>, String>>()?,
+                None => Vec::new(),
+                Some(keys) => return Err(format!("Invalid storage keys {}.", keys)),
+            };
+            Ok(list.slots(parse_address(address)?, keys))
+        })
+    }
+
+    /// The RLP encoding of the access list, as signed in typed transactions.
+    pub fn rlp_encode(&self) -> Vec<u8> {
+        let items: Vec<u8> = self
+            .0
+            .iter()
+            .flat_map(|item| {
+                let keys: Vec<u8> = item
+                    .storage_keys
+                    .iter()
+                    .flat_map(|key| rlp::string(&key.0))
+                    .collect();
+                let mut fields = rlp::string(item.address.as_bytes());
+                fields.extend(rlp::list(&keys));
+                rlp::list(&fields)
+            })
+            .collect();
+        rlp::list(&items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_test() {
+        let token = Address([0xaa; 20]);
+        let (owner, router) = (Address([0x11; 20]), Address([0x22; 20]));
+        let access_list = AccessList::new()
+            .address(router)
+            .erc20_balance(token, &owner, U256::ZERO)
+            .erc20_allowance(token, &owner, &router, U256::ONE)
+            .erc20_balance(token, &owner, U256::ZERO);
+        assert_eq!(access_list.0.len(), 2);
+        assert_eq!(access_list.0[0].address, router);
+        assert_eq!(
+            access_list.0[1].storage_keys,
+            [
+                balance_slot(&owner, U256::ZERO),
+                allowance_slot(&owner, &router, U256::ONE)
+            ]
+        );
+        assert_eq!(access_list.gas(), 2 * 2400 + 2 * 1900);
+    }
+
+    #[test]
+    fn json_test() {
+        let json = json!([{
+            "address": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "storageKeys": ["0x0000000000000000000000000000000000000000000000000000000000000001"]
+        }]);
+        let access_list = AccessList::from_json(&json).unwrap();
+        assert_eq!(
+            access_list,
+            AccessList::new().slot(Address([0xaa; 20]), U256::ONE)
+        );
+        assert_eq!(access_list.to_json(), json);
+
+        assert!(AccessList::from_json(&json!({})).is_err());
+        assert!(AccessList::from_json(&json!([{"storageKeys": []}])).is_err());
+        assert!(AccessList::from_json(&json!([{
+            "address": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "storageKeys": ["0x01"]
+        }]))
+        .is_err());
+    }
+
+    #[test]
+    fn rlp_encode_test() {
+        assert_eq!(AccessList::new().rlp_encode(), [0xc0]);
+        let access_list = AccessList::new().address(Address([0xaa; 20]));
+        let mut expected = vec![0xd7, 0xd6, 0x94];
+        expected.extend_from_slice(&[0xaa; 20]);
+        expected.push(0xc0);
+        assert_eq!(access_list.rlp_encode(), expected);
+
+        // a key makes the list longer than 55 bytes, which takes a long list header
+        let encoded = access_list
+            .slot(Address([0xaa; 20]), U256::ONE)
+            .rlp_encode();
+        assert_eq!(&encoded[..3], &[0xf8, 0x38, 0xf7]);
+        assert_eq!(encoded.len(), 2 + 56);
+    }
+}
@@ -0,0 +1,361 @@
+use crate::hex;
+use crate::{ChainId, EthereumTypes, U256};
+
+use sha3::{Digest, Keccak256};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A 20 byte account or contract address.
+///
+/// Displayed and parsed with the EIP-55 checksum, see [`parse_address`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address(pub [u8; 20]);
+
+impl Address {
+    pub const ZERO: Address = Address([0; 20]);
+
+    #[inline]
+    pub const fn new(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    /// The address left padded to a 32 byte word, as it is ABI encoded and used as a mapping key.
+    #[inline]
+    pub fn to_word(&self) -> [u8; 32] {
+        let mut word = [0_u8; 32];
+        word[12..].copy_from_slice(&self.0);
+        word
+    }
+
+    /// The address of an ABI encoded word, or `None` if the 12 padding bytes aren't zero.
+    pub fn from_word(word: &[u8; 32]) -> Option<Self> {
+        if word[..12].iter().any(|b| *b != 0) {
+            return None;
+        }
+        let mut address = [0_u8; 20];
+        address.copy_from_slice(&word[12..]);
+        Some(Self(address))
+    }
+}
+
+impl From<[u8; 20]> for Address {
+    fn from(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Address> for [u8; 20] {
+    fn from(address: Address) -> Self {
+        address.0
+    }
+}
+
+impl From<Address> for EthereumTypes {
+    fn from(address: Address) -> Self {
+        EthereumTypes::Address(address.0)
+    }
+}
+
+impl AsRef<[u8]> for Address {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl FromStr for Address {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_address(s)
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&to_checksum(self))
+    }
+}
+
+/// Lowercase `0x` prefixed hex, the checksum would hash the address on every log.
+#[cfg(feature = "defmt")]
+impl defmt::Format for Address {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let mut hex = [0_u8; 42];
+        defmt::write!(f, "{=str}", hex::encode_prefixed_into(&self.0, &mut hex))
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Address({})", to_checksum(self))
+    }
+}
+
+/// Parses a 20 byte address from hex, with or without the `0x` prefix.
+///
+/// Mixed-case input has to carry a valid EIP-55 checksum, all lowercase or all uppercase input is
+/// accepted as is.
+pub fn parse_address(s: &str) -> Result<Address, String> {
+    let address: [u8; 20] =
+        hex::decode_to_array(s).map_err(|e| format!("Invalid address {}: {}", s, e))?;
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    let mixed_case = digits.chars().any(|c| c.is_ascii_lowercase())
+        && digits.chars().any(|c| c.is_ascii_uppercase());
+    if mixed_case && to_checksum(&Address(address))[2..] != *digits {
+        return Err(format!("Invalid address {}: wrong EIP-55 checksum.", s));
+    }
+    Ok(Address(address))
+}
+
+/// Formats the address as `0x` prefixed hex with the EIP-55 mixed-case checksum.
+pub fn to_checksum(address: &Address) -> String {
+    let lowercase = hex::encode(address.0);
+    let hash = Keccak256::digest(lowercase.as_bytes());
+    let mut checksummed = String::with_capacity(42);
+    checksummed.push_str("0x");
+    for (i, c) in lowercase.chars().enumerate() {
+        // a letter is uppercased if the corresponding nibble of the hash is at least 8
+        let nibble = if i % 2 == 0 {
+            hash[i / 2] >> 4
+        } else {
+            hash[i / 2] & 0x0f
+        };
+        checksummed.push(if nibble >= 8 {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        });
+    }
+    checksummed
+}
+
+/// Parses an EIP-3770 chain-specific address like `eth:0x30E7…`, the short name has to belong
+/// to a well-known [`ChainId`].
+pub fn parse_prefixed_address(s: &str) -> Result<(ChainId, Address), String> {
+    let (short_name, address) = s
+        .split_once(':')
+        .ok_or_else(|| format!("Address {} has no chain prefix.", s))?;
+    let chain = ChainId::from_short_name(short_name)
+        .ok_or_else(|| format!("Unknown chain short name {}.", short_name))?;
+    Ok((chain, parse_address(address)?))
+}
+
+/// Formats the address as an EIP-3770 chain-specific address like `oeth:0x30E7…`.
+pub fn to_prefixed_address(chain: ChainId, address: &Address) -> Result<String, String> {
+    let short_name = chain
+        .short_name()
+        .ok_or_else(|| format!("Chain {} has no known short name.", chain))?;
+    Ok(format!("{}:{}", short_name, to_checksum(address)))
+}
+
+/// Parses a CAIP-10 account identifier of the `eip155` namespace, e.g. `eip155:1:0x30E7…`.
+pub fn parse_caip10(s: &str) -> Result<(ChainId, Address), String> {
+    let mut parts = s.splitn(3, ':');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some("eip155"), Some(reference), Some(address)) => {
+            // CAIP-2 references of the eip155 namespace are decimal chain ids without leading zeros
+            if reference.is_empty()
+                || (reference.len() > 1 && reference.starts_with('0'))
+                || !reference.bytes().all(|b| b.is_ascii_digit())
+            {
+                return Err(format!("Invalid eip155 chain reference {}.", reference));
+            }
+            let chain = reference
+                .parse::<u64>()
+                .map(ChainId)
+                .map_err(|e| format!("Invalid eip155 chain reference {}: {}", reference, e))?;
+            Ok((chain, parse_address(address)?))
+        }
+        (Some(namespace), Some(_), Some(_)) => Err(format!(
+            "Unsupported CAIP-2 namespace {}, only eip155 is supported.",
+            namespace
+        )),
+        _ => Err(format!("Invalid CAIP-10 account identifier {}.", s)),
+    }
+}
+
+/// Formats the address as a CAIP-10 account identifier like `eip155:10:0x30E7…`.
+pub fn to_caip10(chain: ChainId, address: &Address) -> String {
+    format!("eip155:{}:{}", chain.0, to_checksum(address))
+}
+
+const BASE36: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Parses a direct or basic ICAP address like `XE7338O073KYGTWWZN0F2WZ0R8PX5ZPPZS`, i.e. an IBAN
+/// of the `XE` country code holding the address as a base 36 number, validating its check digits.
+///
+/// Direct addresses have 30 digits and can only hold addresses below 2^155, basic ones have 31.
+/// Indirect ICAP addresses referring to a name registry are not supported.
+pub fn parse_icap(s: &str) -> Result<Address, String> {
+    let icap = s.to_ascii_uppercase();
+    // checked before slicing, which would panic inside of multi-byte characters
+    if !icap.bytes().all(|b| BASE36.contains(&b)) {
+        return Err(format!("Invalid ICAP address {}: unexpected character.", s));
+    }
+    let bban = match icap.strip_prefix("XE") {
+        Some(rest) if rest.len() == 32 || rest.len() == 33 => &rest[2..],
+        Some(rest) if rest.len() == 18 => {
+            return Err(format!("Indirect ICAP address {} is not supported.", s))
+        }
+        _ => return Err(format!("Invalid ICAP address {}.", s)),
+    };
+    if iban_remainder(&icap[4..], &icap[..4]) != 1 {
+        return Err(format!("Invalid ICAP address {}: wrong check digits.", s));
+    }
+
+    let mut value = U256::ZERO;
+    for b in bban.bytes() {
+        let digit = BASE36.iter().position(|d| *d == b).unwrap() as u64;
+        value = value
+            .checked_mul(U256::from(36_u64))
+            .and_then(|v| v.checked_add(U256::from(digit)))
+            .ok_or_else(|| format!("Invalid ICAP address {}: out of range.", s))?;
+    }
+    Address::from_word(&value.0).ok_or_else(|| format!("Invalid ICAP address {}: out of range.", s))
+}
+
+/// Formats the address as an ICAP address, a direct one if it fits into 30 base 36 digits and a
+/// basic one otherwise.
+pub fn to_icap(address: &Address) -> String {
+    let mut digits = Vec::with_capacity(31);
+    let mut rest = U256(address.to_word());
+    while !rest.is_zero() {
+        let (quotient, digit) = rest.div_rem_u64(36);
+        digits.push(BASE36[digit as usize]);
+        rest = quotient;
+    }
+    digits.resize(digits.len().max(30), b'0');
+    digits.reverse();
+    // only base 36 digits were pushed
+    let bban = String::from_utf8(digits).unwrap();
+    let check = 98 - iban_remainder(&bban, "XE00");
+    format!("XE{:02}{}", check, bban)
+}
+
+// ISO 7064 mod 97-10 of the IBAN rearranged to `bban ++ country ++ check`, letters counting as
+// 10 to 35
+fn iban_remainder(bban: &str, country_check: &str) -> u32 {
+    bban.bytes()
+        .chain(country_check.bytes())
+        .fold(0, |remainder, b| {
+            let digit = BASE36.iter().position(|d| *d == b).unwrap() as u32;
+            if digit < 10 {
+                (remainder * 10 + digit) % 97
+            } else {
+                (remainder * 100 + digit) % 97
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: Address = Address([
+        0x30, 0xe7, 0xd7, 0xff, 0xf8, 0x5c, 0x8d, 0x0e, 0x77, 0x51, 0x40, 0xb1, 0xad, 0x93, 0xc2,
+        0x30, 0xd5, 0x59, 0x52, 0x07,
+    ]);
+
+    #[test]
+    fn checksum_test() {
+        assert_eq!(
+            to_checksum(&ADDRESS),
+            "0x30E7d7FfF85C8d0E775140b1aD93C230D5595207"
+        );
+        assert_eq!(
+            parse_address("0x30E7d7FfF85C8d0E775140b1aD93C230D5595207"),
+            Ok(ADDRESS)
+        );
+        assert_eq!(
+            parse_address("30e7d7fff85c8d0e775140b1ad93c230d5595207"),
+            Ok(ADDRESS)
+        );
+        assert!(parse_address("0x30e7D7FfF85C8d0E775140b1aD93C230D5595207").is_err());
+        assert!(parse_address("0x30e7d7").is_err());
+        // 39 digits, not an address with a missing leading zero
+        assert!(parse_address("0x0e7d7fff85c8d0e775140b1ad93c230d5595207").is_err());
+    }
+
+    #[test]
+    fn address_type_test() {
+        assert_eq!(
+            "0x30E7d7FfF85C8d0E775140b1aD93C230D5595207".parse(),
+            Ok(ADDRESS)
+        );
+        assert_eq!(
+            ADDRESS.to_string(),
+            "0x30E7d7FfF85C8d0E775140b1aD93C230D5595207"
+        );
+        assert_eq!(Address::from_word(&ADDRESS.to_word()), Some(ADDRESS));
+        assert_eq!(Address::from_word(&[0xff; 32]), None);
+        assert!(Address::ZERO.is_zero());
+        assert_eq!(<[u8; 20]>::from(ADDRESS), ADDRESS.0);
+    }
+
+    #[test]
+    fn prefixed_address_test() {
+        let prefixed = "oeth:0x30E7d7FfF85C8d0E775140b1aD93C230D5595207";
+        assert_eq!(
+            parse_prefixed_address(prefixed),
+            Ok((ChainId::OPTIMISM, ADDRESS))
+        );
+        assert_eq!(
+            to_prefixed_address(ChainId::OPTIMISM, &ADDRESS).unwrap(),
+            prefixed
+        );
+        assert!(parse_prefixed_address("0x30E7d7FfF85C8d0E775140b1aD93C230D5595207").is_err());
+        assert!(parse_prefixed_address("foo:0x30E7d7FfF85C8d0E775140b1aD93C230D5595207").is_err());
+        assert!(to_prefixed_address(ChainId(31_337), &ADDRESS).is_err());
+    }
+
+    #[test]
+    fn caip10_test() {
+        let caip10 = "eip155:1:0x30E7d7FfF85C8d0E775140b1aD93C230D5595207";
+        assert_eq!(parse_caip10(caip10), Ok((ChainId::MAINNET, ADDRESS)));
+        assert_eq!(to_caip10(ChainId::MAINNET, &ADDRESS), caip10);
+        assert_eq!(
+            parse_caip10("eip155:31337:0x30e7d7fff85c8d0e775140b1ad93c230d5595207"),
+            Ok((ChainId(31_337), ADDRESS))
+        );
+
+        assert!(
+            parse_caip10("cosmos:cosmoshub-3:cosmos1t2uflqwqe0fsj0shcfkrvpukewcw40yjj6hdc0")
+                .is_err()
+        );
+        assert!(parse_caip10("eip155:01:0x30E7d7FfF85C8d0E775140b1aD93C230D5595207").is_err());
+        assert!(parse_caip10("eip155:0x30E7d7FfF85C8d0E775140b1aD93C230D5595207").is_err());
+    }
+
+    #[test]
+    fn icap_test() {
+        let direct = Address::from_str("0x00c5496aee77c1ba1f0854206a26dda82a81d6d8").unwrap();
+        assert_eq!(to_icap(&direct), "XE7338O073KYGTWWZN0F2WZ0R8PX5ZPPZS");
+        assert_eq!(parse_icap("XE7338O073KYGTWWZN0F2WZ0R8PX5ZPPZS"), Ok(direct));
+        assert_eq!(parse_icap("xe7338o073kygtwwzn0f2wz0r8px5zppzs"), Ok(direct));
+
+        // too large for a direct address
+        let basic = to_icap(&ADDRESS);
+        assert_eq!(basic.len(), 35);
+        assert_eq!(parse_icap(&basic), Ok(ADDRESS));
+        assert_eq!(parse_icap(&to_icap(&Address::ZERO)), Ok(Address::ZERO));
+
+        assert!(parse_icap("XE7438O073KYGTWWZN0F2WZ0R8PX5ZPPZS").is_err());
+        assert!(parse_icap("XE7338O073KYGTWWZN0F2WZ0R8PX5ZPPZ").is_err());
+        assert!(parse_icap("XE81ETHXREGGAVOFYORK").is_err());
+        assert!(parse_icap("GB82WEST12345698765432").is_err());
+        assert!(parse_icap("XE1é345678901234567890123456789012").is_err());
+        // the canonical IBAN example validates with the same check digits
+        assert_eq!(iban_remainder("WEST12345698765432", "GB82"), 1);
+    }
+}
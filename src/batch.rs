@@ -0,0 +1,306 @@
+use crate::abi::param_types;
+use crate::{
+    decode, hex, selector, Address, EthCall, EthereumTypes, Function, ParamType, Selector,
+};
+
+use serde_json::{json, Value};
+
+use std::convert::TryFrom;
+
+/// A JSON-RPC batch of `eth_call` requests, e.g. the `balanceOf` of many holders in one round
+/// trip.
+///
+/// Every call gets the id of its position in the batch, which maps the responses, in any order,
+/// back to the output types of the call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallBatch {
+    block: String,
+    calls: Vec<BatchedCall>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct BatchedCall {
+    to: Address,
+    calldata: Vec<u8>,
+    outputs: Vec<ParamType>,
+}
+
+impl CallBatch {
+    /// A batch of calls at `block`, a tag like `latest` or a hex block number.
+    pub fn new(block: &str) -> Self {
+        Self {
+            block: block.to_owned(),
+            calls: Vec::new(),
+        }
+    }
+
+    /// Adds a call returning the given output types, returns its id.
+    pub fn call(&mut self, to: Address, calldata: Vec<u8>, outputs: Vec<ParamType>) -> usize {
+        self.calls.push(BatchedCall {
+            to,
+            calldata,
+            outputs,
+        });
+        self.calls.len() - 1
+    }
+
+    /// Adds a typed call, e.g. one declared with `sol!`.
+    pub fn eth_call<C: EthCall>(&mut self, to: Address, call: C) -> usize {
+        self.call(to, call.encode(), C::output_types())
+    }
+
+    /// Adds a call of an ABI function, fails if an output type is not supported.
+    pub fn function_call(
+        &mut self,
+        to: Address,
+        function: &Function,
+        calldata: Vec<u8>,
+    ) -> Result<usize, String> {
+        let outputs = param_types(&function.outputs)?;
+        Ok(self.call(to, calldata, outputs))
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// The batch request, an array of `eth_call` requests with the call ids.
+    pub fn to_json(&self) -> Value {
+        let requests: Vec<Value> = self
+            .calls
+            .iter()
+            .enumerate()
+            .map(|(id, call)| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": "eth_call",
+                    "params": [
+                        {
+                            "to": hex::encode_prefixed(call.to),
+                            "data": hex::encode_prefixed(&call.calldata),
+                        },
+                        self.block,
+                    ],
+                })
+            })
+            .collect();
+        Value::Array(requests)
+    }
+
+    /// Decoded outputs of every call in id order.
+    ///
+    /// Fails if the responses are not a batch response, a call fails on its own if it has no
+    /// response, an error response like a revert, or a result that doesn't decode.
+    pub fn decode_responses(
+        &self,
+        responses: &Value,
+    ) -> Result<Vec<Result<Vec<EthereumTypes>, String>>, String> {
+        let responses = responses
+            .as_array()
+            .ok_or_else(|| "Batch response is not an array.".to_owned())?;
+        let mut results = vec![Err("Missing response.".to_owned()); self.calls.len()];
+        for response in responses {
+            let id = response
+                .get("id")
+                .and_then(Value::as_u64)
+                .and_then(|id| usize::try_from(id).ok())
+                .filter(|id| *id < self.calls.len())
+                .ok_or_else(|| format!("Response with an unknown id: {}", response))?;
+            results[id] = self.decode_response(id, response);
+        }
+        Ok(results)
+    }
+
+    fn decode_response(&self, id: usize, response: &Value) -> Result<Vec<EthereumTypes>, String> {
+        if let Some(error) = response.get("error") {
+            let message = error.get("message").and_then(Value::as_str);
+            return Err(format!(
+                "Call failed: {}",
+                message.unwrap_or("unknown error")
+            ));
+        }
+        let result = response
+            .get("result")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "Response has no result.".to_owned())?;
+        let data = hex::decode(result).map_err(|e| e.to_string())?;
+        decode(&self.calls[id].outputs, &data)
+    }
+}
+
+/// The ERC-165 interface id of a set of functions, the xor of their selectors.
+pub fn interface_id(signatures: &[&str]) -> Selector {
+    let mut id = [0_u8; 4];
+    for signature in signatures {
+        for (byte, selector_byte) in id.iter_mut().zip(selector(signature).0) {
+            *byte ^= selector_byte;
+        }
+    }
+    Selector(id)
+}
+
+/// A batch of ERC-165 `supportsInterface(bytes4)` calls probing which of the interfaces a contract
+/// implements.
+///
+/// ```ignore
+/// let probe = InterfaceProbe::new(token, &[ERC721_ID, ERC2981_ID], "latest");
+/// let supported = probe.decode_responses(&send(probe.to_json()))?;
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterfaceProbe {
+    batch: CallBatch,
+    interface_ids: Vec<Selector>,
+}
+
+impl InterfaceProbe {
+    pub fn new(contract: Address, interface_ids: &[Selector], block: &str) -> Self {
+        let mut batch = CallBatch::new(block);
+        for id in interface_ids {
+            let mut calldata = selector("supportsInterface(bytes4)").0.to_vec();
+            calldata.extend_from_slice(&id.0);
+            calldata.resize(36, 0);
+            batch.call(contract, calldata, vec![ParamType::Bool]);
+        }
+        Self {
+            batch,
+            interface_ids: interface_ids.to_vec(),
+        }
+    }
+
+    /// The batch request of `eth_call`s, one per interface id.
+    pub fn to_json(&self) -> Value {
+        self.batch.to_json()
+    }
+
+    /// Whether the contract supports each of the interfaces, in the order of the ids.
+    ///
+    /// As ERC-165 demands, a call that reverts or doesn't return a boolean means the interface is
+    /// not supported, e.g. for contracts not implementing `supportsInterface` at all.
+    pub fn decode_responses(&self, responses: &Value) -> Result<Vec<(Selector, bool)>, String> {
+        let results = self.batch.decode_responses(responses)?;
+        Ok(self
+            .interface_ids
+            .iter()
+            .zip(results)
+            .map(|(id, result)| {
+                let supported = matches!(result.as_deref(), Ok([EthereumTypes::Bool(true)]));
+                (*id, supported)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{selector, U256};
+
+    fn balance_of(holder: u8) -> Vec<u8> {
+        let mut calldata = selector("balanceOf(address)").0.to_vec();
+        calldata.extend_from_slice(&Address([holder; 20]).to_word());
+        calldata
+    }
+
+    #[test]
+    fn to_json_test() {
+        let token = Address([0xaa; 20]);
+        let mut batch = CallBatch::new("latest");
+        assert!(batch.is_empty());
+        assert_eq!(
+            batch.call(token, balance_of(0x11), vec![ParamType::U256]),
+            0
+        );
+        assert_eq!(
+            batch.call(token, balance_of(0x22), vec![ParamType::U256]),
+            1
+        );
+
+        let requests = batch.to_json();
+        assert_eq!(requests.as_array().unwrap().len(), 2);
+        assert_eq!(
+            requests[1],
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "eth_call",
+                "params": [
+                    {
+                        "to": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                        "data": "0x70a082310000000000000000000000002222222222222222222222222222222222222222",
+                    },
+                    "latest",
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn decode_responses_test() {
+        let mut batch = CallBatch::new("0x10");
+        for holder in 0..4 {
+            batch.call(Address::ZERO, balance_of(holder), vec![ParamType::U256]);
+        }
+        // responses may come in any order
+        let responses = json!([
+            {"jsonrpc": "2.0", "id": 2, "result": hex::encode_prefixed(U256::from(5_u64).0)},
+            {"jsonrpc": "2.0", "id": 0, "result": hex::encode_prefixed(U256::ONE.0)},
+            {"jsonrpc": "2.0", "id": 1, "error": {"code": 3, "message": "execution reverted"}},
+        ]);
+        let results = batch.decode_responses(&responses).unwrap();
+        assert_eq!(results[0], Ok(vec![U256::ONE.into()]));
+        assert_eq!(
+            results[1],
+            Err("Call failed: execution reverted".to_owned())
+        );
+        assert_eq!(results[2], Ok(vec![U256::from(5_u64).into()]));
+        assert_eq!(results[3], Err("Missing response.".to_owned()));
+
+        assert!(batch.decode_responses(&json!({"id": 0})).is_err());
+        assert!(batch
+            .decode_responses(&json!([{"id": 9, "result": "0x"}]))
+            .is_err());
+        let truncated = json!([{"id": 0, "result": "0x01"}]);
+        assert!(batch.decode_responses(&truncated).unwrap()[0].is_err());
+        assert_eq!(batch.len(), 4);
+    }
+
+    #[test]
+    fn interface_probe_test() {
+        assert_eq!(
+            interface_id(&["supportsInterface(bytes4)"]),
+            Selector([0x01, 0xff, 0xc9, 0xa7])
+        );
+        // the ERC-721 metadata extension
+        assert_eq!(
+            interface_id(&["name()", "symbol()", "tokenURI(uint256)"]),
+            Selector([0x5b, 0x5e, 0x13, 0x9f])
+        );
+
+        let ids = [
+            Selector([0x80, 0xac, 0x58, 0xcd]),
+            Selector([0x2a, 0x55, 0x20, 0x5a]),
+            Selector([0xff; 4]),
+        ];
+        let probe = InterfaceProbe::new(Address([0xaa; 20]), &ids, "latest");
+        let requests = probe.to_json();
+        assert_eq!(
+            requests[0]["params"][0]["data"],
+            "0x01ffc9a780ac58cd00000000000000000000000000000000000000000000000000000000"
+        );
+
+        let responses = json!([
+            {"jsonrpc": "2.0", "id": 0, "result": hex::encode_prefixed(U256::ONE.0)},
+            {"jsonrpc": "2.0", "id": 1, "error": {"code": 3, "message": "execution reverted"}},
+            {"jsonrpc": "2.0", "id": 2, "result": "0x"},
+        ]);
+        assert_eq!(
+            probe.decode_responses(&responses).unwrap(),
+            [(ids[0], true), (ids[1], false), (ids[2], false)]
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! The 2048 bit bloom filter of block headers and receipts (`logsBloom`).
+//!
+//! Every log adds its address and each of its topics to the bloom, so a receipt or block can only
+//! contain a log of an event if the bloom contains the event's topic.
+
+use crate::{Address, Log};
+
+use sha3::{Digest, Keccak256};
+
+/// Size of a logs bloom in bytes.
+pub const BLOOM_SIZE: usize = 256;
+
+/// A `logsBloom` filter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bloom(pub [u8; BLOOM_SIZE]);
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self([0_u8; BLOOM_SIZE])
+    }
+}
+
+impl From<[u8; BLOOM_SIZE]> for Bloom {
+    fn from(bytes: [u8; BLOOM_SIZE]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Bloom {
+    /// Parses a bloom from 256 bytes, e.g. the decoded `logsBloom` field of a receipt.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != BLOOM_SIZE {
+            return Err(format!(
+                "Logs bloom has to be {} bytes long, found {}.",
+                BLOOM_SIZE,
+                bytes.len()
+            ));
+        }
+        let mut bloom = [0_u8; BLOOM_SIZE];
+        bloom.copy_from_slice(bytes);
+        Ok(Self(bloom))
+    }
+
+    /// Adds the bits of an arbitrary input (an address or a topic) to the bloom.
+    pub fn accrue(&mut self, input: &[u8]) {
+        for (byte, mask) in bloom_bits(input).iter() {
+            self.0[*byte] |= mask;
+        }
+    }
+
+    pub fn accrue_address(&mut self, address: &Address) {
+        self.accrue(address.as_bytes());
+    }
+
+    pub fn accrue_topic(&mut self, topic: &[u8; 32]) {
+        self.accrue(topic);
+    }
+
+    /// Adds the address and every topic of the log, its data isn't part of the bloom.
+    pub fn accrue_log(&mut self, log: &Log) {
+        self.accrue_address(&log.address);
+        for topic in log.topics.iter() {
+            self.accrue_topic(topic);
+        }
+    }
+
+    /// Adds every bit of another bloom, e.g. to combine receipt blooms into a block bloom.
+    pub fn accrue_bloom(&mut self, other: &Bloom) {
+        for (byte, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *byte |= other;
+        }
+    }
+
+    /// Bloom filters have false positives, a `true` result only means the input may have been added.
+    pub fn may_contain(&self, input: &[u8]) -> bool {
+        bloom_bits(input)
+            .iter()
+            .all(|(byte, mask)| self.0[*byte] & mask == *mask)
+    }
+
+    pub fn may_contain_address(&self, address: &Address) -> bool {
+        self.may_contain(address.as_bytes())
+    }
+
+    pub fn may_contain_topic(&self, topic: &[u8; 32]) -> bool {
+        self.may_contain(topic)
+    }
+
+    /// Whether a log with the given `topic0`, optionally emitted by the given address, may be
+    /// covered by the bloom. A `false` result means the receipt or block can be skipped.
+    pub fn may_contain_event(&self, address: Option<&Address>, topic0: &[u8; 32]) -> bool {
+        address.is_none_or(|a| self.may_contain_address(a)) && self.may_contain_topic(topic0)
+    }
+
+    /// Whether the address and every topic of the log may be covered by the bloom, a `false`
+    /// result means the log can't be part of the receipt or block.
+    pub fn may_contain_log(&self, log: &Log) -> bool {
+        self.may_contain_address(&log.address)
+            && log.topics.iter().all(|t| self.may_contain_topic(t))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|b| *b == 0)
+    }
+}
+
+/// Bloom contribution of a single address or topic.
+pub fn bloom_of(input: &[u8]) -> Bloom {
+    let mut bloom = Bloom::default();
+    bloom.accrue(input);
+    bloom
+}
+
+/// The `logsBloom` of a receipt emitting the logs, or of a block if the logs of all of its
+/// receipts are passed.
+pub fn logs_bloom<'l, I: IntoIterator<Item = &'l Log>>(logs: I) -> Bloom {
+    let mut bloom = Bloom::default();
+    for log in logs {
+        bloom.accrue_log(log);
+    }
+    bloom
+}
+
+// the low 11 bits of the first three 16 bit words of the input's hash select the bits to set,
+// counting from the end of the bloom
+fn bloom_bits(input: &[u8]) -> [(usize, u8); 3] {
+    let hash = Keccak256::digest(input);
+    let mut bits = [(0, 0); 3];
+    for (i, bit) in bits.iter_mut().enumerate() {
+        let index = ((usize::from(hash[2 * i]) << 8) | usize::from(hash[2 * i + 1])) & 0x7ff;
+        *bit = (BLOOM_SIZE - 1 - index / 8, 1 << (index % 8));
+    }
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSFER_TOPIC: [u8; 32] = [
+        0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d,
+        0xaa, 0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23,
+        0xb3, 0xef,
+    ];
+
+    #[test]
+    fn accrue_test() {
+        let address = Address([0x30; 20]);
+        let mut bloom = Bloom::default();
+        assert!(bloom.is_empty());
+        assert!(!bloom.may_contain_event(None, &TRANSFER_TOPIC));
+
+        bloom.accrue_address(&address);
+        bloom.accrue_topic(&TRANSFER_TOPIC);
+        assert!(bloom.may_contain_event(Some(&address), &TRANSFER_TOPIC));
+        assert!(bloom.may_contain_event(None, &TRANSFER_TOPIC));
+        assert!(bloom.0.iter().map(|b| b.count_ones()).sum::<u32>() <= 6);
+
+        let mut combined = bloom_of(&[0x81; 20]);
+        combined.accrue_bloom(&bloom);
+        assert!(combined.may_contain_address(&Address([0x81; 20])));
+        assert!(combined.may_contain_topic(&TRANSFER_TOPIC));
+    }
+
+    #[test]
+    fn logs_bloom_test() {
+        let transfer = Log {
+            address: Address([0x30; 20]),
+            topics: vec![TRANSFER_TOPIC, Address([0x11; 20]).to_word()],
+            data: vec![0xff; 32],
+        };
+        let anonymous = Log {
+            address: Address([0x40; 20]),
+            topics: vec![],
+            data: vec![],
+        };
+        let bloom = logs_bloom(&[transfer.clone(), anonymous.clone()]);
+        let mut expected = bloom_of(&[0x30; 20]);
+        expected.accrue_topic(&TRANSFER_TOPIC);
+        expected.accrue(&Address([0x11; 20]).to_word());
+        expected.accrue_address(&Address([0x40; 20]));
+        assert_eq!(bloom, expected);
+        assert!(bloom.may_contain_log(&transfer) && bloom.may_contain_log(&anonymous));
+
+        // the data doesn't contribute
+        let mut other_data = transfer.clone();
+        other_data.data.clear();
+        assert_eq!(logs_bloom(&[other_data]), logs_bloom(&[transfer]));
+        assert!(logs_bloom(&[]).is_empty());
+        assert!(!Bloom::default().may_contain_log(&anonymous));
+    }
+
+    #[test]
+    fn known_bloom_test() {
+        // the bloom of a receipt with a single log of one topic, the reference vector of the
+        // ethbloom crate
+        let log = Log {
+            address: crate::parse_address("0xef2d6d194084c2de36e0dabfce45d046b37d1106").unwrap(),
+            topics: vec![crate::hex::decode_to_array(
+                "02c69be41d0b7e40352fc85be1cd65eb03d40ef8427a0ca4596b1ead9a00e9fc",
+            )
+            .unwrap()],
+            data: vec![],
+        };
+        let expected = concat!(
+            "0000000000000000000000000000000000000000100000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000202000000000000000000000000000000000000000000000800000000",
+            "1000000000000000000000000000000000000000000000000000001000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+        );
+        assert_eq!(
+            logs_bloom(&[log]),
+            Bloom(crate::hex::decode_to_array(expected).unwrap())
+        );
+    }
+
+    #[test]
+    fn from_slice_test() {
+        assert!(Bloom::from_slice(&[0_u8; 255]).is_err());
+        assert!(Bloom::from_slice(&[0_u8; 256]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,314 @@
+use crate::types::fits_int;
+
+use std::fmt;
+
+/// A single word type, the element type of the arrays [`decode_bounded`] supports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WordKind {
+    Address,
+    /// `uintN`, with `N` a multiple of 8 up to 256
+    Uint(usize),
+    /// `intN`, with `N` a multiple of 8 up to 256
+    Int(usize),
+    Bool,
+    /// `bytesN` with `1 <= N <= 32`
+    FixedBytes(usize),
+}
+
+/// The types [`decode_bounded`] decodes, which unlike [`ParamType`](crate::ParamType) can be
+/// declared as constants without an allocator.
+///
+/// ```ignore
+/// const TRANSFER: [BoundedKind; 2] = [
+///     BoundedKind::Word(WordKind::Address),
+///     BoundedKind::Word(WordKind::Uint(256)),
+/// ];
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BoundedKind {
+    Word(WordKind),
+    Bytes,
+    String,
+    /// `T[]` of a single word type
+    Array(WordKind),
+    /// `T[N]` of a single word type
+    FixedArray(WordKind, usize),
+}
+
+impl BoundedKind {
+    fn is_dynamic(&self) -> bool {
+        !matches!(self, Self::Word(_) | Self::FixedArray(..))
+    }
+
+    fn head_size(&self) -> usize {
+        match self {
+            Self::FixedArray(_, size) => 32 * size,
+            _ => 32,
+        }
+    }
+}
+
+/// A value decoded by [`decode_bounded`], borrowing dynamic content from the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BoundedValue<'d> {
+    /// The encoded word of a single word type, already checked to be in range.
+    Word([u8; 32]),
+    Bytes(&'d [u8]),
+    String(&'d str),
+    /// The consecutive encoded elements of an array, see [`words`](Self::words).
+    Words(&'d [u8]),
+}
+
+impl Default for BoundedValue<'_> {
+    fn default() -> Self {
+        Self::Word([0; 32])
+    }
+}
+
+impl<'d> BoundedValue<'d> {
+    /// The elements of an array, or the word of a single word value.
+    pub fn words(&self) -> impl Iterator<Item = &[u8]> {
+        let words: &[u8] = match self {
+            Self::Word(word) => word,
+            Self::Words(words) => words,
+            Self::Bytes(_) | Self::String(_) => &[],
+        };
+        words.chunks_exact(32)
+    }
+}
+
+/// Reasons [`decode_bounded`] rejects an input, without allocating a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BoundedDecodeError {
+    /// More values than the output has room for.
+    Capacity { needed: usize, capacity: usize },
+    /// Bytes, a string or an array longer than the limit.
+    TooLong { len: usize, max_len: usize },
+    /// Lengths or offsets pointing past the end of the data.
+    Truncated,
+    /// The value at the index is out of the range of its type.
+    InvalidWord(usize),
+    /// The string at the index is not valid UTF-8.
+    InvalidUtf8(usize),
+}
+
+impl fmt::Display for BoundedDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Capacity { needed, capacity } => write!(
+                f,
+                "Decoding {} values into room for {} values.",
+                needed, capacity
+            ),
+            Self::TooLong { len, max_len } => write!(
+                f,
+                "Encoded length {} exceeds the maximum of {}.",
+                len, max_len
+            ),
+            Self::Truncated => f.write_str("Encoded data is too short."),
+            Self::InvalidWord(index) => write!(f, "Encoded value {} is out of range.", index),
+            Self::InvalidUtf8(index) => write!(f, "Encoded string {} is not valid UTF-8.", index),
+        }
+    }
+}
+
+impl std::error::Error for BoundedDecodeError {}
+
+/// Decodes ABI encoded values into `out` without allocating, for firmware and other targets
+/// without a heap.
+///
+/// Bytes, strings and arrays borrow from `data` and are rejected if longer than `max_len` bytes
+/// or elements, inputs with more values than `out` holds are rejected as well. Values are checked
+/// like [`decode`](crate::decode) does, returns the decoded prefix of `out`.
+pub fn decode_bounded<'d, 'o>(
+    kinds: &[BoundedKind],
+    data: &'d [u8],
+    max_len: usize,
+    out: &'o mut [BoundedValue<'d>],
+) -> Result<&'o [BoundedValue<'d>], BoundedDecodeError> {
+    if kinds.len() > out.len() {
+        return Err(BoundedDecodeError::Capacity {
+            needed: kinds.len(),
+            capacity: out.len(),
+        });
+    }
+    let mut head = 0;
+    for (index, (kind, value)) in kinds.iter().zip(out.iter_mut()).enumerate() {
+        let at = match kind.is_dynamic() {
+            true => read_usize(data, head)?,
+            false => head,
+        };
+        let tail = data.get(at..).ok_or(BoundedDecodeError::Truncated)?;
+        *value = decode_value(kind, tail, max_len, index)?;
+        head += kind.head_size();
+    }
+    Ok(&out[..kinds.len()])
+}
+
+fn decode_value<'d>(
+    kind: &BoundedKind,
+    data: &'d [u8],
+    max_len: usize,
+    index: usize,
+) -> Result<BoundedValue<'d>, BoundedDecodeError> {
+    match kind {
+        BoundedKind::Word(word_kind) => {
+            let word = read_word(data, 0)?;
+            if !is_valid(word_kind, word) {
+                return Err(BoundedDecodeError::InvalidWord(index));
+            }
+            let mut value = [0_u8; 32];
+            value.copy_from_slice(word);
+            Ok(BoundedValue::Word(value))
+        }
+        BoundedKind::Bytes => read_bytes(data, max_len).map(BoundedValue::Bytes),
+        BoundedKind::String => {
+            let bytes = read_bytes(data, max_len)?;
+            std::str::from_utf8(bytes)
+                .map(BoundedValue::String)
+                .map_err(|_| BoundedDecodeError::InvalidUtf8(index))
+        }
+        BoundedKind::Array(element) => {
+            let len = read_usize(data, 0)?;
+            let words = read_words(&data[32..], element, len, max_len, index)?;
+            Ok(BoundedValue::Words(words))
+        }
+        BoundedKind::FixedArray(element, size) => {
+            read_words(data, element, *size, max_len, index).map(BoundedValue::Words)
+        }
+    }
+}
+
+fn read_words<'d>(
+    data: &'d [u8],
+    element: &WordKind,
+    len: usize,
+    max_len: usize,
+    index: usize,
+) -> Result<&'d [u8], BoundedDecodeError> {
+    if len > max_len {
+        return Err(BoundedDecodeError::TooLong { len, max_len });
+    }
+    let words = len
+        .checked_mul(32)
+        .and_then(|size| data.get(..size))
+        .ok_or(BoundedDecodeError::Truncated)?;
+    if !words.chunks_exact(32).all(|word| is_valid(element, word)) {
+        return Err(BoundedDecodeError::InvalidWord(index));
+    }
+    Ok(words)
+}
+
+fn read_bytes(data: &[u8], max_len: usize) -> Result<&[u8], BoundedDecodeError> {
+    let len = read_usize(data, 0)?;
+    if len > max_len {
+        return Err(BoundedDecodeError::TooLong { len, max_len });
+    }
+    data.get(32..)
+        .and_then(|content| content.get(..len))
+        .ok_or(BoundedDecodeError::Truncated)
+}
+
+fn read_word(data: &[u8], at: usize) -> Result<&[u8], BoundedDecodeError> {
+    at.checked_add(32)
+        .and_then(|end| data.get(at..end))
+        .ok_or(BoundedDecodeError::Truncated)
+}
+
+fn read_usize(data: &[u8], at: usize) -> Result<usize, BoundedDecodeError> {
+    let word = read_word(data, at)?;
+    if word[..28].iter().any(|b| *b != 0) {
+        return Err(BoundedDecodeError::Truncated);
+    }
+    Ok(u32::from_be_bytes([word[28], word[29], word[30], word[31]]) as usize)
+}
+
+fn is_valid(kind: &WordKind, word: &[u8]) -> bool {
+    let zero = |bytes: &[u8]| bytes.iter().all(|b| *b == 0);
+    match *kind {
+        WordKind::Address => zero(&word[..12]),
+        WordKind::Uint(bits) if bits % 8 == 0 && (8..=256).contains(&bits) => {
+            zero(&word[..32 - bits / 8])
+        }
+        WordKind::Int(bits) if bits % 8 == 0 && (8..=256).contains(&bits) => {
+            let mut value = [0_u8; 32];
+            value.copy_from_slice(word);
+            fits_int(&value, bits)
+        }
+        WordKind::Bool => zero(&word[..31]) && word[31] <= 1,
+        WordKind::FixedBytes(size) if (1..=32).contains(&size) => zero(&word[size..]),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode, Address, EthereumTypes, U256};
+
+    const KINDS: [BoundedKind; 4] = [
+        BoundedKind::Word(WordKind::Address),
+        BoundedKind::String,
+        BoundedKind::Array(WordKind::Uint(8)),
+        BoundedKind::FixedArray(WordKind::Bool, 2),
+    ];
+
+    fn data() -> Vec<u8> {
+        encode(&[
+            Address([0x11; 20]).into(),
+            EthereumTypes::String("gm".to_owned()),
+            EthereumTypes::Array(vec![U256::ONE.into(), U256::from(255_u64).into()]),
+            EthereumTypes::FixedArray(vec![EthereumTypes::Bool(true), EthereumTypes::Bool(false)]),
+        ])
+    }
+
+    #[test]
+    fn decode_bounded_test() {
+        let data = data();
+        let mut out = [BoundedValue::default(); 8];
+        let values = decode_bounded(&KINDS, &data, 16, &mut out).unwrap();
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[0], BoundedValue::Word(Address([0x11; 20]).to_word()));
+        assert_eq!(values[1], BoundedValue::String("gm"));
+        let elements: Vec<u8> = values[2].words().map(|word| word[31]).collect();
+        assert_eq!(elements, [1, 255]);
+        assert_eq!(values[3].words().count(), 2);
+    }
+
+    #[test]
+    fn limits_test() {
+        let data = data();
+        let mut out = [BoundedValue::default(); 3];
+        assert_eq!(
+            decode_bounded(&KINDS, &data, 16, &mut out),
+            Err(BoundedDecodeError::Capacity {
+                needed: 4,
+                capacity: 3
+            })
+        );
+        let mut out = [BoundedValue::default(); 4];
+        assert_eq!(
+            decode_bounded(&KINDS, &data, 1, &mut out),
+            Err(BoundedDecodeError::TooLong { len: 2, max_len: 1 })
+        );
+        assert_eq!(
+            decode_bounded(&KINDS, &data[..100], 16, &mut out),
+            Err(BoundedDecodeError::Truncated)
+        );
+
+        // 511 doesn't fit the uint8 elements, just like dirty address padding
+        let mut dirty = data.clone();
+        dirty[data.len() - 2] = 1;
+        assert_eq!(
+            decode_bounded(&KINDS, &dirty, 16, &mut [BoundedValue::default(); 4]),
+            Err(BoundedDecodeError::InvalidWord(2))
+        );
+        let mut dirty = data;
+        dirty[0] = 1;
+        assert_eq!(
+            decode_bounded(&KINDS, &dirty, 16, &mut [BoundedValue::default(); 4]),
+            Err(BoundedDecodeError::InvalidWord(0))
+        );
+    }
+}
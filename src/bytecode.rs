@@ -0,0 +1,151 @@
+use crate::hex;
+use crate::selector::{selector, Selector};
+use crate::Address;
+
+use sha3::{Digest, Keccak256};
+use std::collections::HashMap;
+
+const PUSH1: u8 = 0x60;
+const PUSH4: u8 = 0x63;
+const PUSH32: u8 = 0x7f;
+
+/// Returns the distinct `PUSH4` constants of the runtime bytecode in order of appearance.
+///
+/// Solidity and Vyper dispatchers compare the calldata selector against `PUSH4` immediates, so these
+/// are candidate function selectors. Other 4 byte constants (masks, magic values) show up as well.
+pub fn push4_selectors(bytecode: &[u8]) -> Vec<Selector> {
+    let mut selectors = Vec::new();
+    let mut pc = 0;
+    while pc < bytecode.len() {
+        let opcode = bytecode[pc];
+        if (PUSH1..=PUSH32).contains(&opcode) {
+            let size = usize::from(opcode - PUSH1) + 1;
+            // a truncated immediate at the end of the code is not a complete constant
+            if opcode == PUSH4 && pc + size < bytecode.len() {
+                let mut candidate = Selector::default();
+                candidate
+                    .0
+                    .copy_from_slice(&bytecode[pc + 1..pc + 1 + size]);
+                if !selectors.contains(&candidate) {
+                    selectors.push(candidate);
+                }
+            }
+            // skip the immediate such that push data is never interpreted as an opcode
+            pc += size;
+        }
+        pc += 1;
+    }
+    selectors
+}
+
+/// Matches the candidate selectors of the runtime bytecode against a list of known canonical
+/// signatures, e.g. `transfer(address,uint256)`.
+///
+/// Every candidate is returned, paired with the signature it was resolved to, if any.
+pub fn match_selectors<'s>(
+    bytecode: &[u8],
+    signatures: &[&'s str],
+) -> Vec<(Selector, Option<&'s str>)> {
+    let known: HashMap<Selector, &str> = signatures.iter().map(|s| (selector(s), *s)).collect();
+    push4_selectors(bytecode)
+        .into_iter()
+        .map(|candidate| (candidate, known.get(&candidate).copied()))
+        .collect()
+}
+
+/// Replaces the library placeholders of unlinked bytecode, as found in the `bytecode.object` of
+/// solc and Foundry artifacts, with the deployed library addresses and decodes the result.
+///
+/// Libraries are keyed by their fully qualified name like `lib/Contract.sol:Lib`, both the
+/// `__$<hash>$__` placeholders of solc 0.5 and later and the older `__lib/Contract.sol:Lib___`
+/// ones are replaced. It's an error if any placeholder is left unlinked.
+pub fn link_bytecode<K, I>(bytecode: &str, libraries: I) -> Result<Vec<u8>, String>
+where
+    K: AsRef<str>,
+    I: IntoIterator<Item = (K, Address)>,
+{
+    let mut linked = bytecode.strip_prefix("0x").unwrap_or(bytecode).to_owned();
+    for (name, address) in libraries {
+        let name = name.as_ref();
+        let address = hex::encode(address.0);
+        let hash = Keccak256::digest(name.as_bytes());
+        let placeholder = format!("__${}$__", hex::encode(&hash[..17]));
+        linked = linked.replace(&placeholder, &address);
+
+        // legacy placeholders carry the name itself, truncated and padded to the address length
+        let truncated = name.get(..36).unwrap_or(name);
+        let legacy = format!("__{:_<38}", truncated);
+        linked = linked.replace(&legacy, &address);
+    }
+
+    if let Some(start) = linked.find("__") {
+        let placeholder = linked.get(start..start + 40).unwrap_or(&linked[start..]);
+        return Err(format!("Unlinked library placeholder {}.", placeholder));
+    }
+    hex::decode(&linked).map_err(|e| format!("Invalid bytecode: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // DUP1 PUSH4 0x70a08231 EQ PUSH2 0x0063 JUMPI
+    // DUP1 PUSH4 0xa9059cbb EQ PUSH1 0x63 JUMPI
+    // PUSH5 0x6363636363 PUSH4 0x70a08231 PUSH4 0x0102
+    const CODE: [u8; 35] = [
+        0x80, 0x63, 0x70, 0xa0, 0x82, 0x31, 0x14, 0x61, 0x00, 0x63, 0x57, 0x80, 0x63, 0xa9, 0x05,
+        0x9c, 0xbb, 0x14, 0x60, 0x63, 0x57, 0x64, 0x63, 0x63, 0x63, 0x63, 0x63, 0x63, 0x70, 0xa0,
+        0x82, 0x31, 0x63, 0x01, 0x02,
+    ];
+
+    #[test]
+    fn push4_selectors_test() {
+        assert_eq!(
+            push4_selectors(&CODE),
+            vec![[0x70, 0xa0, 0x82, 0x31], [0xa9, 0x05, 0x9c, 0xbb]]
+        );
+    }
+
+    #[test]
+    fn match_selectors_test() {
+        assert_eq!(
+            match_selectors(
+                &CODE,
+                &["transfer(address,uint256)", "approve(address,uint256)"]
+            ),
+            vec![
+                (Selector([0x70, 0xa0, 0x82, 0x31]), None),
+                (
+                    Selector([0xa9, 0x05, 0x9c, 0xbb]),
+                    Some("transfer(address,uint256)")
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn link_bytecode_test() {
+        let name = "lib/Contract.sol:Lib";
+        let hash = hex::encode(&Keccak256::digest(name.as_bytes())[..17]);
+        let unlinked = format!("0x73__${}$__6300", hash);
+        let linked = link_bytecode(&unlinked, [(name, Address([0xbe; 20]))]).unwrap();
+        assert_eq!(linked[0], 0x73);
+        assert_eq!(linked[1..21], [0xbe; 20]);
+        assert_eq!(linked[21..], [0x63, 0x00]);
+
+        let legacy = "73__lib/Contract.sol:Lib__________________6300";
+        assert_eq!(
+            link_bytecode(legacy, [(name, Address([0xbe; 20]))]).unwrap(),
+            linked
+        );
+    }
+
+    #[test]
+    fn unlinked_bytecode_test() {
+        let unlinked = format!("73__${}$__", "00".repeat(17));
+        let error =
+            link_bytecode(&unlinked, [("lib/Contract.sol:Lib", Address([0xbe; 20]))]).unwrap_err();
+        assert!(error.contains(&unlinked[2..]));
+        assert!(link_bytecode("6300", Vec::<(String, Address)>::new()).is_ok());
+    }
+}
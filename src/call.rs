@@ -0,0 +1,200 @@
+use crate::{decode, encode, selector, AbiDecode, EthereumTypes, ParamType, Selector};
+
+/// A contract function as a Rust type holding its arguments, usually declared with `sol!`.
+///
+/// ```ignore
+/// sol! {
+///     function transfer(address to, uint256 amount) external returns (bool);
+/// }
+///
+/// let calldata = TransferCall { to, amount }.encode();
+/// let success: bool = TransferCall::decode_returns(&return_data)?;
+/// ```
+pub trait EthCall: Sized {
+    /// Canonical signature of the function, e.g. `transfer(address,uint256)`.
+    const SIGNATURE: &'static str;
+
+    /// Decoded return data, `()` without outputs, the output itself for a single one and a tuple
+    /// of the outputs otherwise.
+    type Return: AbiDecode;
+
+    fn input_types() -> Vec<ParamType>;
+
+    fn output_types() -> Vec<ParamType>;
+
+    fn into_tokens(self) -> Vec<EthereumTypes>;
+
+    fn from_tokens(tokens: Vec<EthereumTypes>) -> Result<Self, String>;
+
+    fn selector() -> Selector {
+        selector(Self::SIGNATURE)
+    }
+
+    /// Calldata of the call, the selector followed by the ABI encoded arguments.
+    fn encode(self) -> Vec<u8> {
+        let mut calldata = Self::selector().0.to_vec();
+        calldata.extend_from_slice(&encode(&self.into_tokens()));
+        calldata
+    }
+
+    /// Fails if the calldata isn't a call of the function or can't be decoded.
+    fn decode(calldata: &[u8]) -> Result<Self, String> {
+        match Selector::from_calldata(calldata) {
+            Some(selector) if selector == Self::selector() => {
+                Self::from_tokens(decode(&Self::input_types(), &calldata[4..])?)
+            }
+            _ => Err(format!("Calldata is not a call of {}.", Self::SIGNATURE)),
+        }
+    }
+
+    fn decode_returns(data: &[u8]) -> Result<Self::Return, String> {
+        Self::Return::from_tokens(decode(&Self::output_types(), data)?)
+    }
+}
+
+// used by `sol!` for the declared types, which can be narrower than the Rust types holding them
+#[doc(hidden)]
+pub fn param_types(names: &[&str]) -> Vec<ParamType> {
+    names
+        .iter()
+        .map(|name| ParamType::from_name(name).expect("sol! declares valid types"))
+        .collect()
+}
+
+#[cfg(all(test, feature = "derive"))]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{hex, sol, Address, EthEvent, Int, Log, Uint, U256};
+
+    use std::convert::TryFrom;
+
+    sol! {
+        function transfer(address to, uint256 amount) external returns (bool);
+        function swap(uint256, uint256, address, bytes calldata) external;
+        function slot0() external view returns (uint160 sqrtPriceX96, int24 tick, bool unlocked);
+        function batch((address,uint8)[] memory orders, string[2] memory) external payable;
+        event Sync(uint112 reserve0, uint112 reserve1);
+        event Approval(address indexed owner, address indexed spender, uint256 value);
+        event Named(string indexed name, int24 tick);
+        function safeTransferFrom(address from, address to, uint256 id) external;
+        function safeTransferFrom(address from, address to, uint256 id, bytes data) external;
+        function setType(uint8 type) external;
+        function setTick(int24 tick) external;
+    }
+
+    #[test]
+    fn sol_function_test() {
+        assert_eq!(TransferCall::SIGNATURE, "transfer(address,uint256)");
+        assert_eq!(TransferCall::selector(), [0xa9, 0x05, 0x9c, 0xbb]);
+        let call = TransferCall {
+            to: Address([0x22; 20]),
+            amount: U256::from(1000_u64),
+        };
+        let calldata = call.clone().encode();
+        assert_eq!(
+            hex::encode(&calldata),
+            concat!(
+                "a9059cbb",
+                "0000000000000000000000002222222222222222222222222222222222222222",
+                "00000000000000000000000000000000000000000000000000000000000003e8"
+            )
+        );
+        assert_eq!(TransferCall::decode(&calldata), Ok(call));
+        assert!(TransferCall::decode(&calldata[..4]).is_err());
+        assert_eq!(TransferCall::decode_returns(&U256::ONE.0), Ok(true));
+
+        assert_eq!(SwapCall::SIGNATURE, "swap(uint256,uint256,address,bytes)");
+        let swap = SwapCall {
+            _0: U256::ONE,
+            _1: U256::ZERO,
+            _2: Address::ZERO,
+            _3: vec![0xab; 3],
+        };
+        assert_eq!(SwapCall::decode(&swap.clone().encode()), Ok(swap));
+        assert!(SwapCall::decode(&calldata).is_err());
+        assert_eq!(SwapCall::decode_returns(&[]), Ok(()));
+
+        assert_eq!(BatchCall::SIGNATURE, "batch((address,uint8)[],string[2])");
+        let batch = BatchCall {
+            orders: vec![(Address([0x11; 20]), 7)],
+            _1: [String::from("a"), String::from("b")],
+        };
+        assert_eq!(BatchCall::decode(&batch.clone().encode()), Ok(batch));
+
+        assert_eq!(
+            SafeTransferFrom2Call::SIGNATURE,
+            "safeTransferFrom(address,address,uint256,bytes)"
+        );
+        assert_ne!(
+            SafeTransferFromCall::selector(),
+            SafeTransferFrom2Call::selector()
+        );
+        assert_eq!(SetTypeCall { r#type: 1 }.encode().len(), 36);
+
+        // integers of other widths are range checked, so encoded calls always decode
+        let tick = Int::<24>::MIN;
+        let call = SetTickCall { tick };
+        assert_eq!(SetTickCall::decode(&call.clone().encode()), Ok(call));
+        assert!(Int::<24>::try_from(1_i64 << 23).is_err());
+    }
+
+    #[test]
+    fn sol_returns_test() {
+        assert_eq!(Slot0Call::SIGNATURE, "slot0()");
+        let data = encode(&[
+            U256::from(1_u64 << 40).into(),
+            crate::I256::from(-60_i64).into(),
+            EthereumTypes::Bool(true),
+        ]);
+        let (price, tick, unlocked) = Slot0Call::decode_returns(&data).unwrap();
+        assert_eq!(price, Uint::<160>::try_from(1_u64 << 40).unwrap());
+        assert_eq!((tick, unlocked), (Int::try_from(-60_i64).unwrap(), true));
+
+        // the tick is an int24, wider values are rejected
+        let data = encode(&[
+            U256::ONE.into(),
+            crate::I256::from(1_i64 << 23).into(),
+            EthereumTypes::Bool(true),
+        ]);
+        assert!(Slot0Call::decode_returns(&data).is_err());
+    }
+
+    #[test]
+    fn sol_event_test() {
+        assert_eq!(Sync::signature(), "Sync(uint112,uint112)");
+        assert_eq!(
+            Approval::topic0(),
+            crate::event_topic("Approval(address,address,uint256)")
+        );
+        let log = Log {
+            address: Address::ZERO,
+            topics: vec![
+                Approval::topic0(),
+                Address([0x11; 20]).to_word(),
+                Address([0x22; 20]).to_word(),
+            ],
+            data: U256::from(5_u64).0.to_vec(),
+        };
+        assert_eq!(
+            Approval::decode(&log),
+            Ok(Approval {
+                owner: Address([0x11; 20]),
+                spender: Address([0x22; 20]),
+                value: U256::from(5_u64),
+            })
+        );
+        assert!(Sync::decode(&log).is_err());
+
+        // indexed strings are only stored as their hash
+        assert_eq!(Named::signature(), "Named(string,int24)");
+        let log = Log {
+            address: Address::ZERO,
+            topics: vec![Named::topic0(), [0xcc; 32]],
+            data: crate::I256::from(-1_i64).0.to_vec(),
+        };
+        let named = Named::decode(&log).unwrap();
+        assert_eq!(named.name, [0xcc; 32]);
+        assert_eq!(named.tick.value(), crate::I256::MINUS_ONE);
+    }
+}
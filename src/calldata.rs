@@ -0,0 +1,268 @@
+use crate::{encode, hex, Abi, Function, Selector};
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// Calldata split into the function selector and the encoded arguments.
+///
+/// Serializes as the `0x` prefixed hex string of the flat bytes, the `data` of JSON-RPC calls
+/// and transactions.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct CallData {
+    pub selector: Selector,
+    pub args: Vec<u8>,
+}
+
+impl CallData {
+    pub fn new(selector: Selector, args: Vec<u8>) -> Self {
+        Self { selector, args }
+    }
+
+    /// The flat calldata, the selector followed by the arguments.
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut calldata = Vec::with_capacity(4 + self.args.len());
+        calldata.extend_from_slice(&self.selector.0);
+        calldata.extend_from_slice(&self.args);
+        calldata
+    }
+
+    /// Length of the flat calldata.
+    #[inline]
+    pub fn len(&self) -> usize {
+        4 + self.args.len()
+    }
+
+    /// Always false, calldata has at least a selector.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+impl TryFrom<&[u8]> for CallData {
+    type Error = String;
+
+    fn try_from(calldata: &[u8]) -> Result<Self, Self::Error> {
+        let selector = Selector::from_calldata(calldata)
+            .ok_or_else(|| format!("Calldata of {} bytes has no selector.", calldata.len()))?;
+        Ok(Self::new(selector, calldata[4..].to_vec()))
+    }
+}
+
+impl TryFrom<Vec<u8>> for CallData {
+    type Error = String;
+
+    fn try_from(mut calldata: Vec<u8>) -> Result<Self, Self::Error> {
+        let selector = Selector::from_calldata(&calldata)
+            .ok_or_else(|| format!("Calldata of {} bytes has no selector.", calldata.len()))?;
+        // reuse the allocation for the arguments
+        calldata.drain(..4);
+        Ok(Self::new(selector, calldata))
+    }
+}
+
+impl From<CallData> for Vec<u8> {
+    fn from(calldata: CallData) -> Self {
+        calldata.to_vec()
+    }
+}
+
+impl FromStr for CallData {
+    type Err = String;
+
+    /// Parses hex calldata, with or without the `0x` prefix.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s).map_err(|e| format!("Invalid calldata: {}", e))?;
+        Self::try_from(bytes)
+    }
+}
+
+impl fmt::Display for CallData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.selector, hex::encode(&self.args))
+    }
+}
+
+impl fmt::Debug for CallData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CallData({})", self)
+    }
+}
+
+impl Serialize for CallData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CallData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// Reasons [`validate_calldata`] rejects a call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CalldataError {
+    /// Calldata shorter than a selector.
+    TooShort(usize),
+    /// A selector the ABI doesn't declare.
+    UnknownSelector(Selector),
+    /// Arguments that don't decode under the declared types, or not in their canonical encoding.
+    InvalidArguments { signature: String, reason: String },
+}
+
+impl fmt::Display for CalldataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort(len) => write!(f, "Calldata of {} bytes has no selector.", len),
+            Self::UnknownSelector(selector) => {
+                write!(f, "Unknown selector {}.", selector)
+            }
+            Self::InvalidArguments { signature, reason } => {
+                write!(f, "Invalid arguments of {}: {}", signature, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CalldataError {}
+
+/// The function called by the calldata, if the ABI declares its selector and the arguments decode
+/// cleanly under the declared types.
+///
+/// Meant for gateways rejecting unexpected calls before relaying them. Arguments also have to be
+/// in their canonical encoding, without trailing bytes, dirty padding or unusual offsets, such
+/// that what is relayed is exactly what was validated. Decoding is bounded by the size of the
+/// calldata, offsets making values share their encoding are rejected before they amplify.
+pub fn validate_calldata<'a, 'b>(
+    abi: &'a Abi<'b>,
+    calldata: &[u8],
+) -> Result<&'a Function<'b>, CalldataError> {
+    let selector =
+        Selector::from_calldata(calldata).ok_or(CalldataError::TooShort(calldata.len()))?;
+    let mut error = CalldataError::UnknownSelector(selector);
+    // a selector collision declares more than one function with the same selector
+    for function in abi.functions.iter().filter(|f| f.selector() == selector) {
+        let reason = match function.decode_input(calldata) {
+            Ok(arguments) if encode(&arguments) == calldata[4..] => return Ok(function),
+            Ok(_) => "Arguments are not canonically encoded.".to_owned(),
+            Err(reason) => reason,
+        };
+        error = CalldataError::InvalidArguments {
+            signature: function.signature(),
+            reason,
+        };
+    }
+    Err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{selector, Address, U256};
+
+    fn transfer() -> CallData {
+        let mut args = Address([0x11; 20]).to_word().to_vec();
+        args.extend_from_slice(&U256::ONE.0);
+        CallData::new(selector("transfer(address,uint256)"), args)
+    }
+
+    #[test]
+    fn bytes_test() {
+        let calldata = transfer();
+        let flat = calldata.to_vec();
+        assert_eq!((flat.len(), calldata.len()), (68, 68));
+        assert_eq!(&flat[..4], &[0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(CallData::try_from(&flat[..]), Ok(calldata.clone()));
+        assert_eq!(CallData::try_from(flat.clone()), Ok(calldata.clone()));
+        assert_eq!(Vec::from(calldata), flat);
+
+        assert_eq!(
+            CallData::try_from(vec![0xa9, 0x05, 0x9c]),
+            Err("Calldata of 3 bytes has no selector.".to_owned())
+        );
+        let empty = CallData::try_from(&[0xd0, 0xe3, 0x0d, 0xb0][..]).unwrap();
+        assert!(empty.args.is_empty());
+    }
+
+    #[test]
+    fn validate_calldata_test() {
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        let abi: Abi = serde_json::from_str(&json).unwrap();
+        let calldata = transfer().to_vec();
+        let function = validate_calldata(&abi, &calldata).unwrap();
+        assert_eq!(function.signature(), "transfer(address,uint256)");
+
+        assert_eq!(
+            validate_calldata(&abi, &calldata[..3]),
+            Err(CalldataError::TooShort(3))
+        );
+        assert_eq!(
+            validate_calldata(&abi, &[0xde, 0xad, 0xbe, 0xef]),
+            Err(CalldataError::UnknownSelector(Selector([
+                0xde, 0xad, 0xbe, 0xef
+            ])))
+        );
+        let is_invalid = |calldata: &[u8]| {
+            matches!(
+                validate_calldata(&abi, calldata),
+                Err(CalldataError::InvalidArguments { .. })
+            )
+        };
+        assert!(is_invalid(&calldata[..40]));
+        // trailing bytes and dirty address padding decode, but aren't canonical
+        let mut trailing = calldata.clone();
+        trailing.push(0);
+        assert!(is_invalid(&trailing));
+        let mut dirty = calldata;
+        dirty[4] = 0xff;
+        assert!(is_invalid(&dirty));
+    }
+
+    #[test]
+    fn aliased_offsets_test() {
+        let abi: Abi =
+            serde_json::from_str(r#"[{"name":"batch","inputs":[{"type":"uint256[][]"}]}]"#)
+                .unwrap();
+        // n element offsets all pointing at the same array of n words, which would decode into
+        // n * n words from 2 * n words of calldata
+        let n = 4096_u64;
+        let mut calldata = selector("batch(uint256[][])").0.to_vec();
+        calldata.extend_from_slice(&U256::from(0x20_u64).0);
+        calldata.extend_from_slice(&U256::from(n).0);
+        for _ in 0..n {
+            calldata.extend_from_slice(&U256::from(32 * n).0);
+        }
+        calldata.extend_from_slice(&U256::from(n).0);
+        calldata.resize(calldata.len() + 32 * n as usize, 0);
+        assert!(matches!(
+            validate_calldata(&abi, &calldata),
+            Err(CalldataError::InvalidArguments { .. })
+        ));
+    }
+
+    #[test]
+    fn serde_test() {
+        let calldata = transfer();
+        let json = serde_json::to_string(&calldata).unwrap();
+        assert!(json.starts_with("\"0xa9059cbb000000000000000000000000111111"));
+        assert_eq!(serde_json::from_str::<CallData>(&json).unwrap(), calldata);
+        assert_eq!(json.trim_matches('"').parse(), Ok(calldata));
+
+        assert!(serde_json::from_str::<CallData>("\"0x1234\"").is_err());
+        assert!(serde_json::from_str::<CallData>("\"0xzz059cbb\"").is_err());
+        assert_eq!(
+            serde_json::from_str::<CallData>("\"d0e30db0\"")
+                .unwrap()
+                .selector,
+            Selector([0xd0, 0xe3, 0x0d, 0xb0])
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// An EIP-155 chain id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChainId(pub u64);
+
+impl ChainId {
+    pub const MAINNET: ChainId = ChainId(1);
+    pub const SEPOLIA: ChainId = ChainId(11_155_111);
+    pub const HOLESKY: ChainId = ChainId(17_000);
+    pub const OPTIMISM: ChainId = ChainId(10);
+    pub const OPTIMISM_SEPOLIA: ChainId = ChainId(11_155_420);
+    pub const ARBITRUM: ChainId = ChainId(42_161);
+    pub const ARBITRUM_SEPOLIA: ChainId = ChainId(421_614);
+    pub const BASE: ChainId = ChainId(8_453);
+    pub const BASE_SEPOLIA: ChainId = ChainId(84_532);
+    pub const POLYGON: ChainId = ChainId(137);
+    pub const BSC: ChainId = ChainId(56);
+    pub const GNOSIS: ChainId = ChainId(100);
+    pub const AVALANCHE: ChainId = ChainId(43_114);
+    pub const ZKSYNC: ChainId = ChainId(324);
+    pub const LINEA: ChainId = ChainId(59_144);
+    pub const SCROLL: ChainId = ChainId(534_352);
+
+    /// Every chain with a well-known name.
+    pub const KNOWN: [ChainId; 16] = [
+        Self::MAINNET,
+        Self::SEPOLIA,
+        Self::HOLESKY,
+        Self::OPTIMISM,
+        Self::OPTIMISM_SEPOLIA,
+        Self::ARBITRUM,
+        Self::ARBITRUM_SEPOLIA,
+        Self::BASE,
+        Self::BASE_SEPOLIA,
+        Self::POLYGON,
+        Self::BSC,
+        Self::GNOSIS,
+        Self::AVALANCHE,
+        Self::ZKSYNC,
+        Self::LINEA,
+        Self::SCROLL,
+    ];
+
+    /// Lowercase name of a well-known chain, e.g. `mainnet` or `arbitrum-sepolia`.
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match *self {
+            Self::MAINNET => "mainnet",
+            Self::SEPOLIA => "sepolia",
+            Self::HOLESKY => "holesky",
+            Self::OPTIMISM => "optimism",
+            Self::OPTIMISM_SEPOLIA => "optimism-sepolia",
+            Self::ARBITRUM => "arbitrum",
+            Self::ARBITRUM_SEPOLIA => "arbitrum-sepolia",
+            Self::BASE => "base",
+            Self::BASE_SEPOLIA => "base-sepolia",
+            Self::POLYGON => "polygon",
+            Self::BSC => "bsc",
+            Self::GNOSIS => "gnosis",
+            Self::AVALANCHE => "avalanche",
+            Self::ZKSYNC => "zksync",
+            Self::LINEA => "linea",
+            Self::SCROLL => "scroll",
+            _ => return None,
+        })
+    }
+
+    /// EIP-3770 short name of a well-known chain, e.g. `eth` or `arb1`.
+    pub fn short_name(&self) -> Option<&'static str> {
+        Some(match *self {
+            Self::MAINNET => "eth",
+            Self::SEPOLIA => "sep",
+            Self::HOLESKY => "holesky",
+            Self::OPTIMISM => "oeth",
+            Self::OPTIMISM_SEPOLIA => "opsep",
+            Self::ARBITRUM => "arb1",
+            Self::ARBITRUM_SEPOLIA => "arb-sep",
+            Self::BASE => "base",
+            Self::BASE_SEPOLIA => "basesep",
+            Self::POLYGON => "pol",
+            Self::BSC => "bnb",
+            Self::GNOSIS => "gno",
+            Self::AVALANCHE => "avax",
+            Self::ZKSYNC => "zksync",
+            Self::LINEA => "linea",
+            Self::SCROLL => "scr",
+            _ => return None,
+        })
+    }
+
+    /// Looks up a well-known chain by its EIP-3770 short name.
+    pub fn from_short_name(short_name: &str) -> Option<Self> {
+        Self::KNOWN
+            .iter()
+            .copied()
+            .find(|id| id.short_name() == Some(short_name))
+    }
+
+    /// Looks up a well-known chain by its name, case insensitively.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::KNOWN
+            .iter()
+            .copied()
+            .find(|id| id.name().is_some_and(|n| n.eq_ignore_ascii_case(name)))
+    }
+
+    #[inline]
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for ChainId {
+    fn from(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+impl From<ChainId> for u64 {
+    fn from(id: ChainId) -> Self {
+        id.0
+    }
+}
+
+/// Formats well-known chains by name and every other chain by its decimal id.
+impl fmt::Display for ChainId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+/// Parses either the name of a well-known chain or a decimal chain id.
+impl FromStr for ChainId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(id) = Self::from_name(s) {
+            return Ok(id);
+        }
+        s.parse::<u64>()
+            .map(Self)
+            .map_err(|_| format!("Unknown chain {}.", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_lookup_test() {
+        for id in ChainId::KNOWN.iter() {
+            assert_eq!(ChainId::from_name(id.name().unwrap()), Some(*id));
+        }
+        assert_eq!(ChainId::from_name("Mainnet"), Some(ChainId::MAINNET));
+        assert_eq!(ChainId(31_337).name(), None);
+    }
+
+    #[test]
+    fn short_name_lookup_test() {
+        for id in ChainId::KNOWN.iter() {
+            assert_eq!(
+                ChainId::from_short_name(id.short_name().unwrap()),
+                Some(*id)
+            );
+        }
+        assert_eq!(ChainId::from_short_name("oeth"), Some(ChainId::OPTIMISM));
+        assert_eq!(ChainId::from_short_name("ETH"), None);
+    }
+
+    #[test]
+    fn display_parse_test() {
+        assert_eq!(ChainId::BASE.to_string(), "base");
+        assert_eq!(ChainId(31_337).to_string(), "31337");
+        assert_eq!("sepolia".parse(), Ok(ChainId::SEPOLIA));
+        assert_eq!("31337".parse(), Ok(ChainId(31_337)));
+        assert!("ropsten".parse::<ChainId>().is_err());
+    }
+}
@@ -0,0 +1,533 @@
+use crate::types::fits_int;
+use crate::{EthereumTypes, ParamType};
+
+/// ABI encodes the values one after the other, as they follow the selector in calldata.
+///
+/// Static values are written in place, dynamic ones are replaced by an offset into the tail that
+/// holds their content.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "trace", skip_all, fields(values = tokens.len()))
+)]
+pub fn encode(tokens: &[EthereumTypes]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(sequence_size(tokens));
+    encode_sequence(tokens, &mut encoded);
+    #[cfg(all(feature = "ethabi-check", debug_assertions))]
+    crate::crosscheck::check_encode(tokens, &encoded);
+    #[cfg(feature = "tracing")]
+    tracing::trace!(bytes = encoded.len(), "encoded");
+    encoded
+}
+
+// encoded sizes of the values of a sequence, kept on the stack for typical calls
+#[cfg(feature = "smallvec")]
+type Sizes = smallvec::SmallVec<[usize; 4]>;
+#[cfg(not(feature = "smallvec"))]
+type Sizes = Vec<usize>;
+
+// writes the heads and then the tails straight into `out`, the offsets are known from the sizes
+fn encode_sequence(tokens: &[EthereumTypes], out: &mut Vec<u8>) {
+    let sizes: Sizes = tokens.iter().map(encoded_size).collect();
+    let head_size: usize = tokens
+        .iter()
+        .zip(&sizes)
+        .map(|(token, size)| if token.is_dynamic() { 32 } else { *size })
+        .sum();
+
+    let mut tail_offset = head_size;
+    for (token, size) in tokens.iter().zip(&sizes) {
+        if token.is_dynamic() {
+            out.extend_from_slice(&word(tail_offset));
+            tail_offset += size;
+        } else {
+            encode_token(token, out);
+        }
+    }
+    for token in tokens.iter().filter(|token| token.is_dynamic()) {
+        encode_token(token, out);
+    }
+}
+
+// size of the encoding of a sequence, including the offsets of its dynamic values
+fn sequence_size(tokens: &[EthereumTypes]) -> usize {
+    tokens
+        .iter()
+        .map(|token| match token.is_dynamic() {
+            true => 32 + encoded_size(token),
+            false => encoded_size(token),
+        })
+        .sum()
+}
+
+// size of the encoding of a value, the tail for dynamic values
+fn encoded_size(token: &EthereumTypes) -> usize {
+    match token {
+        EthereumTypes::Bytes(bytes) => 32 + padded_len(bytes.len()),
+        EthereumTypes::String(string) => 32 + padded_len(string.len()),
+        EthereumTypes::Array(elements) => 32 + sequence_size(elements),
+        EthereumTypes::FixedArray(elements) | EthereumTypes::Tuple(elements) => {
+            sequence_size(elements)
+        }
+        _ => 32,
+    }
+}
+
+fn padded_len(len: usize) -> usize {
+    len + (32 - len % 32) % 32
+}
+
+fn encode_token(token: &EthereumTypes, out: &mut Vec<u8>) {
+    match token {
+        EthereumTypes::Bytes(bytes) => encode_bytes(bytes, out),
+        EthereumTypes::String(string) => encode_bytes(string.as_bytes(), out),
+        EthereumTypes::Array(elements) => {
+            out.extend_from_slice(&word(elements.len()));
+            encode_sequence(elements, out);
+        }
+        EthereumTypes::FixedArray(elements) | EthereumTypes::Tuple(elements) => {
+            encode_sequence(elements, out)
+        }
+        token => out.extend_from_slice(&token.value_as_u256()),
+    }
+}
+
+// the length followed by the content, right padded to full words
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(&word(bytes.len()));
+    out.extend_from_slice(bytes);
+    out.resize(out.len() + padded_len(bytes.len()) - bytes.len(), 0);
+}
+
+fn word(value: usize) -> [u8; 32] {
+    let mut word = [0_u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+/// Non-standard packed encoding of values of the given types, as done by Solidity's
+/// `abi.encodePacked`.
+///
+/// Values are concatenated without offsets or lengths, in the size of their type: 20 bytes for
+/// addresses, `N / 8` bytes for `uintN` and `intN`, one byte for bools and the content of `bytesN`,
+/// `bytes` and `string`. Elements of arrays are still padded to full words. The result isn't
+/// decodable in general, but is often hashed.
+///
+/// Fails if the values aren't of the given types.
+pub fn encode_packed(types: &[ParamType], tokens: &[EthereumTypes]) -> Result<Vec<u8>, String> {
+    if types.len() != tokens.len() {
+        return Err(format!(
+            "Expected {} values to pack, found {}.",
+            types.len(),
+            tokens.len()
+        ));
+    }
+    let mut encoded = Vec::new();
+    for (kind, token) in types.iter().zip(tokens) {
+        if !token.type_check(kind) {
+            return Err(format!(
+                "Expected a value of type {}, found {}.",
+                kind.name_as_str(),
+                token.name_as_str()
+            ));
+        }
+        encode_packed_token(kind, token, &mut encoded);
+    }
+    Ok(encoded)
+}
+
+// a value already checked to be of the type
+fn encode_packed_token(kind: &ParamType, token: &EthereumTypes, out: &mut Vec<u8>) {
+    match (kind, token) {
+        (
+            ParamType::Uint(bits) | ParamType::Int(bits),
+            EthereumTypes::U256(word) | EthereumTypes::I256(word),
+        ) => out.extend_from_slice(&word[32 - bits / 8..]),
+        (ParamType::Tuple(types), EthereumTypes::Tuple(components)) => {
+            for (kind, component) in types.iter().zip(components) {
+                encode_packed_token(kind, component, out);
+            }
+        }
+        (_, EthereumTypes::Address(address)) => out.extend_from_slice(address),
+        (_, EthereumTypes::Bool(value)) => out.push(u8::from(*value)),
+        (_, EthereumTypes::FixedBytes(bytes) | EthereumTypes::Bytes(bytes)) => {
+            out.extend_from_slice(bytes)
+        }
+        (_, EthereumTypes::String(string)) => out.extend_from_slice(string.as_bytes()),
+        (_, EthereumTypes::Array(elements) | EthereumTypes::FixedArray(elements)) => {
+            for element in elements {
+                encode_token(element, out);
+            }
+        }
+        (_, EthereumTypes::U256(word) | EthereumTypes::I256(word)) => out.extend_from_slice(word),
+        (_, EthereumTypes::Tuple(_)) => unreachable!("tuples are type checked"),
+    }
+}
+
+/// Decodes ABI encoded values of the given types, e.g. return data or calldata without the selector.
+///
+/// The decoded values are limited to what the data holds, so overlapping offsets that make many
+/// values share one encoding are rejected instead of decoding into a much larger result.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(types = types.len(), bytes = data.len()),
+        err(level = "debug")
+    )
+)]
+pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<EthereumTypes>, String> {
+    let head_size = types
+        .iter()
+        .try_fold(0_usize, |sum, kind| sum.checked_add(kind.head_size()?))
+        .ok_or("Types are too large to be encoded.")?;
+    if data.len() < head_size {
+        return Err(format!(
+            "Encoded data is too short. Expected at least {} bytes, found {}.",
+            head_size,
+            data.len()
+        ));
+    }
+    let mut budget = data.len() / 32;
+    let decoded = decode_sequence(types.iter(), data, &mut budget)?;
+    #[cfg(all(feature = "ethabi-check", debug_assertions))]
+    crate::crosscheck::check_decode(types, data, &decoded);
+    Ok(decoded)
+}
+
+// decodes consecutive values whose offsets are relative to the start of `data`, `budget` is the
+// number of words still left to decode over the whole recursion
+fn decode_sequence<'t, I>(
+    types: I,
+    data: &[u8],
+    budget: &mut usize,
+) -> Result<Vec<EthereumTypes>, String>
+where
+    I: Iterator<Item = &'t ParamType>,
+{
+    let mut tokens = Vec::new();
+    let mut head = 0;
+    for kind in types {
+        if kind.is_dynamic() {
+            let offset = read_usize(data, head)?;
+            let tail = data
+                .get(offset..)
+                .ok_or_else(|| format!("Encoded offset {} is out of bounds.", offset))?;
+            tokens.push(decode_token(kind, tail, budget)?);
+            head += 32;
+        } else {
+            let value = data.get(head..).ok_or("Encoded data is too short.")?;
+            tokens.push(decode_token(kind, value, budget)?);
+            head = kind
+                .head_size()
+                .and_then(|size| head.checked_add(size))
+                .ok_or("Types are too large to be encoded.")?;
+        }
+    }
+    Ok(tokens)
+}
+
+fn decode_token(
+    kind: &ParamType,
+    data: &[u8],
+    budget: &mut usize,
+) -> Result<EthereumTypes, String> {
+    match kind {
+        ParamType::Bytes => Ok(EthereumTypes::Bytes(decode_bytes(data, budget)?.to_vec())),
+        ParamType::String => String::from_utf8(decode_bytes(data, budget)?.to_vec())
+            .map(EthereumTypes::String)
+            .map_err(|e| format!("Encoded string is not valid UTF-8: {}", e)),
+        ParamType::Array(element) => {
+            let len = read_usize(data, 0)?;
+            // every element takes at least a word, which bounds the allocation by the input size
+            if len > data.len() / 32 {
+                return Err(format!("Encoded array length {} is too large.", len));
+            }
+            charge(budget, 1)?;
+            decode_sequence(std::iter::repeat_n(&**element, len), &data[32..], budget)
+                .map(EthereumTypes::Array)
+        }
+        ParamType::FixedArray(element, size) => {
+            // elements without an encoding, like `()`, don't consume any input
+            if element.head_size() == Some(0) && *size > *budget {
+                return Err(format!("Encoded array size {} is too large.", size));
+            }
+            decode_sequence(std::iter::repeat_n(&**element, *size), data, budget)
+                .map(EthereumTypes::FixedArray)
+        }
+        ParamType::Tuple(components) => {
+            if components.is_empty() {
+                charge(budget, 1)?;
+            }
+            decode_sequence(components.iter(), data, budget).map(EthereumTypes::Tuple)
+        }
+        kind => {
+            charge(budget, 1)?;
+            let mut value = [0_u8; 32];
+            value.copy_from_slice(read_word(data, 0)?);
+            decode_word(kind, value)
+        }
+    }
+}
+
+// takes `words` from the decode budget, which a valid encoding never exceeds as every word of it
+// decodes at most once
+fn charge(budget: &mut usize, words: usize) -> Result<(), String> {
+    *budget = budget
+        .checked_sub(words)
+        .ok_or("Encoded data decodes to more values than it holds, its offsets overlap.")?;
+    Ok(())
+}
+
+fn decode_bytes<'d>(data: &'d [u8], budget: &mut usize) -> Result<&'d [u8], String> {
+    let len = read_usize(data, 0)?;
+    let content = data
+        .get(32..)
+        .and_then(|content| content.get(..len))
+        .ok_or_else(|| format!("Encoded bytes of length {} are truncated.", len))?;
+    charge(budget, 1 + padded_len(len) / 32)?;
+    Ok(content)
+}
+
+fn read_word(data: &[u8], at: usize) -> Result<&[u8], String> {
+    at.checked_add(32)
+        .and_then(|end| data.get(at..end))
+        .ok_or_else(|| "Encoded data is too short.".to_owned())
+}
+
+// a length or offset, which has to fit into 32 bits to be addressable at all
+fn read_usize(data: &[u8], at: usize) -> Result<usize, String> {
+    let word = read_word(data, at)?;
+    if word[..28].iter().any(|b| *b != 0) {
+        return Err("Encoded length or offset is too large.".to_owned());
+    }
+    Ok(u32::from_be_bytes([word[28], word[29], word[30], word[31]]) as usize)
+}
+
+fn decode_word(kind: &ParamType, word: [u8; 32]) -> Result<EthereumTypes, String> {
+    match kind {
+        ParamType::Address => {
+            // an address is left padded with 12 zero bytes, anything else is not a valid encoding
+            if word[..12].iter().any(|b| *b != 0) {
+                return Err("Encoded address has non-zero padding bytes.".to_owned());
+            }
+            Ok(EthereumTypes::address_from_bytes(&word[12..]))
+        }
+        ParamType::U256 => Ok(EthereumTypes::U256(word)),
+        ParamType::Uint(bits) => {
+            if word[..32 - bits / 8].iter().any(|b| *b != 0) {
+                return Err(format!("Encoded uint{} is out of range.", bits));
+            }
+            Ok(EthereumTypes::U256(word))
+        }
+        ParamType::Int(bits) => {
+            if !fits_int(&word, *bits) {
+                return Err(format!("Encoded int{} is out of range.", bits));
+            }
+            Ok(EthereumTypes::I256(word))
+        }
+        ParamType::Bool => match word {
+            w if w[..31].iter().any(|b| *b != 0) || w[31] > 1 => {
+                Err("Encoded bool is neither 0 nor 1.".to_owned())
+            }
+            w => Ok(EthereumTypes::Bool(w[31] == 1)),
+        },
+        ParamType::FixedBytes(size) => {
+            if word
+                .get(*size..)
+                .is_none_or(|padding| padding.iter().any(|b| *b != 0))
+            {
+                return Err(format!("Encoded bytes{} has non-zero padding bytes.", size));
+            }
+            Ok(EthereumTypes::FixedBytes(word[..*size].to_vec()))
+        }
+        kind => Err(format!("{} is not a single word type.", kind.name_as_str())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    #[test]
+    fn roundtrip_test() {
+        let tokens = vec![
+            EthereumTypes::Address([0x30; 20]),
+            EthereumTypes::u256_from_bytes(&20000000000_u64.to_be_bytes()),
+        ];
+        let encoded = encode(&tokens);
+        assert_eq!(encoded.len(), 64);
+        assert_eq!(
+            decode(&[ParamType::Address, ParamType::U256], &encoded).unwrap(),
+            tokens
+        );
+    }
+
+    #[test]
+    fn dynamic_roundtrip_test() {
+        // the `f(uint256,uint32[],bytes10,bytes)` example of the ABI specification, with the
+        // uint32 array encoded as uint256 values
+        let tokens = vec![
+            EthereumTypes::u256_from_bytes(&[0x01, 0x23]),
+            EthereumTypes::Array(vec![
+                EthereumTypes::u256_from_bytes(&[0x04, 0x56]),
+                EthereumTypes::u256_from_bytes(&[0x07, 0x89]),
+            ]),
+            EthereumTypes::FixedBytes(b"1234567890".to_vec()),
+            EthereumTypes::Bytes(b"Hello, world!".to_vec()),
+        ];
+        let encoded = encode(&tokens);
+        assert_eq!(
+            hex::encode(&encoded),
+            concat!(
+                "0000000000000000000000000000000000000000000000000000000000000123",
+                "0000000000000000000000000000000000000000000000000000000000000080",
+                "3132333435363738393000000000000000000000000000000000000000000000",
+                "00000000000000000000000000000000000000000000000000000000000000e0",
+                "0000000000000000000000000000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000000000000000000000000000456",
+                "0000000000000000000000000000000000000000000000000000000000000789",
+                "000000000000000000000000000000000000000000000000000000000000000d",
+                "48656c6c6f2c20776f726c642100000000000000000000000000000000000000"
+            )
+        );
+        let types: Vec<ParamType> = tokens.iter().map(EthereumTypes::param_type).collect();
+        assert_eq!(decode(&types, &encoded).unwrap(), tokens);
+    }
+
+    #[test]
+    fn nested_roundtrip_test() {
+        let tokens = vec![
+            EthereumTypes::Tuple(vec![
+                EthereumTypes::Bool(true),
+                EthereumTypes::Array(vec![
+                    EthereumTypes::String("one".to_owned()),
+                    EthereumTypes::String(String::new()),
+                ]),
+            ]),
+            EthereumTypes::FixedArray(vec![
+                EthereumTypes::Address([0x11; 20]),
+                EthereumTypes::Address([0x22; 20]),
+            ]),
+        ];
+        let types = [
+            ParamType::from_name("(bool,string[])").unwrap(),
+            ParamType::from_name("address[2]").unwrap(),
+        ];
+        assert_eq!(decode(&types, &encode(&tokens)).unwrap(), tokens);
+    }
+
+    #[test]
+    fn encoded_size_test() {
+        let tokens = [
+            EthereumTypes::Bool(true),
+            EthereumTypes::Bytes(vec![0xab; 33]),
+            EthereumTypes::Array(vec![EthereumTypes::String("gm".to_owned())]),
+            EthereumTypes::FixedArray(vec![EthereumTypes::Bool(false); 2]),
+        ];
+        let encoded = encode(&tokens);
+        assert_eq!(sequence_size(&tokens), encoded.len());
+        // encoded in a single allocation of the exact size
+        assert_eq!(encoded.capacity(), encoded.len());
+    }
+
+    #[test]
+    fn aliased_offsets_test() {
+        // a uint256[][] whose n element offsets all point at the same n element array would
+        // decode into n * n values
+        let n = 64;
+        let mut data = word(0x20).to_vec();
+        data.extend_from_slice(&word(n));
+        for _ in 0..n {
+            data.extend_from_slice(&word(32 * n));
+        }
+        data.extend_from_slice(&word(n));
+        data.resize(data.len() + 32 * n, 0);
+        let kind = ParamType::from_name("uint256[][]").unwrap();
+        assert!(decode(std::slice::from_ref(&kind), &data)
+            .unwrap_err()
+            .contains("overlap"));
+
+        // the same for bytes
+        let mut data = word(0x20).to_vec();
+        data.extend_from_slice(&word(n));
+        for _ in 0..n {
+            data.extend_from_slice(&word(32 * n));
+        }
+        data.extend_from_slice(&word(32 * n));
+        data.resize(data.len() + 32 * n, 0xab);
+        let kind = ParamType::from_name("bytes[]").unwrap();
+        assert!(decode(std::slice::from_ref(&kind), &data).is_err());
+
+        // an array without distinct elements isn't looked at element by element
+        let kind = ParamType::from_name("()[4294967295]").unwrap();
+        assert!(decode(&[kind], &[0; 64]).is_err());
+
+        // canonical encodings are within the budget
+        let tokens = vec![EthereumTypes::Array(vec![
+            EthereumTypes::Array(vec![EthereumTypes::U256([1; 32]); 3]),
+            EthereumTypes::Array(vec![]),
+        ])];
+        let types: Vec<ParamType> = tokens.iter().map(EthereumTypes::param_type).collect();
+        assert_eq!(decode(&types, &encode(&tokens)), Ok(tokens));
+    }
+
+    #[test]
+    fn encode_packed_test() {
+        let tokens = [
+            EthereumTypes::Address([0x11; 20]),
+            EthereumTypes::Bool(true),
+            EthereumTypes::String("ab".to_owned()),
+            EthereumTypes::Array(vec![EthereumTypes::Bool(false)]),
+        ];
+        let types = [
+            ParamType::Address,
+            ParamType::Bool,
+            ParamType::String,
+            ParamType::from_name("bool[]").unwrap(),
+        ];
+        let mut expected = vec![0x11; 20];
+        expected.extend_from_slice(&[0x01, b'a', b'b']);
+        expected.extend_from_slice(&[0; 32]);
+        assert_eq!(encode_packed(&types, &tokens), Ok(expected));
+
+        // integers take the size of their type, like `abi.encodePacked(uint24(500), int8(-1))`
+        let tokens = [
+            EthereumTypes::u256_from_bytes(&500_u32.to_be_bytes()),
+            EthereumTypes::I256([0xff; 32]),
+            EthereumTypes::Tuple(vec![EthereumTypes::U256([0x22; 32])]),
+        ];
+        let types = [
+            ParamType::Uint(24),
+            ParamType::Int(8),
+            ParamType::from_name("(uint256)").unwrap(),
+        ];
+        assert_eq!(
+            encode_packed(&types, &tokens),
+            Ok([&[0x00, 0x01, 0xf4, 0xff][..], &[0x22; 32]].concat())
+        );
+        assert!(encode_packed(&types[..1], &tokens[1..2]).is_err());
+        assert!(encode_packed(&types, &tokens[..2]).is_err());
+    }
+
+    #[test]
+    fn invalid_decode_test() {
+        assert!(decode(&[ParamType::U256], &[0_u8; 31]).is_err());
+        assert!(decode(&[ParamType::Address], &[0xff_u8; 32]).is_err());
+        assert!(decode(&[ParamType::Bool], &[0x02_u8; 32]).is_err());
+        assert!(decode(&[ParamType::Uint(248)], &[0x01_u8; 32]).is_err());
+        assert!(decode(&[ParamType::Int(8)], &[0x01_u8; 32]).is_err());
+        assert!(decode(&[ParamType::Int(8)], &[0xff_u8; 32]).is_ok());
+        // an offset pointing past the data and an array length exceeding it
+        let mut data = [0_u8; 64];
+        data[31] = 0x40;
+        assert!(decode(&[ParamType::Bytes], &data).is_err());
+        data[31] = 0x20;
+        data[63] = 0xff;
+        assert!(decode(&[ParamType::from_name("uint256[]").unwrap()], &data).is_err());
+        // sizes overflowing the head are rejected instead of wrapping around
+        let huge = ParamType::FixedArray(Box::new(ParamType::U256), usize::MAX / 16);
+        assert!(decode(&[huge], &data).is_err());
+    }
+}
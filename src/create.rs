@@ -0,0 +1,154 @@
+use crate::Address;
+
+use sha3::{Digest, Keccak256};
+
+/// Init code of the CREATE3 proxy deployed by Solady's and 0xSequence's `CREATE3` libraries.
+///
+/// The proxy deploys whatever init code it is called with using plain `CREATE`, so the final
+/// address only depends on the factory and the salt.
+pub const CREATE3_PROXY_INIT_CODE: [u8; 16] = [
+    0x67, 0x36, 0x3d, 0x3d, 0x37, 0x36, 0x3d, 0x34, 0xf0, 0x3d, 0x52, 0x60, 0x08, 0x60, 0x18, 0xf3,
+];
+
+/// Keccak hash of [`CREATE3_PROXY_INIT_CODE`].
+pub const CREATE3_PROXY_INIT_CODE_HASH: [u8; 32] = [
+    0x21, 0xc3, 0x5d, 0xbe, 0x1b, 0x34, 0x4a, 0x24, 0x88, 0xcf, 0x33, 0x21, 0xd6, 0xce, 0x54, 0x2f,
+    0x8e, 0x9f, 0x30, 0x55, 0x44, 0xff, 0x09, 0xe4, 0x99, 0x3a, 0x62, 0x31, 0x9a, 0x49, 0x7c, 0x1f,
+];
+
+/// Address of a contract deployed with `CREATE` by `deployer` at the given account nonce.
+pub fn create_address(deployer: &Address, nonce: u64) -> Address {
+    // rlp([deployer, nonce]), which always fits a short list
+    let mut rlp = Vec::with_capacity(31);
+    rlp.push(0);
+    rlp.push(0x80 + 20);
+    rlp.extend_from_slice(deployer.as_bytes());
+    match nonce {
+        0 => rlp.push(0x80),
+        1..=0x7f => rlp.push(nonce as u8),
+        _ => {
+            let bytes = nonce.to_be_bytes();
+            let leading = (nonce.leading_zeros() / 8) as usize;
+            rlp.push(0x80 + (8 - leading) as u8);
+            rlp.extend_from_slice(&bytes[leading..]);
+        }
+    }
+    rlp[0] = 0xc0 + (rlp.len() - 1) as u8;
+    address_of(&Keccak256::digest(&rlp))
+}
+
+/// Address of a contract deployed with `CREATE2` (EIP-1014) by `deployer`, given the keccak hash
+/// of its init code.
+pub fn create2_address(deployer: &Address, salt: &[u8; 32], init_code_hash: &[u8; 32]) -> Address {
+    let mut hasher = Keccak256::new();
+    hasher.update([0xff]);
+    hasher.update(deployer.as_bytes());
+    hasher.update(salt);
+    hasher.update(init_code_hash);
+    address_of(&hasher.finalize())
+}
+
+/// Address of a contract deployed through a CREATE3 factory, which is independent of the init code.
+///
+/// The factory `CREATE2`-deploys [`CREATE3_PROXY_INIT_CODE`] with the salt, and the proxy then
+/// deploys the contract as its first `CREATE`. Factories that mix the caller into the salt expect
+/// the already mixed salt here.
+pub fn create3_address(factory: &Address, salt: &[u8; 32]) -> Address {
+    let proxy = create2_address(factory, salt, &CREATE3_PROXY_INIT_CODE_HASH);
+    // a freshly created contract starts with nonce 1 (EIP-161)
+    create_address(&proxy, 1)
+}
+
+/// Arachnid's deterministic deployment proxy, deployed at the same address on most EVM chains
+/// and used by Foundry for `CREATE2` deployments.
+pub const DETERMINISTIC_DEPLOYER: Address = Address([
+    0x4e, 0x59, 0xb4, 0x48, 0x47, 0xb3, 0x79, 0x57, 0x85, 0x88, 0x92, 0x0c, 0xa7, 0x8f, 0xbf, 0x26,
+    0xc0, 0xb4, 0x95, 0x6c,
+]);
+
+/// Address of a contract deployed through [`DETERMINISTIC_DEPLOYER`] with the salt and init code.
+pub fn deterministic_deployment_address(salt: &[u8; 32], init_code: &[u8]) -> Address {
+    let init_code_hash: [u8; 32] = Keccak256::digest(init_code).into();
+    create2_address(&DETERMINISTIC_DEPLOYER, salt, &init_code_hash)
+}
+
+/// Data of the transaction to [`DETERMINISTIC_DEPLOYER`] deploying the init code, which is the
+/// salt followed by the init code without any ABI encoding.
+pub fn deterministic_deployment_data(salt: &[u8; 32], init_code: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(32 + init_code.len());
+    data.extend_from_slice(salt);
+    data.extend_from_slice(init_code);
+    data
+}
+
+fn address_of(hash: &[u8]) -> Address {
+    let mut address = Address::ZERO;
+    address.0.copy_from_slice(&hash[12..]);
+    address
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_address;
+
+    #[test]
+    fn create_address_test() {
+        let deployer = parse_address("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap();
+        assert_eq!(
+            create_address(&deployer, 0),
+            parse_address("0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d").unwrap()
+        );
+        assert_eq!(
+            create_address(&deployer, 1),
+            parse_address("0x343c43a37d37dff08ae8c4a11544c718abb4fcf8").unwrap()
+        );
+        assert_eq!(
+            create_address(&deployer, 2),
+            parse_address("0xf778b86fa74e846c4f0a1fbd1335fe81c00a0c91").unwrap()
+        );
+    }
+
+    #[test]
+    fn create2_address_test() {
+        // first example of EIP-1014
+        let hash: [u8; 32] = Keccak256::digest(&[0x00]).into();
+        assert_eq!(
+            create2_address(&Address::ZERO, &[0; 32], &hash),
+            parse_address("0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38").unwrap()
+        );
+    }
+
+    #[test]
+    fn create3_address_test() {
+        let hash: [u8; 32] = Keccak256::digest(&CREATE3_PROXY_INIT_CODE).into();
+        assert_eq!(hash, CREATE3_PROXY_INIT_CODE_HASH);
+
+        let factory = Address([0x11; 20]);
+        let salt = [0x22; 32];
+        let proxy = create2_address(&factory, &salt, &CREATE3_PROXY_INIT_CODE_HASH);
+        assert_eq!(create3_address(&factory, &salt), create_address(&proxy, 1));
+        assert_ne!(
+            create3_address(&factory, &[0x23; 32]),
+            create3_address(&factory, &salt)
+        );
+    }
+
+    #[test]
+    fn deterministic_deployment_test() {
+        assert_eq!(
+            DETERMINISTIC_DEPLOYER,
+            parse_address("0x4e59b44847b379578588920cA78FbF26c0B4956C").unwrap()
+        );
+        let init_code = [0x60, 0x00, 0x60, 0x00, 0xf3];
+        let data = deterministic_deployment_data(&[0x01; 32], &init_code);
+        assert_eq!(data[..32], [0x01; 32]);
+        assert_eq!(data[32..], init_code);
+
+        let hash: [u8; 32] = Keccak256::digest(&init_code).into();
+        assert_eq!(
+            deterministic_deployment_address(&[0x01; 32], &init_code),
+            create2_address(&DETERMINISTIC_DEPLOYER, &[0x01; 32], &hash)
+        );
+    }
+}
@@ -0,0 +1,136 @@
+// differential checks of the codec against ethabi, run by debug builds with the `ethabi-check`
+// feature. Mismatches panic with both results so they can be reported.
+
+use crate::{hex, EthereumTypes, ParamType};
+
+use ethabi::{ethereum_types::H160, Token, Uint};
+
+use std::fmt::Write;
+
+pub(crate) fn check_encode(tokens: &[EthereumTypes], encoded: &[u8]) {
+    let expected = ethabi::encode(&tokens.iter().map(token).collect::<Vec<_>>());
+    if encoded != expected.as_slice() {
+        panic!(
+            "encode disagrees with ethabi for {:?}:\n{}",
+            tokens,
+            word_diff(encoded, &expected)
+        );
+    }
+}
+
+// only successful decodes are checked, this crate rejects encodings ethabi accepts, like trailing
+// bytes, dirty padding or invalid UTF-8
+pub(crate) fn check_decode(types: &[ParamType], data: &[u8], decoded: &[EthereumTypes]) {
+    let params: Vec<ethabi::ParamType> = types.iter().map(param_type).collect();
+    let decoded: Vec<Token> = decoded.iter().map(token).collect();
+    match ethabi::decode(&params, data) {
+        Ok(expected) if expected == decoded => {}
+        Ok(expected) => panic!(
+            "decode disagrees with ethabi for {} of {}:\n  decoded {:?}\n  ethabi  {:?}",
+            types_name(types),
+            hex::encode_prefixed(data),
+            decoded,
+            expected
+        ),
+        Err(e) => panic!(
+            "decode of {} from {} succeeded, ethabi failed: {}",
+            types_name(types),
+            hex::encode_prefixed(data),
+            e
+        ),
+    }
+}
+
+fn token(token: &EthereumTypes) -> Token {
+    let tokens = |tokens: &[EthereumTypes]| tokens.iter().map(self::token).collect();
+    match token {
+        EthereumTypes::Address(address) => Token::Address(H160(*address)),
+        EthereumTypes::U256(word) => Token::Uint(Uint::from_big_endian(word)),
+        EthereumTypes::I256(word) => Token::Int(Uint::from_big_endian(word)),
+        EthereumTypes::Bool(value) => Token::Bool(*value),
+        EthereumTypes::FixedBytes(bytes) => Token::FixedBytes(bytes.clone()),
+        EthereumTypes::Bytes(bytes) => Token::Bytes(bytes.clone()),
+        EthereumTypes::String(string) => Token::String(string.clone()),
+        EthereumTypes::Array(elements) => Token::Array(tokens(elements)),
+        EthereumTypes::FixedArray(elements) => Token::FixedArray(tokens(elements)),
+        EthereumTypes::Tuple(components) => Token::Tuple(tokens(components)),
+    }
+}
+
+fn param_type(kind: &ParamType) -> ethabi::ParamType {
+    match kind {
+        ParamType::Address => ethabi::ParamType::Address,
+        ParamType::U256 => ethabi::ParamType::Uint(256),
+        ParamType::Uint(bits) => ethabi::ParamType::Uint(*bits),
+        ParamType::Int(bits) => ethabi::ParamType::Int(*bits),
+        ParamType::Bool => ethabi::ParamType::Bool,
+        ParamType::FixedBytes(size) => ethabi::ParamType::FixedBytes(*size),
+        ParamType::Bytes => ethabi::ParamType::Bytes,
+        ParamType::String => ethabi::ParamType::String,
+        ParamType::Array(element) => ethabi::ParamType::Array(Box::new(param_type(element))),
+        ParamType::FixedArray(element, size) => {
+            ethabi::ParamType::FixedArray(Box::new(param_type(element)), *size)
+        }
+        ParamType::Tuple(components) => {
+            ethabi::ParamType::Tuple(components.iter().map(param_type).collect())
+        }
+    }
+}
+
+fn types_name(types: &[ParamType]) -> String {
+    ParamType::Tuple(types.to_vec()).name_as_str().into_owned()
+}
+
+// both encodings word by word, differing words marked
+fn word_diff(encoded: &[u8], expected: &[u8]) -> String {
+    let mut diff = String::new();
+    let words = encoded.len().max(expected.len()).div_ceil(32);
+    for i in 0..words {
+        let word = |data: &[u8]| {
+            data.get(32 * i..data.len().min(32 * (i + 1)))
+                .map_or_else(String::new, hex::encode)
+        };
+        let (ours, theirs) = (word(encoded), word(expected));
+        let marker = if ours == theirs { ' ' } else { '!' };
+        let _ = writeln!(diff, "{} {:#06x} {:<64} {}", marker, 32 * i, ours, theirs);
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode, encode};
+
+    #[test]
+    fn agreeing_test() {
+        // encode and decode run the checks themselves
+        let tokens = vec![
+            EthereumTypes::Address([0x11; 20]),
+            EthereumTypes::I256([0xff; 32]),
+            EthereumTypes::Array(vec![EthereumTypes::Tuple(vec![
+                EthereumTypes::String("gm".to_owned()),
+                EthereumTypes::FixedBytes(vec![0xab; 4]),
+            ])]),
+        ];
+        let types = [
+            ParamType::Address,
+            ParamType::Int(8),
+            ParamType::from_name("(string,bytes4)[]").unwrap(),
+        ];
+        assert_eq!(decode(&types, &encode(&tokens)), Ok(tokens));
+    }
+
+    #[test]
+    #[should_panic(expected = "encode disagrees with ethabi")]
+    fn mismatch_test() {
+        check_encode(&[EthereumTypes::Bool(true)], &[0; 32]);
+    }
+
+    #[test]
+    fn word_diff_test() {
+        let diff = word_diff(&[0; 32], &[1; 64]);
+        assert_eq!(diff.lines().count(), 2);
+        assert!(diff.lines().all(|line| line.starts_with('!')));
+    }
+}
@@ -0,0 +1,251 @@
+use crate::selector::{selector, Selector};
+use crate::{Abi, Address, U256};
+
+use std::collections::HashMap;
+
+/// What a [`FacetCut`] does with its selectors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FacetCutAction {
+    Add = 0,
+    Replace = 1,
+    Remove = 2,
+}
+
+/// A `FacetCut` struct of EIP-2535 `diamondCut` calls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FacetCut {
+    /// The facet providing the functions, zero for [`FacetCutAction::Remove`].
+    pub facet_address: Address,
+    pub action: FacetCutAction,
+    pub function_selectors: Vec<Selector>,
+}
+
+/// A `Facet` struct returned by the `facets()` loupe function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Facet {
+    pub facet_address: Address,
+    pub function_selectors: Vec<Selector>,
+}
+
+/// Encodes `diamondCut((address,uint8,bytes4[])[],address,bytes)` calldata, which applies the
+/// cuts and then delegatecalls `init` with `calldata`, or nothing if `init` is zero.
+pub fn encode_diamond_cut(cuts: &[FacetCut], init: &Address, calldata: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(
+        selector("diamondCut((address,uint8,bytes4[])[],address,bytes)").as_bytes(),
+    );
+
+    // a cut takes the address, action, selectors offset and length words plus one per selector
+    let cut_sizes: Vec<usize> = cuts
+        .iter()
+        .map(|cut| 32 * (4 + cut.function_selectors.len()))
+        .collect();
+    let cuts_size = 32 + 32 * cuts.len() + cut_sizes.iter().sum::<usize>();
+
+    push_word(&mut data, 0x60);
+    push_address(&mut data, init);
+    push_word(&mut data, 0x60 + cuts_size);
+
+    push_word(&mut data, cuts.len());
+    // offsets are relative to the first offset word
+    let mut offset = 32 * cuts.len();
+    for size in &cut_sizes {
+        push_word(&mut data, offset);
+        offset += size;
+    }
+    for cut in cuts {
+        push_address(&mut data, &cut.facet_address);
+        push_word(&mut data, cut.action as usize);
+        push_word(&mut data, 0x60);
+        push_word(&mut data, cut.function_selectors.len());
+        for function_selector in &cut.function_selectors {
+            // fixed size bytes are right padded
+            data.extend_from_slice(function_selector.as_bytes());
+            data.extend_from_slice(&[0; 28]);
+        }
+    }
+
+    push_word(&mut data, calldata.len());
+    data.extend_from_slice(calldata);
+    data.resize(data.len() + (32 - calldata.len() % 32) % 32, 0);
+    data
+}
+
+/// Decodes the return data of `facets()`, which is a `(address,bytes4[])[]`.
+pub fn decode_facets(data: &[u8]) -> Result<Vec<Facet>, String> {
+    let array = read_offset(data, 0, 0)?;
+    let len = read_usize(data, array)?;
+    let items = array + 32;
+    let mut facets = Vec::with_capacity(len.min(data.len() / 32));
+    for i in 0..len {
+        let facet = read_offset(data, items, items + 32 * i)?;
+        let facet_address = read_address(data, facet)?;
+        let selectors = read_offset(data, facet, facet + 32)?;
+        facets.push(Facet {
+            facet_address,
+            function_selectors: read_selectors(data, selectors)?,
+        });
+    }
+    Ok(facets)
+}
+
+/// Decodes the return data of `facetFunctionSelectors(address)`, which is a `bytes4[]`.
+pub fn decode_facet_function_selectors(data: &[u8]) -> Result<Vec<Selector>, String> {
+    let array = read_offset(data, 0, 0)?;
+    read_selectors(data, array)
+}
+
+/// Maps every selector of the diamond to the facet implementing it, e.g. to pick the facet ABI
+/// for decoding a call.
+pub fn selector_facets(facets: &[Facet]) -> HashMap<Selector, Address> {
+    facets
+        .iter()
+        .flat_map(|facet| {
+            facet
+                .function_selectors
+                .iter()
+                .map(move |s| (*s, facet.facet_address))
+        })
+        .collect()
+}
+
+/// Combines the ABIs of a diamond's facets into one, functions that more than one facet declares
+/// are kept once.
+pub fn merge_facet_abis<'a, I: IntoIterator<Item = Abi<'a>>>(abis: I) -> Abi<'a> {
+    let mut merged = Abi::default();
+    for abi in abis {
+        merged.merge(abi);
+    }
+    merged
+}
+
+fn push_word(data: &mut Vec<u8>, value: usize) {
+    data.extend_from_slice(&U256::from(value as u64).0);
+}
+
+fn push_address(data: &mut Vec<u8>, address: &Address) {
+    data.extend_from_slice(&address.to_word());
+}
+
+fn read_word(data: &[u8], at: usize) -> Result<&[u8], String> {
+    at.checked_add(32)
+        .and_then(|end| data.get(at..end))
+        .ok_or_else(|| format!("Encoded data is too short to read a word at {}.", at))
+}
+
+fn read_usize(data: &[u8], at: usize) -> Result<usize, String> {
+    let word = read_word(data, at)?;
+    if word[..28].iter().any(|b| *b != 0) {
+        return Err(format!("Encoded length or offset at {} is too large.", at));
+    }
+    Ok(u32::from_be_bytes([word[28], word[29], word[30], word[31]]) as usize)
+}
+
+// an offset read at `at`, relative to `base`
+fn read_offset(data: &[u8], base: usize, at: usize) -> Result<usize, String> {
+    base.checked_add(read_usize(data, at)?)
+        .ok_or_else(|| "Encoded offset overflows.".to_owned())
+}
+
+fn read_address(data: &[u8], at: usize) -> Result<Address, String> {
+    let mut word = [0_u8; 32];
+    word.copy_from_slice(read_word(data, at)?);
+    Address::from_word(&word)
+        .ok_or_else(|| "Encoded address has non-zero padding bytes.".to_owned())
+}
+
+fn read_selectors(data: &[u8], array: usize) -> Result<Vec<Selector>, String> {
+    let len = read_usize(data, array)?;
+    let mut selectors = Vec::with_capacity(len.min(data.len() / 32));
+    for i in 0..len {
+        let word = read_word(data, array + 32 * (i + 1))?;
+        selectors.push(Selector([word[0], word[1], word[2], word[3]]));
+    }
+    Ok(selectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    fn words(words: &[&str]) -> Vec<u8> {
+        words
+            .iter()
+            .flat_map(|w| hex::decode(&format!("{:0>64}", w)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn encode_diamond_cut_test() {
+        let cut = FacetCut {
+            facet_address: Address([0xbe; 20]),
+            action: FacetCutAction::Add,
+            function_selectors: vec![Selector([0xa9, 0x05, 0x9c, 0xbb])],
+        };
+        let data = encode_diamond_cut(&[cut], &Address::ZERO, &[0x01]);
+        assert_eq!(data[..4], [0x1f, 0x93, 0x1c, 0x1c]);
+        assert_eq!(
+            data[4..],
+            words(&[
+                "60",
+                "0",
+                "140",
+                "1",
+                "20",
+                "bebebebebebebebebebebebebebebebebebebebe",
+                "0",
+                "60",
+                "1",
+                "a9059cbb00000000000000000000000000000000000000000000000000000000",
+                "1",
+                "0100000000000000000000000000000000000000000000000000000000000000",
+            ])[..]
+        );
+    }
+
+    #[test]
+    fn decode_facets_test() {
+        let data = words(&[
+            "20",
+            "1",
+            "20",
+            "bebebebebebebebebebebebebebebebebebebebe",
+            "40",
+            "2",
+            "a9059cbb00000000000000000000000000000000000000000000000000000000",
+            "70a0823100000000000000000000000000000000000000000000000000000000",
+        ]);
+        let facets = decode_facets(&data).unwrap();
+        assert_eq!(
+            facets,
+            vec![Facet {
+                facet_address: Address([0xbe; 20]),
+                function_selectors: vec![
+                    Selector([0xa9, 0x05, 0x9c, 0xbb]),
+                    Selector([0x70, 0xa0, 0x82, 0x31])
+                ],
+            }]
+        );
+        let facets_of = selector_facets(&facets);
+        assert_eq!(
+            facets_of[&Selector([0x70, 0xa0, 0x82, 0x31])],
+            Address([0xbe; 20])
+        );
+        assert!(decode_facets(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn decode_facet_function_selectors_test() {
+        let data = words(&[
+            "20",
+            "1",
+            "a9059cbb00000000000000000000000000000000000000000000000000000000",
+        ]);
+        assert_eq!(
+            decode_facet_function_selectors(&data).unwrap(),
+            vec![Selector([0xa9, 0x05, 0x9c, 0xbb])]
+        );
+        assert!(decode_facet_function_selectors(&words(&["20", "ffffffffff"])).is_err());
+    }
+}
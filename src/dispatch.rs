@@ -0,0 +1,220 @@
+use crate::{selector, Abi, Selector};
+
+use sha3::{Digest, Keccak256};
+
+// `DUP1 PUSH4 selector EQ PUSH2 tag JUMPI`, the same holds for the `GT` of a split
+const COMPARISON_GAS: u64 = 22;
+// solc compares linearly up to this many selectors and splits larger sets at the middle one
+const MAX_LINEAR: usize = 4;
+
+/// Position of a function in the selector dispatcher solc generates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DispatchEntry {
+    pub selector: Selector,
+    pub signature: String,
+    /// Comparisons executed before jumping to the function, splits included.
+    pub comparisons: usize,
+    /// Estimated dispatch gas of the comparisons, without the common prologue.
+    pub gas: u64,
+}
+
+/// The functions of the ABI sorted by selector, the order of solc's dispatcher, with the estimated
+/// cost of reaching each of them.
+///
+/// The dispatcher checks up to 4 selectors one after the other, larger sets are split in halves
+/// around the middle selector first. A function is cheaper to call the fewer comparisons precede
+/// it, which renaming it to a smaller selector can achieve, see [`dispatch_suffix`].
+pub fn dispatch_order(abi: &Abi) -> Vec<DispatchEntry> {
+    let mut functions: Vec<(Selector, String)> = abi
+        .functions
+        .iter()
+        .map(|f| (f.selector(), f.signature()))
+        .collect();
+    functions.sort();
+    functions.dedup_by_key(|(selector, _)| *selector);
+
+    let mut comparisons = vec![0; functions.len()];
+    count_comparisons(&mut comparisons, 0);
+    functions
+        .into_iter()
+        .zip(comparisons)
+        .map(|((selector, signature), comparisons)| DispatchEntry {
+            selector,
+            signature,
+            comparisons,
+            gas: COMPARISON_GAS * comparisons as u64,
+        })
+        .collect()
+}
+
+// adds the comparisons to reach each of the sorted selectors, `before` being the splits already
+// passed
+fn count_comparisons(comparisons: &mut [usize], before: usize) {
+    if comparisons.len() > MAX_LINEAR {
+        let (lower, upper) = comparisons.split_at_mut(comparisons.len() / 2);
+        count_comparisons(lower, before + 1);
+        count_comparisons(upper, before + 1);
+    } else {
+        for (i, count) in comparisons.iter_mut().enumerate() {
+            *count = before + i + 1;
+        }
+    }
+}
+
+/// The first `name_<n>` rename of the function, trying `n` up to `attempts`, whose selector is
+/// below `below`, e.g. the smallest selector of a contract to be dispatched first.
+///
+/// ```ignore
+/// let renamed = dispatch_suffix("deposit(uint256)", Selector([0x00, 0x00, 0x10, 0x00]), 1 << 20);
+/// ```
+pub fn dispatch_suffix(signature: &str, below: Selector, attempts: u64) -> Option<String> {
+    let open = signature.find('(')?;
+    let (name, params) = signature.split_at(open);
+    (0..attempts)
+        .map(|n| format!("{}_{}{}", name, n, params))
+        .find(|renamed| selector(renamed) < below)
+}
+
+/// Mines a `name_<suffix>` rename of the function whose selector starts with `prefix`, e.g.
+/// `[0, 0, 0, 0]` for the cheapest possible calldata and dispatch, trying up to `attempts`
+/// base 62 suffixes, on all available cores with the `rayon` feature.
+///
+/// The result is deterministic, the smallest of the matching suffixes in trial order. Each
+/// additional byte of prefix takes 256 times as many attempts on average.
+pub fn mine_selector(signature: &str, prefix: &[u8], attempts: u64) -> Option<String> {
+    assert!(prefix.len() <= 4, "Selectors have 4 bytes");
+    let open = signature.find('(')?;
+    let (name, params) = signature.split_at(open);
+    let matches = |candidate: &mut String, n: u64| {
+        candidate.clear();
+        candidate.push_str(name);
+        push_suffix(candidate, n);
+        candidate.push_str(params);
+        Keccak256::digest(candidate.as_bytes()).starts_with(prefix)
+    };
+    let capacity = signature.len() + 12;
+
+    #[cfg(feature = "rayon")]
+    let found = {
+        use rayon::prelude::*;
+        (0..attempts)
+            .into_par_iter()
+            .map_init(
+                || String::with_capacity(capacity),
+                |candidate, n| (n, matches(candidate, n)),
+            )
+            .find_first(|(_, matched)| *matched)
+            .map(|(n, _)| n)
+    };
+    #[cfg(not(feature = "rayon"))]
+    let found = {
+        let mut candidate = String::with_capacity(capacity);
+        (0..attempts).find(|n| matches(&mut candidate, *n))
+    };
+
+    found.map(|n| {
+        let mut renamed = name.to_owned();
+        push_suffix(&mut renamed, n);
+        renamed + params
+    })
+}
+
+const BASE62: &[u8; 62] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// `_` followed by the base 62 digits of n
+fn push_suffix(name: &mut String, mut n: u64) {
+    name.push('_');
+    let start = name.len();
+    loop {
+        name.insert(start, char::from(BASE62[(n % 62) as usize]));
+        n /= 62;
+        if n == 0 {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abi(names: &[&str]) -> Abi<'static> {
+        let entries: Vec<String> = names
+            .iter()
+            .map(|name| format!(r#"{{"name":"{}","inputs":[]}}"#, name))
+            .collect();
+        let json = format!("[{}]", entries.join(","));
+        serde_json::from_str::<Abi>(&json).unwrap().into_owned()
+    }
+
+    #[test]
+    fn linear_dispatch_test() {
+        let order = dispatch_order(&abi(&["pause", "unpause", "owner"]));
+        let selectors: Vec<Selector> = order.iter().map(|e| e.selector).collect();
+        let mut sorted = selectors.clone();
+        sorted.sort();
+        assert_eq!(selectors, sorted);
+        assert_eq!(
+            order.iter().map(|e| e.comparisons).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert_eq!(order[2].gas, 66);
+    }
+
+    #[test]
+    fn split_dispatch_test() {
+        let names: Vec<String> = (0..10).map(|i| format!("f{}", i)).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let order = dispatch_order(&abi(&names));
+        // 10 functions split into 5 and 5, which split into 2 and 3 each
+        assert_eq!(
+            order.iter().map(|e| e.comparisons).collect::<Vec<_>>(),
+            [3, 4, 3, 4, 5, 3, 4, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn dispatch_suffix_test() {
+        let below = Selector([0x10, 0, 0, 0]);
+        let renamed = dispatch_suffix("deposit(uint256)", below, 1000).unwrap();
+        assert!(renamed.starts_with("deposit_") && renamed.ends_with("(uint256)"));
+        assert!(selector(&renamed) < below);
+        assert_eq!(
+            dispatch_suffix("deposit(uint256)", Selector([0; 4]), 100),
+            None
+        );
+        assert_eq!(dispatch_suffix("deposit", below, 100), None);
+    }
+
+    #[test]
+    fn mine_selector_test() {
+        let mined = mine_selector("transfer(address,uint256)", &[0x00], 100_000).unwrap();
+        assert!(mined.starts_with("transfer_") && mined.ends_with("(address,uint256)"));
+        assert_eq!(selector(&mined).0[0], 0x00);
+
+        // the first match in trial order, whatever the number of threads
+        let sequential = (0..100_000)
+            .map(|n| {
+                let mut name = "transfer".to_owned();
+                push_suffix(&mut name, n);
+                name + "(address,uint256)"
+            })
+            .find(|candidate| selector(candidate).0[0] == 0x00);
+        assert_eq!(Some(mined), sequential);
+
+        assert_eq!(
+            mine_selector("transfer(address,uint256)", &[0; 4], 10),
+            None
+        );
+        assert!(mine_selector("pause()", &[], 1)
+            .unwrap()
+            .starts_with("pause_0"));
+    }
+
+    #[test]
+    fn push_suffix_test() {
+        let mut name = String::from("f");
+        push_suffix(&mut name, 62 * 62 + 61);
+        assert_eq!(name, "f_10Z");
+    }
+}
@@ -0,0 +1,157 @@
+use crate::eip712::{hash_struct, type_hash, typed_data_hash, Eip712Domain};
+use crate::selector::selector;
+use crate::{encode, Address, EthereumTypes, Signature, U256};
+
+const TRANSFER_WITH_AUTHORIZATION_TYPE: &str = "TransferWithAuthorization(address from,address to,uint256 value,uint256 validAfter,uint256 validBefore,bytes32 nonce)";
+const RECEIVE_WITH_AUTHORIZATION_TYPE: &str = "ReceiveWithAuthorization(address from,address to,uint256 value,uint256 validAfter,uint256 validBefore,bytes32 nonce)";
+
+/// An EIP-3009 authorization of a token transfer signed by `from`, e.g. for gasless USDC transfers
+/// submitted by a relayer.
+///
+/// The transfer can be submitted by anyone with `transferWithAuthorization`, or only by `to` with
+/// `receiveWithAuthorization`, which keeps relayers from front-running deposits into contracts.
+/// Both sign a different struct, so the signature determines which of them is callable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferAuthorization {
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    /// Unix time after which the authorization is valid.
+    pub valid_after: U256,
+    /// Unix time before which the authorization is valid.
+    pub valid_before: U256,
+    /// A random nonce, authorizations aren't ordered unlike account nonces.
+    pub nonce: [u8; 32],
+}
+
+impl TransferAuthorization {
+    /// The hash `from` signs to authorize `transferWithAuthorization` on the token with `domain`.
+    pub fn transfer_signing_hash(&self, domain: &Eip712Domain) -> [u8; 32] {
+        typed_data_hash(
+            &domain.separator(),
+            &self.struct_hash(TRANSFER_WITH_AUTHORIZATION_TYPE),
+        )
+    }
+
+    /// The hash `from` signs to authorize `receiveWithAuthorization` on the token with `domain`.
+    pub fn receive_signing_hash(&self, domain: &Eip712Domain) -> [u8; 32] {
+        typed_data_hash(
+            &domain.separator(),
+            &self.struct_hash(RECEIVE_WITH_AUTHORIZATION_TYPE),
+        )
+    }
+
+    /// Encodes
+    /// `transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)`
+    /// calldata.
+    pub fn encode_transfer(&self, signature: &Signature) -> Vec<u8> {
+        self.call(
+            "transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)",
+            signature,
+        )
+    }
+
+    /// Encodes
+    /// `receiveWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)`
+    /// calldata, which has to be sent by `to`.
+    pub fn encode_receive(&self, signature: &Signature) -> Vec<u8> {
+        self.call(
+            "receiveWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)",
+            signature,
+        )
+    }
+
+    fn struct_hash(&self, encoded_type: &str) -> [u8; 32] {
+        hash_struct(
+            &type_hash(encoded_type),
+            &[
+                self.from.to_word(),
+                self.to.to_word(),
+                self.value.0,
+                self.valid_after.0,
+                self.valid_before.0,
+                self.nonce,
+            ],
+        )
+    }
+
+    fn call(&self, function: &str, signature: &Signature) -> Vec<u8> {
+        let mut data = selector(function).0.to_vec();
+        data.extend_from_slice(&encode(&[
+            self.from.into(),
+            self.to.into(),
+            self.value.into(),
+            self.valid_after.into(),
+            self.valid_before.into(),
+            EthereumTypes::FixedBytes(self.nonce.to_vec()),
+            U256::from(u64::from(signature.v())).into(),
+            EthereumTypes::FixedBytes(signature.r.0.to_vec()),
+            EthereumTypes::FixedBytes(signature.s.0.to_vec()),
+        ]));
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hex, ChainId};
+
+    fn authorization() -> TransferAuthorization {
+        TransferAuthorization {
+            from: Address([0x11; 20]),
+            to: Address([0x22; 20]),
+            value: U256::from(1_000_000_u64),
+            valid_after: U256::ZERO,
+            valid_before: U256::from(1_700_000_000_u64),
+            nonce: [0xab; 32],
+        }
+    }
+
+    fn usdc() -> Eip712Domain {
+        Eip712Domain {
+            name: Some("USD Coin".to_owned()),
+            version: Some("2".to_owned()),
+            chain_id: Some(ChainId::MAINNET),
+            verifying_contract: Some(Address([0xa0; 20])),
+            salt: None,
+        }
+    }
+
+    #[test]
+    fn signing_hash_test() {
+        // the typehashes declared by the FiatToken contracts
+        assert_eq!(
+            hex::encode(type_hash(TRANSFER_WITH_AUTHORIZATION_TYPE)),
+            "7c7c6cdb67a18743f49ec6fa9b35f50d52ed05cbed4cc592e13b44501c1a2267"
+        );
+        assert_eq!(
+            hex::encode(type_hash(RECEIVE_WITH_AUTHORIZATION_TYPE)),
+            "d099cc98ef71107a616c4f0f941f04c322d8e254fe26b3c6668db87aae413de8"
+        );
+
+        let authorization = authorization();
+        let transfer = authorization.transfer_signing_hash(&usdc());
+        assert_ne!(transfer, authorization.receive_signing_hash(&usdc()));
+        let mut other = usdc();
+        other.chain_id = Some(ChainId(8453));
+        assert_ne!(transfer, authorization.transfer_signing_hash(&other));
+    }
+
+    #[test]
+    fn encode_test() {
+        let signature = Signature {
+            r: U256([0x01; 32]),
+            s: U256([0x02; 32]),
+            y_parity: true,
+        };
+        let data = authorization().encode_transfer(&signature);
+        assert_eq!(data[..4], [0xe3, 0xee, 0x16, 0x0e]);
+        assert_eq!(data.len(), 4 + 9 * 32);
+        assert_eq!(data[4 + 6 * 32..4 + 7 * 32], U256::from(28_u64).0);
+        assert_eq!(data[4 + 8 * 32..], [0x02; 32]);
+
+        let data = authorization().encode_receive(&signature);
+        assert_eq!(data[..4], [0xef, 0x55, 0xbe, 0xc6]);
+    }
+}
@@ -0,0 +1,266 @@
+use crate::selector::selector;
+use crate::{decode, Address, ChainId, EthereumTypes, ParamType, U256};
+
+use sha3::{Digest, Keccak256};
+
+use std::convert::TryFrom;
+
+/// The `EIP712Domain` of typed data, fields that are `None` are left out of the domain type.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Eip712Domain {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<ChainId>,
+    pub verifying_contract: Option<Address>,
+    pub salt: Option<[u8; 32]>,
+}
+
+impl Eip712Domain {
+    /// The domain separator, i.e. the struct hash of the domain.
+    pub fn separator(&self) -> [u8; 32] {
+        let mut fields = Vec::new();
+        let mut members = Vec::new();
+        if let Some(name) = &self.name {
+            fields.push("string name");
+            members.push(keccak256(name.as_bytes()));
+        }
+        if let Some(version) = &self.version {
+            fields.push("string version");
+            members.push(keccak256(version.as_bytes()));
+        }
+        if let Some(chain_id) = self.chain_id {
+            fields.push("uint256 chainId");
+            members.push(U256::from(chain_id.as_u64()).0);
+        }
+        if let Some(verifying_contract) = &self.verifying_contract {
+            fields.push("address verifyingContract");
+            members.push(verifying_contract.to_word());
+        }
+        if let Some(salt) = self.salt {
+            fields.push("bytes32 salt");
+            members.push(salt);
+        }
+        let type_hash = keccak256(format!("EIP712Domain({})", fields.join(",")).as_bytes());
+        hash_struct(&type_hash, &members)
+    }
+
+    /// Encodes the EIP-5267 `eip712Domain()` calldata, which contracts like OpenZeppelin's
+    /// `EIP712` answer with their domain.
+    pub fn encode_eip712_domain_call() -> Vec<u8> {
+        selector("eip712Domain()").0.to_vec()
+    }
+
+    /// Decodes the output of `eip712Domain()`, the
+    /// `(bytes1 fields, string name, string version, uint256 chainId, address verifyingContract,
+    /// bytes32 salt, uint256[] extensions)` tuple.
+    ///
+    /// The bits of `fields` tell which of the domain fields are used. Extensions aren't defined by
+    /// any EIP yet, domains with extensions are rejected as their separator is unknown.
+    pub fn from_eip712_domain_output(data: &[u8]) -> Result<Self, String> {
+        let types = [
+            ParamType::FixedBytes(1),
+            ParamType::String,
+            ParamType::String,
+            ParamType::U256,
+            ParamType::Address,
+            ParamType::FixedBytes(32),
+            ParamType::Array(Box::new(ParamType::U256)),
+        ];
+        let [fields, name, version, chain_id, verifying_contract, salt, extensions] =
+            <[EthereumTypes; 7]>::try_from(decode(&types, data)?)
+                .map_err(|values| format!("Expected 7 domain fields, found {}.", values.len()))?;
+        let fields = match fields {
+            EthereumTypes::FixedBytes(fields) => fields[0],
+            _ => return Err("Invalid domain fields.".to_owned()),
+        };
+        if fields & 0xe0 != 0 {
+            return Err(format!("Unknown domain fields {:#04x}.", fields));
+        }
+        if !matches!(&extensions, EthereumTypes::Array(e) if e.is_empty()) {
+            return Err("Domain extensions are not supported.".to_owned());
+        }
+        let used = |bit: u8| fields & (1 << bit) != 0;
+
+        let mut domain = Self::default();
+        if let (true, EthereumTypes::String(name)) = (used(0), name) {
+            domain.name = Some(name);
+        }
+        if let (true, EthereumTypes::String(version)) = (used(1), version) {
+            domain.version = Some(version);
+        }
+        if used(2) {
+            let chain_id = U256(chain_id.value_as_u256());
+            if chain_id.leading_zeros() < 192 {
+                return Err(format!("Chain id {} doesn't fit into 64 bits.", chain_id));
+            }
+            let mut bytes = [0_u8; 8];
+            bytes.copy_from_slice(&chain_id.0[24..]);
+            domain.chain_id = Some(ChainId::from(u64::from_be_bytes(bytes)));
+        }
+        if let (true, EthereumTypes::Address(verifying_contract)) = (used(3), verifying_contract) {
+            domain.verifying_contract = Some(Address(verifying_contract));
+        }
+        if used(4) {
+            domain.salt = Some(salt.value_as_u256());
+        }
+        Ok(domain)
+    }
+}
+
+/// The keccak hash of an encoded type like `Mail(Person from,string contents)Person(string name)`.
+#[inline]
+pub fn type_hash(encoded_type: &str) -> [u8; 32] {
+    keccak256(encoded_type.as_bytes())
+}
+
+/// `keccak256(typeHash ++ encodeData(struct))`, with the members already encoded as words, see
+/// [`encode_member`].
+pub fn hash_struct(type_hash: &[u8; 32], members: &[[u8; 32]]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(type_hash);
+    for member in members {
+        hasher.update(member);
+    }
+    hasher.finalize().into()
+}
+
+/// Encodes a struct member as the word it contributes to `encodeData`.
+///
+/// Single word values are encoded as in the ABI, `bytes` and `string` as the hash of their content
+/// and arrays as the hash of their encoded elements. Nested structs can't be told apart from
+/// tuples without their type, pass their [`hash_struct`] as a `bytes32` value instead.
+pub fn encode_member(value: &EthereumTypes) -> [u8; 32] {
+    match value {
+        EthereumTypes::Bytes(bytes) => keccak256(bytes),
+        EthereumTypes::String(string) => keccak256(string.as_bytes()),
+        EthereumTypes::Array(elements) | EthereumTypes::FixedArray(elements) => {
+            let words: Vec<[u8; 32]> = elements.iter().map(encode_member).collect();
+            keccak256(&words.concat())
+        }
+        EthereumTypes::Tuple(_) => panic!("Nested structs have to be passed as their hash"),
+        value => value.value_as_u256(),
+    }
+}
+
+/// The hash signed for typed data, `keccak256(0x1901 ++ domainSeparator ++ hashStruct(message))`.
+pub fn typed_data_hash(domain_separator: &[u8; 32], struct_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update([0x19, 0x01]);
+    hasher.update(domain_separator);
+    hasher.update(struct_hash);
+    hasher.finalize().into()
+}
+
+/// Keccak hash of the members encoded and concatenated, used for arrays of structs.
+pub(crate) fn hash_words(words: &[[u8; 32]]) -> [u8; 32] {
+    keccak256(&words.concat())
+}
+
+pub(crate) fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hex, parse_address};
+
+    // the `Mail` example of EIP-712
+    fn person(name: &str, wallet: &str) -> [u8; 32] {
+        hash_struct(
+            &type_hash("Person(string name,address wallet)"),
+            &[
+                encode_member(&EthereumTypes::String(name.to_owned())),
+                encode_member(&parse_address(wallet).unwrap().into()),
+            ],
+        )
+    }
+
+    #[test]
+    fn mail_test() {
+        let domain = Eip712Domain {
+            name: Some("Ether Mail".to_owned()),
+            version: Some("1".to_owned()),
+            chain_id: Some(ChainId::MAINNET),
+            verifying_contract: Some(
+                parse_address("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC").unwrap(),
+            ),
+            salt: None,
+        };
+        assert_eq!(
+            hex::encode(domain.separator()),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+
+        let mail = hash_struct(
+            &type_hash(
+                "Mail(Person from,Person to,string contents)Person(string name,address wallet)",
+            ),
+            &[
+                person("Cow", "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+                person("Bob", "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"),
+                encode_member(&EthereumTypes::String("Hello, Bob!".to_owned())),
+            ],
+        );
+        assert_eq!(
+            hex::encode(mail),
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+        );
+        assert_eq!(
+            hex::encode(typed_data_hash(&domain.separator(), &mail)),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+
+    #[test]
+    fn encode_member_test() {
+        let array = EthereumTypes::Array(vec![EthereumTypes::Bool(true)]);
+        let mut word = [0_u8; 32];
+        word[31] = 1;
+        assert_eq!(encode_member(&array), keccak256(&word));
+        assert_eq!(encode_member(&EthereumTypes::Bytes(vec![])), keccak256(&[]));
+    }
+
+    #[test]
+    fn eip712_domain_output_test() {
+        assert_eq!(
+            Eip712Domain::encode_eip712_domain_call(),
+            [0x84, 0xb0, 0x19, 0x6e]
+        );
+        let output = |fields: u8, extensions: Vec<EthereumTypes>| {
+            crate::encode(&[
+                EthereumTypes::FixedBytes(vec![fields]),
+                EthereumTypes::String("Ether Mail".to_owned()),
+                EthereumTypes::String("1".to_owned()),
+                U256::ONE.into(),
+                Address([0xcc; 20]).into(),
+                EthereumTypes::FixedBytes(vec![0; 32]),
+                EthereumTypes::Array(extensions),
+            ])
+        };
+        // name, version, chainId and verifyingContract
+        let domain = Eip712Domain::from_eip712_domain_output(&output(0x0f, vec![])).unwrap();
+        assert_eq!(
+            domain,
+            Eip712Domain {
+                name: Some("Ether Mail".to_owned()),
+                version: Some("1".to_owned()),
+                chain_id: Some(ChainId::MAINNET),
+                verifying_contract: Some(Address([0xcc; 20])),
+                salt: None,
+            }
+        );
+        assert_eq!(
+            hex::encode(domain.separator()),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+        let domain = Eip712Domain::from_eip712_domain_output(&output(0x11, vec![])).unwrap();
+        assert_eq!((domain.version, domain.salt), (None, Some([0; 32])));
+
+        assert!(Eip712Domain::from_eip712_domain_output(&output(0x2f, vec![])).is_err());
+        assert!(
+            Eip712Domain::from_eip712_domain_output(&output(0x0f, vec![U256::ONE.into()])).is_err()
+        );
+        assert!(Eip712Domain::from_eip712_domain_output(&[]).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{registry, Abi, OwnedAbi};
+
+use std::path::Path;
+
+/// Embeds an ABI json file into the binary, evaluating to a `&'static OwnedAbi` parsed on first
+/// use.
+///
+/// ```ignore
+/// let abi = include_abi!("../abi/IERC20.json");
+/// let calldata = transaction_with_abi(abi, "balanceOf", [owner.into()])?;
+/// ```
+///
+/// The path is relative to the file invoking the macro, like for `include_str!`. Plain ABI arrays
+/// and solc, Hardhat or Foundry artifacts holding the ABI in their `abi` field are accepted, the
+/// parsed ABI borrows its strings from the embedded json. Panics on first use if the file isn't a
+/// valid ABI.
+#[macro_export]
+macro_rules! include_abi {
+    ($path:expr $(,)?) => {{
+        static ABI: ::std::sync::OnceLock<$crate::OwnedAbi> = ::std::sync::OnceLock::new();
+        ABI.get_or_init(|| $crate::__private::parse_embedded_abi(include_str!($path), $path))
+    }};
+}
+
+// used by `include_abi!`, the json lives as long as the binary so nothing has to be copied
+#[doc(hidden)]
+pub fn parse_embedded_abi(json: &'static str, path: &str) -> OwnedAbi {
+    let abi = Abi::from_str(json).unwrap_or_else(|e| panic!("{} is not a valid ABI: {}", path, e));
+    registry::register_loaded(Path::new(path), &abi);
+    abi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transaction, transaction_with_abi, EthereumTypes};
+
+    use std::borrow::Cow;
+
+    #[test]
+    fn include_abi_test() {
+        // the ABI is parsed once per invocation site
+        let abis: Vec<&OwnedAbi> = (0..2).map(|_| include_abi!("rust_abi.json")).collect();
+        assert!(std::ptr::eq(abis[0], abis[1]));
+        let abi = abis[0];
+        let balance_of = abi.function("balanceOf").unwrap();
+        // the strings are borrowed from the embedded json
+        assert!(matches!(balance_of.name, Cow::Borrowed(_)));
+
+        let arguments = [EthereumTypes::Address([0x11; 20])];
+        assert_eq!(
+            transaction_with_abi(abi, "balanceOf", arguments.clone()),
+            transaction(Path::new("src/rust_abi.json"), "balanceOf", arguments)
+        );
+    }
+
+    #[test]
+    fn parse_embedded_abi_test() {
+        let artifact = r#"{"abi":[{"type":"event","name":"Paused","inputs":[]}],"bytecode":"0x"}"#;
+        assert_eq!(
+            parse_embedded_abi(artifact, "Pausable.json").events[0].name,
+            "Paused"
+        );
+        assert!(parse_embedded_abi("[]", "Empty.json").functions.is_empty());
+        assert!(std::panic::catch_unwind(|| parse_embedded_abi("{}", "Invalid.json")).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{encode, selector, EthereumTypes, Selector};
+
+/// Custom errors of a contract as a Rust type, usually implemented with `#[derive(EthError)]`.
+///
+/// ```ignore
+/// #[derive(EthError)]
+/// enum TokenError {
+///     #[eth_error(name = "ERC20InsufficientBalance")]
+///     InsufficientBalance { sender: Address, balance: U256, needed: U256 },
+///     Paused,
+/// }
+/// ```
+///
+/// A struct is a single error, an enum one error per variant. The fields are the error parameters,
+/// errors are named after the struct or the variant unless renamed with `#[eth_error(name = "…")]`.
+pub trait EthError: Sized {
+    /// Canonical signatures of the errors, e.g. `ERC20InsufficientBalance(address,uint256,uint256)`.
+    fn signatures() -> Vec<String>;
+
+    fn selectors() -> Vec<Selector> {
+        Self::signatures().iter().map(|s| selector(s)).collect()
+    }
+
+    /// Revert data of the error, its selector followed by the ABI encoded parameters.
+    fn encode(&self) -> Vec<u8>;
+
+    /// Fails if the revert data isn't one of the errors or can't be decoded.
+    fn decode(revert_data: &[u8]) -> Result<Self, String>;
+}
+
+// used by the derive to encode the revert data of an error
+#[doc(hidden)]
+pub fn encode_error(signature: &str, tokens: &[EthereumTypes]) -> Vec<u8> {
+    let mut data = selector(signature).0.to_vec();
+    data.extend_from_slice(&encode(tokens));
+    data
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::*;
+    use crate::{hex, Address, EthError, U256};
+
+    #[derive(Clone, Debug, PartialEq, EthError)]
+    enum TokenError {
+        #[eth_error(name = "ERC20InsufficientBalance")]
+        InsufficientBalance {
+            sender: Address,
+            balance: U256,
+            needed: U256,
+        },
+        #[eth_error(name = "ERC20InvalidReceiver")]
+        InvalidReceiver(Address),
+        EnforcedPause,
+    }
+
+    #[derive(Debug, PartialEq, EthError)]
+    struct Unauthorized {
+        account: Address,
+        reason: String,
+    }
+
+    #[test]
+    fn derive_error_enum_test() {
+        assert_eq!(
+            TokenError::signatures(),
+            vec![
+                "ERC20InsufficientBalance(address,uint256,uint256)",
+                "ERC20InvalidReceiver(address)",
+                "EnforcedPause()"
+            ]
+        );
+        assert_eq!(TokenError::selectors()[0], [0xe4, 0x50, 0xd3, 0x8c]);
+
+        let error = TokenError::InsufficientBalance {
+            sender: Address([0x11; 20]),
+            balance: U256::ONE,
+            needed: U256::from(2_u64),
+        };
+        let revert_data = error.encode();
+        assert_eq!(
+            hex::encode(&revert_data),
+            concat!(
+                "e450d38c",
+                "0000000000000000000000001111111111111111111111111111111111111111",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "0000000000000000000000000000000000000000000000000000000000000002"
+            )
+        );
+        for error in [
+            error,
+            TokenError::InvalidReceiver(Address::ZERO),
+            TokenError::EnforcedPause,
+        ] {
+            assert_eq!(TokenError::decode(&error.encode()), Ok(error));
+        }
+
+        assert!(TokenError::decode(&[0xe4, 0x50]).is_err());
+        assert!(TokenError::decode(&[0xde, 0xad, 0xbe, 0xef]).is_err());
+        // a known selector with truncated parameters
+        assert!(TokenError::decode(&revert_data[..40]).is_err());
+    }
+
+    #[test]
+    fn derive_error_struct_test() {
+        assert_eq!(
+            Unauthorized::signatures(),
+            vec!["Unauthorized(address,string)"]
+        );
+        let error = Unauthorized {
+            account: Address([0x22; 20]),
+            reason: "not the owner".to_owned(),
+        };
+        assert_eq!(Unauthorized::decode(&error.encode()), Ok(error));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{decode, event_topic, AbiType, Log};
+
+/// A Rust type decoded from the logs of an event, usually implemented with `#[derive(EthEvent)]`.
+///
+/// ```ignore
+/// #[derive(EthEvent)]
+/// struct Transfer {
+///     #[indexed]
+///     from: Address,
+///     #[indexed]
+///     to: Address,
+///     value: U256,
+/// }
+/// ```
+///
+/// The fields are the event parameters in declaration order. The event is named after the struct
+/// unless renamed with `#[event(name = "…")]`. Indexed parameters have to be value types, indexed
+/// strings, bytes and arrays are only stored as their hash.
+pub trait EthEvent: Sized {
+    /// Number of indexed parameters, which are stored in topics 1 to 3.
+    const INDEXED: usize;
+
+    /// Canonical signature of the event, e.g. `Transfer(address,address,uint256)`.
+    fn signature() -> String;
+
+    fn topic0() -> [u8; 32] {
+        event_topic(&Self::signature())
+    }
+
+    /// Whether the log was emitted for the event, i.e. its event topic and number of topics match.
+    fn matches(log: &Log) -> bool {
+        log.topics.len() == 1 + Self::INDEXED && log.topics[0] == Self::topic0()
+    }
+
+    /// Fails if the log doesn't match the event or its data can't be decoded.
+    fn decode(log: &Log) -> Result<Self, String>;
+}
+
+// used by the derive to decode an indexed parameter
+#[doc(hidden)]
+pub fn decode_topic<T: AbiType>(topic: &[u8; 32]) -> Result<T, String> {
+    let kind = T::param_type();
+    if kind.is_dynamic() {
+        return Err(format!(
+            "Indexed {} parameters are stored as their hash and can't be decoded.",
+            kind.name_as_str()
+        ));
+    }
+    let mut tokens = decode(&[kind], topic)?;
+    T::from_token(tokens.remove(0))
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::*;
+    use crate::{encode, Address, EthEvent, Uint, U256};
+
+    #[derive(Debug, PartialEq, EthEvent)]
+    struct Transfer {
+        #[indexed]
+        from: Address,
+        #[indexed]
+        to: Address,
+        value: U256,
+    }
+
+    #[derive(Debug, PartialEq, EthEvent)]
+    #[event(name = "Sync")]
+    struct Reserves {
+        reserve0: Uint<112>,
+        reserve1: Uint<112>,
+    }
+
+    fn transfer_log() -> Log {
+        Log {
+            address: Address([0xaa; 20]),
+            topics: vec![
+                event_topic("Transfer(address,address,uint256)"),
+                Address([0x11; 20]).to_word(),
+                Address([0x22; 20]).to_word(),
+            ],
+            data: U256::from(1000_u64).0.to_vec(),
+        }
+    }
+
+    #[test]
+    fn derive_event_test() {
+        assert_eq!(Transfer::INDEXED, 2);
+        assert_eq!(Transfer::signature(), "Transfer(address,address,uint256)");
+        let log = transfer_log();
+        assert!(Transfer::matches(&log));
+        assert_eq!(
+            Transfer::decode(&log),
+            Ok(Transfer {
+                from: Address([0x11; 20]),
+                to: Address([0x22; 20]),
+                value: U256::from(1000_u64),
+            })
+        );
+
+        // an ERC-721 transfer has the same topic0 but indexes the token id as well
+        let mut nft = log.clone();
+        nft.topics.push(U256::ONE.0);
+        assert!(!Transfer::matches(&nft));
+        assert!(Transfer::decode(&nft).is_err());
+    }
+
+    #[test]
+    fn renamed_event_test() {
+        assert_eq!(Reserves::signature(), "Sync(uint112,uint112)");
+        let log = Log {
+            address: Address::ZERO,
+            topics: vec![Reserves::topic0()],
+            data: encode(&[U256::ONE.into(), U256::from(2_u64).into()]),
+        };
+        let reserves = Reserves::decode(&log).unwrap();
+        assert_eq!(reserves.reserve0, Uint::new(U256::ONE).unwrap());
+        assert_eq!(reserves.reserve1.value(), U256::from(2_u64));
+    }
+}
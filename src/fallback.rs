@@ -0,0 +1,155 @@
+use crate::{EthereumTypes, Event, Function, Log, OwnedAbi};
+
+/// Decodes calls and logs with the first of several ABIs that matches, e.g. the ABIs of a proxy,
+/// its implementation and the common token standards in that order.
+///
+/// An ABI matches if it declares the selector or event topic and the data decodes with it, so an
+/// ABI sharing a selector by accident doesn't stop the later ones from being tried.
+#[derive(Clone, Debug, Default)]
+pub struct FallbackDecoder {
+    abis: Vec<(String, OwnedAbi)>,
+}
+
+/// A call decoded by a [`FallbackDecoder`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedCall<'d> {
+    /// Name of the ABI that decoded the call.
+    pub abi: &'d str,
+    pub function: &'d Function<'static>,
+    pub arguments: Vec<EthereumTypes>,
+}
+
+/// A log decoded by a [`FallbackDecoder`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedLog<'d> {
+    /// Name of the ABI that decoded the log.
+    pub abi: &'d str,
+    pub event: &'d Event<'static>,
+    pub params: Vec<EthereumTypes>,
+}
+
+impl FallbackDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an ABI with a lower priority than the ones added before.
+    pub fn abi(mut self, name: &str, abi: OwnedAbi) -> Self {
+        self.abis.push((name.to_owned(), abi));
+        self
+    }
+
+    /// Names of the ABIs in priority order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.abis.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn decode_call(&self, calldata: &[u8]) -> Result<DecodedCall<'_>, String> {
+        for (name, abi) in self.abis.iter() {
+            for function in abi.functions.iter() {
+                if let Ok(arguments) = function.decode_input(calldata) {
+                    return Ok(DecodedCall {
+                        abi: name,
+                        function,
+                        arguments,
+                    });
+                }
+            }
+        }
+        Err(match calldata.get(..4) {
+            Some(selector) => format!(
+                "None of the ABIs decodes a call with selector {}.",
+                crate::hex::encode_prefixed(selector)
+            ),
+            None => "Calldata is shorter than a selector.".to_owned(),
+        })
+    }
+
+    pub fn decode_log(&self, log: &Log) -> Result<DecodedLog<'_>, String> {
+        for (name, abi) in self.abis.iter() {
+            for event in abi.events.iter() {
+                if let Ok(params) = event.decode_log(log) {
+                    return Ok(DecodedLog {
+                        abi: name,
+                        event,
+                        params,
+                    });
+                }
+            }
+        }
+        Err("None of the ABIs decodes the log.".to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode, selector, Abi, Address, U256};
+
+    fn abi(json: &str) -> OwnedAbi {
+        serde_json::from_str::<Abi>(json).unwrap().into_owned()
+    }
+
+    fn decoder() -> FallbackDecoder {
+        let proxy = abi(r#"[{"name":"upgradeTo","inputs":[{"type":"address"}]}]"#);
+        let implementation = abi(r#"[{"name":"mint","inputs":[{"type":"uint256"}]}]"#);
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        FallbackDecoder::new()
+            .abi("proxy", proxy)
+            .abi("implementation", implementation)
+            .abi("erc20", abi(&json))
+    }
+
+    #[test]
+    fn decode_call_test() {
+        let decoder = decoder();
+        assert_eq!(
+            decoder.names().collect::<Vec<_>>(),
+            ["proxy", "implementation", "erc20"]
+        );
+
+        let mut calldata = selector("mint(uint256)").0.to_vec();
+        calldata.extend_from_slice(&U256::ONE.0);
+        let call = decoder.decode_call(&calldata).unwrap();
+        assert_eq!(
+            (call.abi, call.function.name.as_ref()),
+            ("implementation", "mint")
+        );
+
+        let mut calldata = selector("transfer(address,uint256)").0.to_vec();
+        calldata.extend_from_slice(&encode(&[
+            Address([0x11; 20]).into(),
+            U256::from(0x1000_u64).into(),
+        ]));
+        let call = decoder.decode_call(&calldata).unwrap();
+        assert_eq!(call.abi, "erc20");
+        assert_eq!(call.function.signature(), "transfer(address,uint256)");
+        assert_eq!(call.arguments[0], EthereumTypes::Address([0x11; 20]));
+
+        assert!(decoder.decode_call(&[0xde, 0xad, 0xbe, 0xef]).is_err());
+        // a known selector with truncated arguments
+        assert!(decoder.decode_call(&calldata[..20]).is_err());
+        assert!(decoder.decode_call(&[0xde]).is_err());
+    }
+
+    #[test]
+    fn decode_log_test() {
+        let decoder = decoder();
+        let log = Log {
+            address: Address::ZERO,
+            topics: vec![
+                crate::event_topic("Transfer(address,address,uint256)"),
+                Address([0x11; 20]).to_word(),
+                Address([0x22; 20]).to_word(),
+            ],
+            data: U256::ONE.0.to_vec(),
+        };
+        let decoded = decoder.decode_log(&log).unwrap();
+        assert_eq!(
+            (decoded.abi, decoded.event.name.as_ref()),
+            ("erc20", "Transfer")
+        );
+        assert_eq!(decoded.params.len(), 3);
+        assert!(FallbackDecoder::new().decode_log(&log).is_err());
+    }
+}
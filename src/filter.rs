@@ -0,0 +1,213 @@
+use crate::bloom::Bloom;
+use crate::{hex, Address, Event, Log};
+
+use serde_json::{json, Value};
+
+/// Maximum number of topics of a log.
+pub const MAX_TOPICS: usize = 4;
+
+/// Client-side log matcher with the semantics of `eth_getLogs` filters.
+///
+/// A log matches if it was emitted by one of the addresses and, at every constrained position, its
+/// topic is one of the given alternatives. An empty address list or topic position matches anything.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TopicFilter {
+    pub addresses: Vec<Address>,
+    pub topics: [Vec<[u8; 32]>; MAX_TOPICS],
+}
+
+impl TopicFilter {
+    /// A filter matching every log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an address to the alternatives of emitting contracts.
+    pub fn address(mut self, address: Address) -> Self {
+        self.addresses.push(address);
+        self
+    }
+
+    /// Adds an alternative topic at the given position.
+    pub fn topic(mut self, position: usize, topic: [u8; 32]) -> Self {
+        assert!(position < MAX_TOPICS, "Logs have at most 4 topics");
+        self.topics[position].push(topic);
+        self
+    }
+
+    /// Adds an alternative event topic, i.e. a topic at position 0.
+    pub fn event(self, topic0: [u8; 32]) -> Self {
+        self.topic(0, topic0)
+    }
+
+    /// Adds the topic of the ABI event to the alternatives at position 0.
+    pub fn abi_event(self, event: &Event) -> Self {
+        self.event(event.topic())
+    }
+
+    /// The filter object of `eth_getLogs`, `eth_newFilter` and `eth_subscribe` without a block range.
+    ///
+    /// Single alternatives are written as plain strings, unconstrained topic positions as `null`,
+    /// trailing unconstrained positions are left out.
+    pub fn to_json(&self) -> Value {
+        let mut filter = serde_json::Map::new();
+        match self.addresses.as_slice() {
+            [] => {}
+            [address] => {
+                filter.insert("address".to_owned(), json!(hex::encode_prefixed(address)));
+            }
+            addresses => {
+                let addresses: Vec<String> = addresses.iter().map(hex::encode_prefixed).collect();
+                filter.insert("address".to_owned(), json!(addresses));
+            }
+        }
+
+        let constrained = self.topics.iter().rposition(|t| !t.is_empty());
+        if let Some(last) = constrained {
+            let topics: Vec<Value> = self.topics[..=last]
+                .iter()
+                .map(|alternatives| match alternatives.as_slice() {
+                    [] => Value::Null,
+                    [topic] => json!(hex::encode_prefixed(topic)),
+                    topics => json!(topics.iter().map(hex::encode_prefixed).collect::<Vec<_>>()),
+                })
+                .collect();
+            filter.insert("topics".to_owned(), json!(topics));
+        }
+        Value::Object(filter)
+    }
+
+    /// The params array of an `eth_subscribe("logs", filter)` request.
+    pub fn subscribe_params(&self) -> Value {
+        json!(["logs", self.to_json()])
+    }
+
+    pub fn matches(&self, log: &Log) -> bool {
+        if !self.addresses.is_empty() && !self.addresses.contains(&log.address) {
+            return false;
+        }
+        self.topics
+            .iter()
+            .enumerate()
+            .all(|(i, alternatives)| match log.topics.get(i) {
+                _ if alternatives.is_empty() => true,
+                Some(topic) => alternatives.contains(topic),
+                None => false,
+            })
+    }
+
+    /// Returns the matching logs.
+    pub fn filter<'l, I>(&'l self, logs: I) -> impl Iterator<Item = &'l Log> + 'l
+    where
+        I: IntoIterator<Item = &'l Log>,
+        I::IntoIter: 'l,
+    {
+        logs.into_iter().filter(move |log| self.matches(log))
+    }
+
+    /// Whether a receipt or block with the given bloom may contain a matching log. If not, its
+    /// logs don't have to be fetched or filtered at all.
+    pub fn may_match_bloom(&self, bloom: &Bloom) -> bool {
+        let address = self.addresses.is_empty()
+            || self.addresses.iter().any(|a| bloom.may_contain_address(a));
+        address
+            && self.topics.iter().all(|alternatives| {
+                alternatives.is_empty() || alternatives.iter().any(|t| bloom.may_contain_topic(t))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(address: u8, topics: &[u8]) -> Log {
+        Log {
+            address: Address([address; 20]),
+            topics: topics.iter().map(|t| [*t; 32]).collect(),
+            data: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn matches_test() {
+        let logs = vec![
+            log(1, &[10, 20]),
+            log(2, &[10, 30]),
+            log(3, &[11]),
+            log(1, &[]),
+        ];
+
+        assert_eq!(TopicFilter::new().filter(&logs).count(), 4);
+
+        let filter = TopicFilter::new()
+            .address(Address([1; 20]))
+            .address(Address([2; 20]));
+        assert_eq!(filter.filter(&logs).count(), 3);
+
+        let filter = TopicFilter::new().event([10; 32]).topic(1, [30; 32]);
+        assert_eq!(filter.filter(&logs).collect::<Vec<_>>(), vec![&logs[1]]);
+
+        // a wildcard at position 0 still requires the log to have a topic at position 1
+        let filter = TopicFilter::new().topic(1, [20; 32]).topic(1, [30; 32]);
+        assert_eq!(filter.filter(&logs).count(), 2);
+    }
+
+    #[test]
+    fn may_match_bloom_test() {
+        let mut bloom = Bloom::default();
+        bloom.accrue_address(&Address([1; 20]));
+        bloom.accrue_topic(&[10; 32]);
+
+        assert!(TopicFilter::new().may_match_bloom(&bloom));
+        assert!(TopicFilter::new()
+            .address(Address([1; 20]))
+            .event([10; 32])
+            .may_match_bloom(&bloom));
+        assert!(!TopicFilter::new().event([11; 32]).may_match_bloom(&bloom));
+    }
+
+    #[test]
+    fn subscribe_params_test() {
+        assert_eq!(TopicFilter::new().subscribe_params(), json!(["logs", {}]));
+
+        let filter = TopicFilter::new()
+            .address(Address([0x11; 20]))
+            .topic(2, [0xaa; 32])
+            .topic(2, [0xbb; 32]);
+        assert_eq!(
+            filter.subscribe_params(),
+            json!(["logs", {
+                "address": "0x1111111111111111111111111111111111111111",
+                "topics": [
+                    null,
+                    null,
+                    [
+                        "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                        "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"
+                    ]
+                ]
+            }])
+        );
+    }
+
+    #[test]
+    fn abi_event_test() {
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        let abi: crate::Abi = serde_json::from_str(&json).unwrap();
+        let filter = TopicFilter::new()
+            .address(Address([0x11; 20]))
+            .address(Address([0x22; 20]))
+            .abi_event(abi.event("Transfer").unwrap());
+        assert_eq!(
+            filter.to_json(),
+            json!({
+                "address": [
+                    "0x1111111111111111111111111111111111111111",
+                    "0x2222222222222222222222222222222222222222"
+                ],
+                "topics": ["0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"]
+            })
+        );
+    }
+}
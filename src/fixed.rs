@@ -0,0 +1,159 @@
+use crate::selector::{selector, Selector};
+use crate::{EthereumTypes, ParamType};
+
+/// Calldata of a call with `N` static arguments, laid out exactly like a `[u8; 4 + 32 * N]` array.
+///
+/// The calldata lives inline and encoding itself doesn't allocate, which makes it usable on
+/// embedded signers and hot paths where the shape of the call is known at compile time. Only the
+/// arguments may own heap memory, `bytesN` values hold their bytes in a `Vec`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedCall<const N: usize> {
+    selector: Selector,
+    words: [[u8; 32]; N],
+}
+
+impl<const N: usize> FixedCall<N> {
+    /// Size of the encoded calldata in bytes.
+    pub const LEN: usize = 4 + 32 * N;
+
+    /// Encodes the arguments behind an already known function selector.
+    ///
+    /// Panics if an argument doesn't encode into a single word, like bytes, arrays or tuples.
+    #[inline]
+    pub fn new(selector: Selector, arguments: &[EthereumTypes; N]) -> Self {
+        let mut words = [[0_u8; 32]; N];
+        for (word, arg) in words.iter_mut().zip(arguments.iter()) {
+            *word = arg.value_as_u256();
+        }
+        Self { selector, words }
+    }
+
+    /// Encodes a call of the canonical `signature`, e.g. `transfer(address,uint256)`, checking that
+    /// the arguments match the declared input types.
+    ///
+    /// With the `lru` feature the signature hash is memoized on the heap, use [`FixedCall::new`]
+    /// with a precomputed selector to stay allocation-free in that case.
+    pub fn encode(signature: &str, arguments: &[EthereumTypes; N]) -> Result<Self, &'static str> {
+        let open = signature
+            .find('(')
+            .ok_or("Function signature is missing its opening parenthesis.")?;
+        let types = signature[open + 1..]
+            .strip_suffix(')')
+            .ok_or("Function signature is missing its closing parenthesis.")?;
+
+        let mut count = 0;
+        if !types.is_empty() {
+            for ty in types.split(',') {
+                match arguments.get(count) {
+                    Some(arg) if !is_word(arg) => {
+                        return Err("Arguments of a fixed call have to fit into a single word.")
+                    }
+                    Some(arg) if word_type_matches(arg, ty) => count += 1,
+                    Some(_) => return Err("Input arguments don't match the function signature."),
+                    None => return Err("Function signature declares more inputs than given."),
+                }
+            }
+        }
+        if count != N {
+            return Err("Function signature declares fewer inputs than given.");
+        }
+        Ok(Self::new(selector(signature), arguments))
+    }
+
+    #[inline]
+    pub fn selector(&self) -> Selector {
+        self.selector
+    }
+
+    /// Returns the encoded calldata.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: the struct is `repr(C)` and only consists of byte arrays, so it has an alignment
+        // of 1, no padding and is exactly `LEN` bytes long.
+        unsafe { std::slice::from_raw_parts(self as *const Self as *const u8, Self::LEN) }
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for FixedCall<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+fn is_word(arg: &EthereumTypes) -> bool {
+    matches!(
+        arg,
+        EthereumTypes::Address(_)
+            | EthereumTypes::U256(_)
+            | EthereumTypes::I256(_)
+            | EthereumTypes::Bool(_)
+            | EthereumTypes::FixedBytes(_)
+    )
+}
+
+// compares without formatting the type name of the argument, a `uint256` value matches the
+// narrower `uintN` types it fits into
+fn word_type_matches(arg: &EthereumTypes, ty: &str) -> bool {
+    // elementary type names parse without allocating, unlike arrays and tuples
+    if ty.contains(['[', '(']) {
+        return false;
+    }
+    ParamType::from_name(ty).is_some_and(|kind| arg.type_check(&kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_transfer_test() {
+        let arguments = [
+            EthereumTypes::Address([0x30; 20]),
+            EthereumTypes::u256_from_bytes(&20000000000_u64.to_be_bytes()),
+        ];
+        let call = FixedCall::encode("transfer(address,uint256)", &arguments).unwrap();
+        assert_eq!(std::mem::size_of::<FixedCall<2>>(), FixedCall::<2>::LEN);
+        assert_eq!(
+            call.as_bytes(),
+            crate::transaction(
+                std::path::Path::new("src/rust_abi.json"),
+                "transfer",
+                arguments
+            )
+            .unwrap()
+            .as_slice()
+        );
+    }
+
+    #[test]
+    fn fixed_mismatch_test() {
+        let arguments = [EthereumTypes::Address([0x30; 20])];
+        assert!(FixedCall::encode("transfer(address,uint256)", &arguments).is_err());
+        assert!(FixedCall::encode("approve(uint256)", &arguments).is_err());
+        assert!(FixedCall::encode("totalSupply()", &arguments).is_err());
+        assert!(FixedCall::<0>::encode("totalSupply()", &[]).is_ok());
+        assert!(FixedCall::encode("f(bytes)", &[EthereumTypes::Bytes(vec![])]).is_err());
+        assert!(FixedCall::encode("f(uint256[1])", &[EthereumTypes::U256([0; 32])]).is_err());
+    }
+
+    #[test]
+    fn fixed_narrow_types_test() {
+        let mut word = [0_u8; 32];
+        word[31] = 0xff;
+        let arguments = [
+            EthereumTypes::U256(word),
+            EthereumTypes::FixedBytes(vec![1; 4]),
+        ];
+        let call = FixedCall::encode("f(uint8,bytes4)", &arguments).unwrap();
+        assert_eq!(call.selector(), selector("f(uint8,bytes4)"));
+        // 256 doesn't fit into an uint8
+        word[30] = 0x01;
+        let arguments = [
+            EthereumTypes::U256(word),
+            EthereumTypes::FixedBytes(vec![1; 4]),
+        ];
+        assert!(FixedCall::encode("f(uint8,bytes4)", &arguments).is_err());
+        assert!(FixedCall::encode("f(uint16,bytes4)", &arguments).is_ok());
+    }
+}
@@ -0,0 +1,281 @@
+//! Transaction fee calculations based on the size and content of calldata.
+
+/// Base cost of every transaction.
+pub const TX_BASE_GAS: u64 = 21_000;
+/// Additional base cost of contract creation transactions.
+pub const TX_CREATE_GAS: u64 = 32_000;
+/// Cost of a zero calldata byte.
+pub const ZERO_BYTE_GAS: u64 = 4;
+/// Cost of a non-zero calldata byte per EIP-2028.
+pub const NONZERO_BYTE_GAS: u64 = 16;
+/// Cost of every 32 byte word of init code per EIP-3860.
+pub const INIT_CODE_WORD_GAS: u64 = 2;
+
+/// Gas charged for the calldata bytes alone.
+#[inline]
+pub fn calldata_gas(calldata: &[u8]) -> u64 {
+    calldata
+        .iter()
+        .map(|b| {
+            if *b == 0 {
+                ZERO_BYTE_GAS
+            } else {
+                NONZERO_BYTE_GAS
+            }
+        })
+        .sum()
+}
+
+/// Intrinsic gas of a call transaction carrying the calldata, including the base cost.
+#[inline]
+pub fn intrinsic_gas(calldata: &[u8]) -> u64 {
+    TX_BASE_GAS + calldata_gas(calldata)
+}
+
+/// Intrinsic gas of a contract creation transaction deploying the init code.
+#[inline]
+pub fn creation_intrinsic_gas(init_code: &[u8]) -> u64 {
+    let words = (init_code.len() as u64).div_ceil(32);
+    TX_BASE_GAS + TX_CREATE_GAS + calldata_gas(init_code) + words * INIT_CODE_WORD_GAS
+}
+
+// the L1 fee formulas account for the signature of the unsigned transaction they are given
+const SIGNATURE_PADDING_GAS: u64 = 68 * NONZERO_BYTE_GAS;
+// OP Stack fee scalars are fixed point numbers with 6 decimals
+const OP_SCALAR_DECIMALS: u128 = 1_000_000;
+// linear regression of the Fjord upgrade estimating the brotli compressed size from the FastLZ
+// compressed size, with 6 decimals
+const FJORD_COST_INTERCEPT: i128 = -42_585_600;
+const FJORD_COST_FASTLZ_COEF: i128 = 836_500;
+const FJORD_MIN_TX_SIZE: i128 = 100;
+/// Assumed size in bytes of a typical transaction without its calldata, as used by Arbitrum Nitro.
+pub const ARBITRUM_TX_FIXED_BYTES: u64 = 140;
+
+/// L1 fee parameters of an OP Stack chain since the Ecotone upgrade, as exposed by its
+/// `GasPriceOracle` predeploy. The Fjord upgrade changed the formula, not the parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OptimismFeeParams {
+    /// L1 base fee in wei.
+    pub l1_base_fee: u128,
+    /// L1 blob base fee in wei.
+    pub l1_blob_base_fee: u128,
+    pub base_fee_scalar: u32,
+    pub blob_base_fee_scalar: u32,
+}
+
+/// L1 fee parameters of an OP Stack chain before the Ecotone upgrade.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OptimismBedrockFeeParams {
+    /// L1 base fee in wei.
+    pub l1_base_fee: u128,
+    pub overhead: u64,
+    pub scalar: u64,
+}
+
+/// L1 pricing parameters of an Arbitrum chain, as exposed by its `ArbGasInfo` precompile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArbitrumFeeParams {
+    /// Estimated price of one L1 calldata gas unit in wei.
+    pub l1_price_per_unit: u128,
+    /// L2 base fee in wei.
+    pub l2_base_fee: u128,
+}
+
+/// L1 data fee in wei of an OP Stack transaction, following `GasPriceOracle.getL1Fee` since the
+/// Fjord upgrade, which prices the FastLZ compressed size of the transaction.
+///
+/// `tx_data` is the unsigned RLP-encoded transaction, passing only the calldata underestimates the
+/// fee by the size of the remaining transaction fields.
+pub fn optimism_l1_fee(tx_data: &[u8], params: &OptimismFeeParams) -> u128 {
+    // the signature adds 68 bytes to the unsigned transaction
+    let fastlz_size = (flz_compress_len(tx_data) + 68) as i128;
+    let estimated_size = (FJORD_COST_INTERCEPT + FJORD_COST_FASTLZ_COEF * fastlz_size)
+        .max(FJORD_MIN_TX_SIZE * OP_SCALAR_DECIMALS as i128) as u128;
+    let scaled_base_fee = u128::from(params.base_fee_scalar) * 16 * params.l1_base_fee;
+    let scaled_blob_base_fee = u128::from(params.blob_base_fee_scalar) * params.l1_blob_base_fee;
+    estimated_size * (scaled_base_fee + scaled_blob_base_fee)
+        / (OP_SCALAR_DECIMALS * OP_SCALAR_DECIMALS)
+}
+
+/// L1 data fee in wei of an OP Stack transaction under the Ecotone formula, used until the Fjord
+/// upgrade, see [`optimism_l1_fee`].
+pub fn optimism_ecotone_l1_fee(tx_data: &[u8], params: &OptimismFeeParams) -> u128 {
+    let l1_gas = u128::from(calldata_gas(tx_data) + SIGNATURE_PADDING_GAS);
+    let scaled_base_fee = u128::from(params.base_fee_scalar) * 16 * params.l1_base_fee;
+    let scaled_blob_base_fee = u128::from(params.blob_base_fee_scalar) * params.l1_blob_base_fee;
+    l1_gas * (scaled_base_fee + scaled_blob_base_fee) / (16 * OP_SCALAR_DECIMALS)
+}
+
+/// L1 data fee in wei of an OP Stack transaction under the pre-Ecotone (Bedrock) formula.
+pub fn optimism_bedrock_l1_fee(tx_data: &[u8], params: &OptimismBedrockFeeParams) -> u128 {
+    let l1_gas = u128::from(calldata_gas(tx_data) + params.overhead + SIGNATURE_PADDING_GAS);
+    l1_gas * params.l1_base_fee * u128::from(params.scalar) / OP_SCALAR_DECIMALS
+}
+
+// length of the FastLZ (level 1) compression of the data as done by Solady's `LibZip.flzCompress`,
+// a port of `FlzCompressLen` of op-geth which only counts the output bytes
+fn flz_compress_len(data: &[u8]) -> usize {
+    let u24 = |i: usize| {
+        u32::from(data[i]) | (u32::from(data[i + 1]) << 8) | (u32::from(data[i + 2]) << 16)
+    };
+    let hash = |value: u32| (value.wrapping_mul(2_654_435_769) >> 19) as usize & 0x1fff;
+    let mut table = vec![0_usize; 8192];
+    let mut len = 0;
+    let literals = |len: &mut usize, run: usize| {
+        *len += 0x21 * (run / 0x20);
+        if !run.is_multiple_of(0x20) {
+            *len += run % 0x20 + 1;
+        }
+    };
+
+    let ip_limit = data.len().saturating_sub(13);
+    let (mut anchor, mut ip) = (0, 2);
+    while ip < ip_limit {
+        // find the next earlier occurrence of the 3 bytes at `ip` within 8KiB
+        let mut reference;
+        loop {
+            let sequence = u24(ip);
+            let h = hash(sequence);
+            reference = table[h];
+            table[h] = ip;
+            let distance = ip - reference;
+            if ip >= ip_limit {
+                break;
+            }
+            ip += 1;
+            if distance <= 0x1fff && sequence == u24(reference) {
+                break;
+            }
+        }
+        if ip >= ip_limit {
+            break;
+        }
+        ip -= 1;
+        if ip > anchor {
+            literals(&mut len, ip - anchor);
+        }
+        // the match length, which counts the first differing byte too like the original
+        let end = ip_limit + 9 - (ip + 3);
+        let mut matched = 0;
+        while matched < end {
+            let differs = data[reference + 3 + matched] != data[ip + 3 + matched];
+            matched += 1;
+            if differs {
+                break;
+            }
+        }
+        len += 3 * ((matched - 1) / 262) + if (matched - 1) % 262 >= 6 { 3 } else { 2 };
+        ip += matched;
+        for _ in 0..2 {
+            table[hash(u24(ip))] = ip;
+            ip += 1;
+        }
+        anchor = ip;
+    }
+    literals(&mut len, data.len() - anchor);
+    len
+}
+
+/// L1 data fee in wei of an Arbitrum transaction.
+///
+/// Arbitrum charges for the brotli-compressed transaction, this estimate prices the uncompressed
+/// bytes and therefore is an upper bound.
+pub fn arbitrum_l1_fee(tx_data: &[u8], params: &ArbitrumFeeParams) -> u128 {
+    let l1_units = calldata_gas(tx_data) + ARBITRUM_TX_FIXED_BYTES * NONZERO_BYTE_GAS;
+    u128::from(l1_units) * params.l1_price_per_unit
+}
+
+/// Amount of L2 gas an Arbitrum transaction is charged on top of its execution to pay for its L1
+/// data fee, see [`arbitrum_l1_fee`].
+pub fn arbitrum_l1_gas(tx_data: &[u8], params: &ArbitrumFeeParams) -> u128 {
+    let fee = arbitrum_l1_fee(tx_data, params);
+    if params.l2_base_fee == 0 {
+        return 0;
+    }
+    fee.div_ceil(params.l2_base_fee)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calldata_gas_test() {
+        // balanceOf(0x30e7d7fff85c8d0e775140b1ad93c230d5595207): 24 non-zero and 12 zero bytes
+        let calldata = [
+            0x70, 0xa0, 0x82, 0x31, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x30, 0xe7, 0xd7, 0xff, 0xf8, 0x5c, 0x8d, 0x0e, 0x77, 0x51, 0x40, 0xb1,
+            0xad, 0x93, 0xc2, 0x30, 0xd5, 0x59, 0x52, 0x07,
+        ];
+        assert_eq!(calldata_gas(&calldata), 24 * 16 + 12 * 4);
+        assert_eq!(intrinsic_gas(&calldata), 21_000 + 432);
+        assert_eq!(intrinsic_gas(&[]), 21_000);
+        assert_eq!(
+            creation_intrinsic_gas(&[0x60; 33]),
+            53_000 + 33 * 16 + 2 * 2
+        );
+    }
+
+    #[test]
+    fn optimism_l1_fee_test() {
+        // 2 zero and 2 non-zero bytes: 40 gas, plus 1088 for the signature
+        let data = [0x00, 0x01, 0x00, 0x02];
+        let params = OptimismFeeParams {
+            l1_base_fee: 10_000_000_000,
+            l1_blob_base_fee: 1,
+            base_fee_scalar: 1368,
+            blob_base_fee_scalar: 810_949,
+        };
+        // 1128 * (1368 * 16 * 10^10 + 810949) / (16 * 10^6)
+        assert_eq!(optimism_ecotone_l1_fee(&data, &params), 15_431_040_057);
+
+        let params = OptimismBedrockFeeParams {
+            l1_base_fee: 10_000_000_000,
+            overhead: 188,
+            scalar: 684_000,
+        };
+        // (1128 + 188) * 10^10 * 0.684
+        assert_eq!(optimism_bedrock_l1_fee(&data, &params), 9_001_440_000_000);
+    }
+
+    #[test]
+    fn flz_compress_len_test() {
+        assert_eq!(flz_compress_len(&[]), 0);
+        assert_eq!(flz_compress_len(&[1]), 2);
+        assert_eq!(flz_compress_len(&[1; 1000]), 21);
+        assert_eq!(flz_compress_len(&[0; 1000]), 21);
+        // literals only, in runs of 32
+        let literals: Vec<u8> = (0..200).collect();
+        assert_eq!(flz_compress_len(&literals), 6 * 33 + 9);
+    }
+
+    #[test]
+    fn optimism_fjord_l1_fee_test() {
+        let params = OptimismFeeParams {
+            l1_base_fee: 10_000_000_000,
+            l1_blob_base_fee: 1,
+            base_fee_scalar: 1368,
+            blob_base_fee_scalar: 810_949,
+        };
+        // small transactions are charged for at least 100 bytes
+        assert_eq!(
+            optimism_l1_fee(&[0x00, 0x01, 0x00, 0x02], &params),
+            21_888_000_081
+        );
+        // (-42.5856 + 0.8365 * (207 + 68)) * (1368 * 16 * 10^10 + 810949) / 10^6
+        let data: Vec<u8> = (0..200).collect();
+        assert_eq!(optimism_l1_fee(&data, &params), 41_029_472_024);
+    }
+
+    #[test]
+    fn arbitrum_l1_fee_test() {
+        let data = [0x00, 0x01, 0x00, 0x02];
+        let params = ArbitrumFeeParams {
+            l1_price_per_unit: 1_000_000_000,
+            l2_base_fee: 100_000_000,
+        };
+        // (40 + 140 * 16) units
+        assert_eq!(arbitrum_l1_fee(&data, &params), 2_280_000_000_000);
+        assert_eq!(arbitrum_l1_gas(&data, &params), 22_800);
+    }
+}
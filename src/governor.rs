@@ -0,0 +1,146 @@
+use crate::selector::selector;
+use crate::{encode, Address, EthereumTypes, U256};
+
+use sha3::{Digest, Keccak256};
+
+/// A call executed by a governance proposal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProposalAction {
+    pub target: Address,
+    /// Wei sent along with the call.
+    pub value: U256,
+    /// Already encoded calldata of the call.
+    pub calldata: Vec<u8>,
+}
+
+/// A proposal of an OpenZeppelin `Governor`, which executes its actions in order once passed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Proposal {
+    pub actions: Vec<ProposalAction>,
+    pub description: String,
+}
+
+impl Proposal {
+    /// A proposal without actions.
+    pub fn new(description: &str) -> Self {
+        Self {
+            actions: Vec::new(),
+            description: description.to_owned(),
+        }
+    }
+
+    /// Appends a call to the actions of the proposal.
+    pub fn action(mut self, target: Address, value: U256, calldata: Vec<u8>) -> Self {
+        self.actions.push(ProposalAction {
+            target,
+            value,
+            calldata,
+        });
+        self
+    }
+
+    /// The keccak hash of the description, which identifies the proposal along with its actions
+    /// in `queue` and `execute`.
+    pub fn description_hash(&self) -> [u8; 32] {
+        Keccak256::digest(self.description.as_bytes()).into()
+    }
+
+    /// The proposal id, `keccak256(abi.encode(targets, values, calldatas, descriptionHash))`.
+    pub fn id(&self) -> U256 {
+        U256(Keccak256::digest(&encode(&self.tokens(self.description_hash_token()))).into())
+    }
+
+    /// Encodes `propose(address[],uint256[],bytes[],string)` calldata.
+    pub fn encode_propose(&self) -> Vec<u8> {
+        let description = EthereumTypes::String(self.description.clone());
+        self.call("propose(address[],uint256[],bytes[],string)", description)
+    }
+
+    /// Encodes `queue(address[],uint256[],bytes[],bytes32)` calldata, used by governors with a
+    /// timelock.
+    pub fn encode_queue(&self) -> Vec<u8> {
+        self.call(
+            "queue(address[],uint256[],bytes[],bytes32)",
+            self.description_hash_token(),
+        )
+    }
+
+    /// Encodes `execute(address[],uint256[],bytes[],bytes32)` calldata.
+    pub fn encode_execute(&self) -> Vec<u8> {
+        self.call(
+            "execute(address[],uint256[],bytes[],bytes32)",
+            self.description_hash_token(),
+        )
+    }
+
+    fn call(&self, signature: &str, description: EthereumTypes) -> Vec<u8> {
+        let mut data = selector(signature).0.to_vec();
+        data.extend_from_slice(&encode(&self.tokens(description)));
+        data
+    }
+
+    fn description_hash_token(&self) -> EthereumTypes {
+        EthereumTypes::FixedBytes(self.description_hash().to_vec())
+    }
+
+    // the parallel target, value and calldata arrays followed by the description
+    fn tokens(&self, description: EthereumTypes) -> [EthereumTypes; 4] {
+        let targets = self.actions.iter().map(|a| EthereumTypes::from(a.target));
+        let values = self.actions.iter().map(|a| EthereumTypes::from(a.value));
+        let calldatas = self
+            .actions
+            .iter()
+            .map(|a| EthereumTypes::Bytes(a.calldata.clone()));
+        [
+            EthereumTypes::Array(targets.collect()),
+            EthereumTypes::Array(values.collect()),
+            EthereumTypes::Array(calldatas.collect()),
+            description,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode, ParamType};
+
+    fn proposal() -> Proposal {
+        Proposal::new("# Fund the grants program").action(
+            Address([0x11; 20]),
+            U256::ZERO,
+            vec![0xa9, 0x05, 0x9c, 0xbb],
+        )
+    }
+
+    #[test]
+    fn propose_test() {
+        let data = proposal().encode_propose();
+        assert_eq!(data[..4], [0x7d, 0x5e, 0x81, 0xe2]);
+        let types = ["address[]", "uint256[]", "bytes[]", "string"]
+            .iter()
+            .map(|t| ParamType::from_name(t).unwrap())
+            .collect::<Vec<_>>();
+        let tokens = decode(&types, &data[4..]).unwrap();
+        assert_eq!(
+            tokens[3],
+            EthereumTypes::String("# Fund the grants program".to_owned())
+        );
+        assert_eq!(
+            tokens[2],
+            EthereumTypes::Array(vec![EthereumTypes::Bytes(vec![0xa9, 0x05, 0x9c, 0xbb])])
+        );
+    }
+
+    #[test]
+    fn proposal_id_test() {
+        let proposal = proposal();
+        let execute = proposal.encode_execute();
+        assert_eq!(execute[..4], [0x26, 0x56, 0x22, 0x7d]);
+        // the id hashes the same arguments that queue and execute take
+        let expected: [u8; 32] = Keccak256::digest(&execute[4..]).into();
+        assert_eq!(proposal.id(), U256(expected));
+        assert_eq!(proposal.encode_queue()[4..], execute[4..]);
+        assert_ne!(Proposal::new("other").id(), Proposal::new("# Fund").id());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{Abi, Param};
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Emits a subgraph style GraphQL schema with an immutable entity per event, holding the event
+/// parameters and the block and transaction the log was emitted in.
+///
+/// Types follow the mapping of graph-cli: addresses and bytes are `Bytes`, integers fitting into
+/// 32 signed bits `Int` and wider ones `BigInt`. Tuples are flattened into one field per component,
+/// indexed parameters of dynamic types hold the topic, i.e. the hash of the value. Overloaded
+/// events get a numeric suffix, anonymous events are skipped as they can't be subscribed to.
+pub fn to_graphql(abi: &Abi, contract_name: &str) -> String {
+    let mut schema = format!(
+        "# Generated by zgen-abi from the {} ABI, do not edit.\n",
+        contract_name
+    );
+    let mut overloads = HashMap::<&str, usize>::new();
+    for event in abi.events.iter().filter(|e| !e.anonymous) {
+        let count = overloads.entry(&event.name).or_insert(0);
+        *count += 1;
+        let mut name = pascal_case(&event.name);
+        if *count > 1 {
+            name += &count.to_string();
+        }
+
+        let _ = write!(schema, "\ntype {} @entity(immutable: true) {{\n", name);
+        schema.push_str("  id: Bytes!\n");
+        for (i, param) in event.inputs.iter().enumerate() {
+            let field = field_name(param, i);
+            if param.indexed && is_hashed(&param.kind) {
+                let _ = writeln!(schema, "  {}: Bytes! # {} (topic)", field, param.kind);
+            } else {
+                fields(&mut schema, &field, param);
+            }
+        }
+        schema.push_str("  blockNumber: BigInt!\n");
+        schema.push_str("  blockTimestamp: BigInt!\n");
+        schema.push_str("  transactionHash: Bytes!\n");
+        schema.push_str("}\n");
+    }
+    schema
+}
+
+fn fields(schema: &mut String, field: &str, param: &Param) {
+    if param.kind == "tuple" {
+        for (i, component) in param.components.iter().enumerate() {
+            let name = format!("{}_{}", field, field_name(component, i));
+            fields(schema, &name, component);
+        }
+    } else {
+        let _ = writeln!(
+            schema,
+            "  {}: {} # {}",
+            field,
+            graphql_type(&param.kind),
+            param.kind
+        );
+    }
+}
+
+// GraphQL type of a non-tuple ABI type, arrays of tuples are stored encoded
+fn graphql_type(kind: &str) -> String {
+    if let Some(open) = kind.rfind('[') {
+        return format!("[{}]!", graphql_type(&kind[..open]));
+    }
+    let scalar = match kind {
+        "bool" => "Boolean",
+        "string" => "String",
+        k if k.starts_with("uint") => match k[4..].parse::<u16>() {
+            Ok(bits) if bits < 32 => "Int",
+            _ => "BigInt",
+        },
+        k if k.starts_with("int") => match k[3..].parse::<u16>() {
+            Ok(bits) if bits <= 32 => "Int",
+            _ => "BigInt",
+        },
+        // address, bytes, bytesN, function and encoded tuples
+        _ => "Bytes",
+    };
+    format!("{}!", scalar)
+}
+
+// indexed parameters of these types are logged as the hash of their encoding
+fn is_hashed(kind: &str) -> bool {
+    matches!(kind, "string" | "bytes" | "tuple") || kind.ends_with(']')
+}
+
+// graph-cli renames parameters clashing with the entity id
+fn field_name(param: &Param, index: usize) -> String {
+    match param.name.as_ref() {
+        "" => format!("param{}", index),
+        "id" => "internal_id".to_owned(),
+        name => name.to_owned(),
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graphql_test() {
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        let abi: Abi = serde_json::from_str(&json).unwrap();
+        let schema = to_graphql(&abi, "Erc20");
+        assert!(schema.starts_with("# Generated by zgen-abi from the Erc20 ABI, do not edit.\n"));
+        assert!(schema.contains(
+            "type Transfer @entity(immutable: true) {\n  id: Bytes!\n  from: Bytes! # address\n"
+        ));
+        assert!(schema.contains("  blockTimestamp: BigInt!\n  transactionHash: Bytes!\n}\n"));
+    }
+
+    #[test]
+    fn params_test() {
+        let json = r#"[
+            {"type":"event","name":"Order","inputs":[
+                {"name":"id","type":"uint256","indexed":true},
+                {"name":"tag","type":"string","indexed":true},
+                {"name":"","type":"int24"},
+                {"name":"fill","type":"tuple","components":[
+                    {"name":"amount","type":"uint128"},{"name":"parts","type":"uint16[]"}]}]},
+            {"type":"event","name":"Order","inputs":[]},
+            {"type":"event","name":"Hidden","inputs":[],"anonymous":true}
+        ]"#;
+        let abi: Abi = serde_json::from_str(json).unwrap();
+        let schema = to_graphql(&abi, "Book");
+        assert!(schema.contains(concat!(
+            "  internal_id: BigInt! # uint256\n",
+            "  tag: Bytes! # string (topic)\n",
+            "  param2: Int! # int24\n",
+            "  fill_amount: BigInt! # uint128\n",
+            "  fill_parts: [Int!]! # uint16[]\n",
+        )));
+        assert!(schema.contains("type Order2 @entity(immutable: true) {\n"));
+        assert!(!schema.contains("Hidden"));
+    }
+
+    #[test]
+    fn graphql_type_test() {
+        assert_eq!(graphql_type("uint32"), "BigInt!");
+        assert_eq!(graphql_type("int32"), "Int!");
+        assert_eq!(graphql_type("bytes32[2][]"), "[[Bytes!]!]!");
+    }
+}
@@ -0,0 +1,193 @@
+//! Hex encoding and decoding of byte strings, with or without the `0x` prefix.
+
+use std::fmt;
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Errors of decoding a hex string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HexError {
+    /// A character that is not a hex digit, at the given index of the input (prefix included).
+    InvalidCharacter { character: char, index: usize },
+    /// The decoded bytes don't have the required length.
+    InvalidLength { expected: usize, found: usize },
+    /// A fixed size value with an odd number of digits, which is likely truncated.
+    OddLength { digits: usize },
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCharacter { character, index } => {
+                write!(
+                    f,
+                    "Invalid hex character {:?} at index {}.",
+                    character, index
+                )
+            }
+            Self::InvalidLength { expected, found } => write!(
+                f,
+                "Invalid hex length. Expected {} bytes, found {}.",
+                expected, found
+            ),
+            Self::OddLength { digits } => {
+                write!(
+                    f,
+                    "Invalid hex length. Found an odd number of {} digits.",
+                    digits
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HexError {}
+
+/// Encodes the bytes as lowercase hex without a prefix.
+pub fn encode<T: AsRef<[u8]>>(bytes: T) -> String {
+    let bytes = bytes.as_ref();
+    let mut hex = String::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        hex.push(char::from(DIGITS[usize::from(byte >> 4)]));
+        hex.push(char::from(DIGITS[usize::from(byte & 0x0f)]));
+    }
+    hex
+}
+
+/// Encodes the bytes as lowercase hex with a `0x` prefix.
+pub fn encode_prefixed<T: AsRef<[u8]>>(bytes: T) -> String {
+    "0x".to_owned() + &encode(bytes)
+}
+
+// `0x` prefixed lowercase hex of the bytes written into `out`, which has to hold `2 + 2 * len`
+// bytes, for formatting without allocating
+#[cfg(feature = "defmt")]
+pub(crate) fn encode_prefixed_into<'o>(bytes: &[u8], out: &'o mut [u8]) -> &'o str {
+    let out = &mut out[..2 + 2 * bytes.len()];
+    out[..2].copy_from_slice(b"0x");
+    for (byte, digits) in bytes.iter().zip(out[2..].chunks_exact_mut(2)) {
+        digits[0] = DIGITS[usize::from(byte >> 4)];
+        digits[1] = DIGITS[usize::from(byte & 0x0f)];
+    }
+    // only ASCII digits were written
+    std::str::from_utf8(out).unwrap()
+}
+
+/// Decodes a hex string, with or without a `0x` prefix and in any letter case.
+///
+/// Odd-length input is read as if it had a leading zero, so `0x1` decodes to `[0x01]`.
+pub fn decode(input: &str) -> Result<Vec<u8>, HexError> {
+    let (offset, digits) = strip_prefix(input);
+    let digits = digits.as_bytes();
+    let mut bytes = Vec::with_capacity(digits.len().div_ceil(2));
+
+    // an odd-length input has a single digit for its first byte
+    let odd = digits.len() % 2;
+    if odd == 1 {
+        bytes.push(nibble(digits[0], offset)?);
+    }
+    for (i, pair) in digits[odd..].chunks_exact(2).enumerate() {
+        let index = offset + odd + 2 * i;
+        bytes.push((nibble(pair[0], index)? << 4) | nibble(pair[1], index + 1)?);
+    }
+    Ok(bytes)
+}
+
+/// Decodes a hex string into exactly `N` bytes, e.g. a 20 byte address.
+///
+/// Unlike [`decode`], the input needs exactly `2 * N` digits, a missing leading zero is rather a
+/// truncated value than a shorter one.
+pub fn decode_to_array<const N: usize>(input: &str) -> Result<[u8; N], HexError> {
+    let bytes = decode_whole_bytes(input)?;
+    if bytes.len() != N {
+        return Err(HexError::InvalidLength {
+            expected: N,
+            found: bytes.len(),
+        });
+    }
+    let mut array = [0_u8; N];
+    array.copy_from_slice(&bytes);
+    Ok(array)
+}
+
+// like `decode`, but fails for an odd number of digits, for fixed size values
+pub(crate) fn decode_whole_bytes(input: &str) -> Result<Vec<u8>, HexError> {
+    let digits = strip_prefix(input).1.len();
+    if digits % 2 == 1 {
+        return Err(HexError::OddLength { digits });
+    }
+    decode(input)
+}
+
+// the offset of the digits in the input and the digits themselves
+fn strip_prefix(input: &str) -> (usize, &str) {
+    match input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+    {
+        Some(digits) => (2, digits),
+        None => (0, input),
+    }
+}
+
+fn nibble(digit: u8, index: usize) -> Result<u8, HexError> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(HexError::InvalidCharacter {
+            character: char::from(digit),
+            index,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_test() {
+        assert_eq!(encode([0xa9, 0x05, 0x9c, 0xbb]), "a9059cbb");
+        assert_eq!(encode_prefixed([0x00, 0xff]), "0x00ff");
+        assert_eq!(encode_prefixed([]), "0x");
+        #[cfg(feature = "defmt")]
+        assert_eq!(
+            encode_prefixed_into(&[0xa9, 0x05], &mut [0; 8]),
+            encode_prefixed([0xa9, 0x05])
+        );
+    }
+
+    #[test]
+    fn decode_test() {
+        assert_eq!(decode("0xa9059CBB"), Ok(vec![0xa9, 0x05, 0x9c, 0xbb]));
+        assert_eq!(decode("a9059cbb"), Ok(vec![0xa9, 0x05, 0x9c, 0xbb]));
+        assert_eq!(decode("0x1"), Ok(vec![0x01]));
+        assert_eq!(decode("0x"), Ok(vec![]));
+        assert_eq!(decode("0X123"), Ok(vec![0x01, 0x23]));
+        assert_eq!(decode_to_array::<2>("0x0102"), Ok([0x01, 0x02]));
+    }
+
+    #[test]
+    fn decode_error_test() {
+        assert_eq!(
+            decode("0x12g4"),
+            Err(HexError::InvalidCharacter {
+                character: 'g',
+                index: 4
+            })
+        );
+        assert_eq!(
+            decode_to_array::<20>("0x0102"),
+            Err(HexError::InvalidLength {
+                expected: 20,
+                found: 2
+            })
+        );
+        assert_eq!(
+            decode_to_array::<2>("0x102"),
+            Err(HexError::OddLength { digits: 3 })
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{hex, Address, DecodedCall, DecodedLog, Log, OwnedAbi, Selector};
+
+use std::collections::HashMap;
+
+/// An ABI of a contract, live from `from_block` until the `from_block` of the next version.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AbiVersion {
+    pub from_block: u64,
+    /// Name of the version, e.g. the implementation it was upgraded to.
+    pub name: String,
+    pub abi: OwnedAbi,
+}
+
+/// The successive ABIs of upgradeable contracts, decoding historical calls and logs with the ABI
+/// that was live at their block.
+///
+/// ```ignore
+/// let history = AbiHistory::new()
+///     .version(proxy, 12_000_000, "v1", v1_abi)
+///     .version(proxy, 15_500_000, "v2", v2_abi);
+/// let call = history.decode_call(&proxy, 13_000_000, &calldata)?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AbiHistory {
+    contracts: HashMap<Address, Vec<AbiVersion>>,
+}
+
+impl AbiHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the ABI a contract has from `from_block` on, e.g. the block of an `Upgraded`
+    /// event. Versions can be recorded in any order, a version starting at the same block as an
+    /// earlier one replaces it.
+    pub fn version(mut self, address: Address, from_block: u64, name: &str, abi: OwnedAbi) -> Self {
+        let versions = self.contracts.entry(address).or_default();
+        let version = AbiVersion {
+            from_block,
+            name: name.to_owned(),
+            abi,
+        };
+        match versions.binary_search_by_key(&from_block, |v| v.from_block) {
+            Ok(index) => versions[index] = version,
+            Err(index) => versions.insert(index, version),
+        }
+        self
+    }
+
+    /// The versions of a contract in block order.
+    pub fn versions(&self, address: &Address) -> &[AbiVersion] {
+        self.contracts.get(address).map_or(&[], Vec::as_slice)
+    }
+
+    /// The version live at `block`, none before the first recorded version.
+    pub fn version_at(&self, address: &Address, block: u64) -> Option<&AbiVersion> {
+        let versions = self.versions(address);
+        let live = versions.partition_point(|v| v.from_block <= block);
+        live.checked_sub(1).map(|index| &versions[index])
+    }
+
+    /// Decodes a call to the contract with the ABI live at `block`.
+    pub fn decode_call(
+        &self,
+        address: &Address,
+        block: u64,
+        calldata: &[u8],
+    ) -> Result<DecodedCall<'_>, String> {
+        let version = self.live(address, block)?;
+        let selector = Selector::from_calldata(calldata)
+            .ok_or_else(|| "Calldata is shorter than a selector.".to_owned())?;
+        for function in version.abi.functions.iter() {
+            if function.selector() != selector {
+                continue;
+            }
+            if let Ok(arguments) = function.decode_input(calldata) {
+                return Ok(DecodedCall {
+                    abi: &version.name,
+                    function,
+                    arguments,
+                });
+            }
+        }
+        Err(format!(
+            "Version {} of {} doesn't decode a call with selector {}.",
+            version.name,
+            hex::encode_prefixed(address),
+            selector
+        ))
+    }
+
+    /// Decodes a log with the ABI its contract had at `block`.
+    pub fn decode_log(&self, log: &Log, block: u64) -> Result<DecodedLog<'_>, String> {
+        let version = self.live(&log.address, block)?;
+        for event in version.abi.events.iter() {
+            if let Ok(params) = event.decode_log(log) {
+                return Ok(DecodedLog {
+                    abi: &version.name,
+                    event,
+                    params,
+                });
+            }
+        }
+        Err(format!(
+            "Version {} of {} doesn't decode the log.",
+            version.name,
+            hex::encode_prefixed(log.address)
+        ))
+    }
+
+    fn live(&self, address: &Address, block: u64) -> Result<&AbiVersion, String> {
+        self.version_at(address, block).ok_or_else(|| {
+            format!(
+                "No ABI of {} is recorded at block {}.",
+                hex::encode_prefixed(address),
+                block
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{selector, Abi, U256};
+
+    fn abi(json: &str) -> OwnedAbi {
+        serde_json::from_str::<Abi>(json).unwrap().into_owned()
+    }
+
+    fn history() -> AbiHistory {
+        let proxy = Address([0xaa; 20]);
+        let v1 = abi(r#"[{"name":"mint","inputs":[{"type":"uint256"}]},
+            {"type":"event","name":"Minted","inputs":[{"name":"amount","type":"uint256"}]}]"#);
+        let v2 = abi(
+            r#"[{"name":"mint","inputs":[{"type":"uint256"},{"type":"address"}]},
+            {"type":"event","name":"Minted","inputs":[{"name":"to","type":"address","indexed":true},
+            {"name":"amount","type":"uint256"}]}]"#,
+        );
+        // recorded out of order
+        AbiHistory::new()
+            .version(proxy, 200, "v2", v2)
+            .version(proxy, 100, "v1", v1)
+    }
+
+    #[test]
+    fn version_at_test() {
+        let history = history();
+        let proxy = Address([0xaa; 20]);
+        let names = |block| history.version_at(&proxy, block).map(|v| v.name.as_str());
+        assert_eq!(names(99), None);
+        assert_eq!(names(100), Some("v1"));
+        assert_eq!(names(199), Some("v1"));
+        assert_eq!(names(200), Some("v2"));
+        assert_eq!(names(u64::MAX), Some("v2"));
+        assert!(history.versions(&Address::ZERO).is_empty());
+
+        let replaced = history.version(proxy, 100, "v1.1", abi("[]"));
+        assert_eq!(replaced.versions(&proxy).len(), 2);
+        assert_eq!(replaced.versions(&proxy)[0].name, "v1.1");
+    }
+
+    #[test]
+    fn decode_test() {
+        let history = history();
+        let proxy = Address([0xaa; 20]);
+        let mut calldata = selector("mint(uint256)").0.to_vec();
+        calldata.extend_from_slice(&U256::ONE.0);
+
+        let call = history.decode_call(&proxy, 150, &calldata).unwrap();
+        assert_eq!((call.abi, call.arguments.len()), ("v1", 1));
+        // the upgrade changed the signature of mint
+        assert!(history.decode_call(&proxy, 250, &calldata).is_err());
+        assert!(history.decode_call(&proxy, 50, &calldata).is_err());
+
+        let log = Log {
+            address: proxy,
+            topics: vec![crate::event_topic("Minted(uint256)")],
+            data: U256::ONE.0.to_vec(),
+        };
+        assert_eq!(history.decode_log(&log, 100).unwrap().abi, "v1");
+        assert!(history.decode_log(&log, 200).is_err());
+    }
+}
@@ -0,0 +1,349 @@
+use crate::{EthereumTypes, ParamType, U256};
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// A signed 256 bit number stored as 32 big-endian bytes in two's complement, exactly like an ABI
+/// encoded `int256` word.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct I256(pub [u8; 32]);
+
+impl I256 {
+    pub const ZERO: I256 = I256([0_u8; 32]);
+    pub const MINUS_ONE: I256 = I256([0xff_u8; 32]);
+
+    pub const ONE: I256 = I256(U256::ONE.0);
+
+    pub const MAX: I256 = {
+        let mut bytes = [0xff_u8; 32];
+        bytes[0] = 0x7f;
+        I256(bytes)
+    };
+
+    pub const MIN: I256 = {
+        let mut bytes = [0_u8; 32];
+        bytes[0] = 0x80;
+        I256(bytes)
+    };
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    #[inline]
+    pub fn is_negative(&self) -> bool {
+        self.0[0] & 0x80 != 0
+    }
+
+    /// The absolute value, which always fits into a `U256`, even for [`I256::MIN`].
+    pub fn unsigned_abs(self) -> U256 {
+        if self.is_negative() {
+            self.wrapping_neg().into_raw()
+        } else {
+            self.into_raw()
+        }
+    }
+
+    pub fn checked_neg(self) -> Option<I256> {
+        match self {
+            Self::MIN => None,
+            value => Some(value.wrapping_neg()),
+        }
+    }
+
+    pub fn checked_add(self, rhs: I256) -> Option<I256> {
+        let sum = I256(self.into_raw().overflowing_add(rhs.into_raw()).0 .0);
+        // adding numbers of the same sign overflowed if the sign of the sum differs
+        if self.is_negative() == rhs.is_negative() && sum.is_negative() != self.is_negative() {
+            None
+        } else {
+            Some(sum)
+        }
+    }
+
+    pub fn checked_sub(self, rhs: I256) -> Option<I256> {
+        let difference = I256(self.into_raw().overflowing_sub(rhs.into_raw()).0 .0);
+        if self.is_negative() != rhs.is_negative() && difference.is_negative() != self.is_negative()
+        {
+            None
+        } else {
+            Some(difference)
+        }
+    }
+
+    pub fn checked_mul(self, rhs: I256) -> Option<I256> {
+        let magnitude = self.unsigned_abs().checked_mul(rhs.unsigned_abs())?;
+        Self::from_sign_and_abs(self.is_negative() != rhs.is_negative(), magnitude)
+    }
+
+    // the number with the given sign and absolute value, if it fits into 256 bits
+    fn from_sign_and_abs(negative: bool, magnitude: U256) -> Option<I256> {
+        let value = I256(magnitude.0);
+        match (negative, value.is_negative()) {
+            (false, false) => Some(value),
+            (false, true) => None,
+            (true, _) if magnitude.0 == Self::MIN.0 => Some(Self::MIN),
+            (true, true) => None,
+            (true, false) => Some(value.wrapping_neg()),
+        }
+    }
+
+    fn wrapping_neg(self) -> I256 {
+        I256((!self.into_raw()).overflowing_add(U256::ONE).0 .0)
+    }
+
+    #[inline]
+    fn into_raw(self) -> U256 {
+        U256(self.0)
+    }
+}
+
+/// Negative numbers are smaller than non-negative ones, otherwise two's complement words compare
+/// like unsigned numbers.
+impl Ord for I256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => self.0.cmp(&other.0),
+        }
+    }
+}
+
+impl PartialOrd for I256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<i64> for I256 {
+    fn from(value: i64) -> Self {
+        Self::from(i128::from(value))
+    }
+}
+
+impl From<i128> for I256 {
+    fn from(value: i128) -> Self {
+        let fill = if value < 0 { 0xff } else { 0 };
+        let mut bytes = [fill; 32];
+        bytes[16..].copy_from_slice(&value.to_be_bytes());
+        Self(bytes)
+    }
+}
+
+/// Fails for numbers of at least 2^255.
+impl TryFrom<U256> for I256 {
+    type Error = String;
+
+    fn try_from(value: U256) -> Result<Self, Self::Error> {
+        Self::from_sign_and_abs(false, value)
+            .ok_or_else(|| "Number doesn't fit into an int256.".to_owned())
+    }
+}
+
+impl From<I256> for EthereumTypes {
+    fn from(value: I256) -> Self {
+        EthereumTypes::I256(value.0)
+    }
+}
+
+/// Formats the number in decimal, with a leading `-` if negative.
+impl fmt::Display for I256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.unsigned_abs().to_string();
+        f.pad_integral(!self.is_negative(), "", &digits)
+    }
+}
+
+/// Decimal if the number fits into 128 bits, `0x` prefixed hex of the two's complement word
+/// otherwise.
+#[cfg(feature = "defmt")]
+impl defmt::Format for I256 {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // an i128 if the upper half and the sign bit of the lower half are the sign extension
+        let fill = if self.is_negative() { 0xff } else { 0x00 };
+        if self.0[..16].iter().all(|b| *b == fill) && self.0[16] & 0x80 == fill & 0x80 {
+            let mut low = [0_u8; 16];
+            low.copy_from_slice(&self.0[16..]);
+            defmt::write!(f, "{=i128}", i128::from_be_bytes(low))
+        } else {
+            let mut hex = [0_u8; 66];
+            defmt::write!(
+                f,
+                "{=str}",
+                crate::hex::encode_prefixed_into(&self.0, &mut hex)
+            )
+        }
+    }
+}
+
+/// Parses a decimal number with an optional `-` or `+` sign, in any of the formats accepted by
+/// [`U256`], e.g. `-2.5e9`.
+impl FromStr for I256 {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let magnitude = digits
+            .parse::<U256>()
+            .map_err(|e| format!("Invalid number {}: {}", s, e))?;
+        Self::from_sign_and_abs(negative, magnitude)
+            .ok_or_else(|| format!("Number {} doesn't fit into an int256.", s))
+    }
+}
+
+/// A signed number of `BITS` bits, i.e. the value of an `intBITS` ABI type.
+///
+/// `BITS` has to be a multiple of 8 between 8 and 256, which is checked at compile time, and the
+/// value is range checked at construction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Int<const BITS: usize>(I256);
+
+impl<const BITS: usize> Int<BITS> {
+    pub const ZERO: Self = Self(I256::ZERO);
+
+    pub const MAX: Self = {
+        assert!(
+            BITS.is_multiple_of(8) && BITS >= 8 && BITS <= 256,
+            "Integer types have a multiple of 8 bits between 8 and 256"
+        );
+        let mut bytes = [0xff_u8; 32];
+        let mut i = 0;
+        while i < 32 - BITS / 8 {
+            bytes[i] = 0;
+            i += 1;
+        }
+        bytes[32 - BITS / 8] = 0x7f;
+        Self(I256(bytes))
+    };
+
+    pub const MIN: Self = {
+        let mut bytes = [0_u8; 32];
+        let mut i = 0;
+        while i < 32 - BITS / 8 {
+            bytes[i] = 0xff;
+            i += 1;
+        }
+        bytes[32 - BITS / 8] = 0x80;
+        Self(I256(bytes))
+    };
+
+    /// Fails if the value doesn't fit into `BITS` bits.
+    pub fn new(value: I256) -> Result<Self, String> {
+        if value < Self::MIN.0 || value > Self::MAX.0 {
+            return Err(format!("Value doesn't fit into an int{}.", BITS));
+        }
+        Ok(Self(value))
+    }
+
+    #[inline]
+    pub fn value(self) -> I256 {
+        self.0
+    }
+
+    /// The ABI type of the values, e.g. `int24` for `Int<24>`.
+    pub fn param_type() -> ParamType {
+        ParamType::Int(BITS)
+    }
+}
+
+impl<const BITS: usize> TryFrom<I256> for Int<BITS> {
+    type Error = String;
+
+    fn try_from(value: I256) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl<const BITS: usize> TryFrom<i64> for Int<BITS> {
+    type Error = String;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        Self::new(I256::from(value))
+    }
+}
+
+impl<const BITS: usize> From<Int<BITS>> for I256 {
+    fn from(value: Int<BITS>) -> Self {
+        value.0
+    }
+}
+
+impl<const BITS: usize> From<Int<BITS>> for EthereumTypes {
+    fn from(value: Int<BITS>) -> Self {
+        EthereumTypes::I256(value.0 .0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sized_int_test() {
+        assert_eq!(Int::<24>::MAX.value(), I256::from((1_i64 << 23) - 1));
+        assert_eq!(Int::<24>::MIN.value(), I256::from(-(1_i64 << 23)));
+        assert_eq!(Int::<256>::MAX.value(), I256::MAX);
+        assert_eq!(Int::<256>::MIN.value(), I256::MIN);
+        assert!(Int::<24>::try_from(1_i64 << 23).is_err());
+        assert!(Int::<24>::try_from(-(1_i64 << 23) - 1).is_err());
+        assert!(Int::<8>::try_from(-128_i64).is_ok());
+
+        assert_eq!(Int::<24>::param_type().name_as_str(), "int24");
+        let token = EthereumTypes::from(Int::<40>::try_from(-7_i64).unwrap());
+        assert!(token.type_check(&Int::<40>::param_type()));
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        let (a, b) = (I256::from(-7_i64), I256::from(3_i64));
+        assert_eq!(a.checked_add(b), Some(I256::from(-4_i64)));
+        assert_eq!(b.checked_sub(a), Some(I256::from(10_i64)));
+        assert_eq!(a.checked_mul(b), Some(I256::from(-21_i64)));
+        assert_eq!(a.checked_mul(a), Some(I256::from(49_i64)));
+        assert_eq!(a.checked_neg(), Some(I256::from(7_i64)));
+        assert_eq!(a.unsigned_abs(), U256::from(7_u64));
+
+        assert_eq!(I256::MAX.checked_add(I256::ONE), None);
+        assert_eq!(I256::MIN.checked_sub(I256::ONE), None);
+        assert_eq!(I256::MIN.checked_neg(), None);
+        assert_eq!(I256::MIN.checked_mul(I256::MINUS_ONE), None);
+        assert_eq!(I256::MAX.checked_add(I256::MIN), Some(I256::MINUS_ONE));
+        assert_eq!(I256::MIN.unsigned_abs(), U256::ONE << 255);
+    }
+
+    #[test]
+    fn ordering_test() {
+        assert!(I256::MIN < I256::MINUS_ONE);
+        assert!(I256::MINUS_ONE < I256::ZERO);
+        assert!(I256::ZERO < I256::MAX);
+        assert!(I256::from(-2_i64) < I256::from(-1_i64));
+        assert!(I256::from(i128::MIN) < I256::from(i64::MIN));
+    }
+
+    #[test]
+    fn decimal_test() {
+        assert_eq!(I256::from(-1234_i64).to_string(), "-1234");
+        assert_eq!(I256::ZERO.to_string(), "0");
+        let min = "-57896044618658097711785492504343953926634992332820282019728792003956564819968";
+        assert_eq!(I256::MIN.to_string(), min);
+        assert_eq!(min.parse::<I256>(), Ok(I256::MIN));
+        assert_eq!("+42".parse::<I256>(), Ok(I256::from(42_i64)));
+        assert_eq!("-0".parse::<I256>(), Ok(I256::ZERO));
+        assert_eq!("-1.5e3".parse::<I256>(), Ok(I256::from(-1500_i64)));
+        // MAX + 1
+        assert!(
+            "57896044618658097711785492504343953926634992332820282019728792003956564819968"
+                .parse::<I256>()
+                .is_err()
+        );
+        assert!("--1".parse::<I256>().is_err());
+        assert!(I256::try_from(U256::ONE << 255).is_err());
+    }
+}
@@ -1,93 +1,315 @@
 #![allow(unused)]
+// lets the derive macros refer to `::zgen_abi` inside of this crate too
+extern crate self as zgen_abi;
+
+mod abi;
+mod access_list;
+mod address;
+mod batch;
+pub mod bloom;
+mod bounded;
+mod bytecode;
+mod call;
+mod calldata;
+mod chain;
+mod codec;
+mod create;
+#[cfg(all(feature = "ethabi-check", debug_assertions))]
+mod crosscheck;
+mod diamond;
+mod dispatch;
+mod eip3009;
+mod eip712;
+mod embed;
+mod error;
+mod event;
+mod fallback;
+mod filter;
+mod fixed;
+pub mod gas;
+mod governor;
+#[cfg(feature = "graphql")]
+mod graphql;
+pub mod hex;
+mod history;
+mod int;
+mod log;
+mod markdown;
+mod metadata;
+mod mock;
+mod multicall;
+mod natspec;
+#[cfg(feature = "presets")]
+pub mod presets;
+mod proxy;
+pub mod registry;
+mod revert;
+mod rlp;
+mod seaport;
+mod selector;
+mod signature;
+mod source;
+mod standards;
+mod state_override;
+pub mod storage;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod timelock;
+mod token;
+mod tx;
+mod typed_data;
 mod types;
-pub use types::EthereumTypes;
+#[cfg(feature = "typescript")]
+mod typescript;
+mod uint;
+mod uniswap;
+mod validate;
+pub use abi::{Abi, Event, Function, OwnedAbi, Param};
+pub use access_list::{
+    AccessList, AccessListItem, ACCESS_LIST_ADDRESS_GAS, ACCESS_LIST_STORAGE_KEY_GAS,
+};
+pub use address::{
+    parse_address, parse_caip10, parse_icap, parse_prefixed_address, to_caip10, to_checksum,
+    to_icap, to_prefixed_address, Address,
+};
+pub use batch::{interface_id, CallBatch, InterfaceProbe};
+pub use bounded::{decode_bounded, BoundedDecodeError, BoundedKind, BoundedValue, WordKind};
+pub use bytecode::{link_bytecode, match_selectors, push4_selectors};
+pub use call::EthCall;
+pub use calldata::{validate_calldata, CallData, CalldataError};
+pub use chain::ChainId;
+pub use codec::{decode, encode, encode_packed};
+pub use create::{
+    create2_address, create3_address, create_address, deterministic_deployment_address,
+    deterministic_deployment_data, CREATE3_PROXY_INIT_CODE, CREATE3_PROXY_INIT_CODE_HASH,
+    DETERMINISTIC_DEPLOYER,
+};
+pub use diamond::{
+    decode_facet_function_selectors, decode_facets, encode_diamond_cut, merge_facet_abis,
+    selector_facets, Facet, FacetCut, FacetCutAction,
+};
+pub use dispatch::{dispatch_order, dispatch_suffix, mine_selector, DispatchEntry};
+pub use eip3009::TransferAuthorization;
+pub use eip712::{encode_member, hash_struct, type_hash, typed_data_hash, Eip712Domain};
+pub use error::EthError;
+pub use event::EthEvent;
+pub use fallback::{DecodedCall, DecodedLog, FallbackDecoder};
+pub use filter::{TopicFilter, MAX_TOPICS};
+pub use fixed::FixedCall;
+pub use governor::{Proposal, ProposalAction};
+#[cfg(feature = "graphql")]
+pub use graphql::to_graphql;
+pub use history::{AbiHistory, AbiVersion};
+pub use int::{Int, I256};
+pub use log::Log;
+pub use markdown::to_markdown;
+pub use metadata::{strip_metadata, BytecodeMetadata};
+pub use mock::{encode_revert_reason, MockResponder};
+pub use multicall::{explode_multicall, SubCall};
+pub use natspec::{DevDoc, DevMember, NatSpec, UserDoc, UserMember};
+pub use proxy::{
+    clone_immutable_args, clone_with_immutable_args_creation_code,
+    clone_with_immutable_args_init_code_hash, encode_upgrade_to, encode_upgrade_to_and_call,
+    is_proxiable_uuid, minimal_proxy_creation_code, minimal_proxy_implementation,
+    minimal_proxy_init_code_hash, minimal_proxy_runtime_code, slot_address, EIP1967_ADMIN_SLOT,
+    EIP1967_BEACON_SLOT, EIP1967_IMPLEMENTATION_SLOT, IMPLEMENTATION_SELECTOR,
+    MINIMAL_PROXY_CREATION_LEN, MINIMAL_PROXY_RUNTIME_LEN, PROXIABLE_UUID_SELECTOR,
+};
+pub use revert::{RevertDecoder, RevertReason};
+pub use seaport::{
+    seaport_domain, ConsiderationItem, ItemType, OfferItem, OrderComponents, OrderType, SEAPORT_1_6,
+};
+#[cfg(feature = "lru")]
+pub use selector::SIGNATURE_CACHE_CAPACITY;
+pub use selector::{event_topic, selector, Selector};
+#[cfg(feature = "subtle")]
+pub use signature::constant_time_eq;
+pub use signature::{Signature, SECP256K1_N};
+pub use source::{AbiSource, Fetch};
+pub use standards::{detect_standards, Standard, StandardReport};
+pub use state_override::{eth_call_params, AccountOverride, StateOverride};
+pub use timelock::TimelockOperation;
+pub use token::{encode_call, AbiDecode, AbiEncode, AbiType, AbiValueType, ArrayElement};
+pub use tx::UnsignedTransaction;
+pub use typed_data::{TypedData, TypedDataField};
+pub use types::{EthereumTypes, ParamType};
+#[cfg(feature = "typescript")]
+pub use typescript::to_typescript;
+pub use uint::{Uint, U128, U16, U160, U256, U32, U64, U8, U96};
+pub use uniswap::{uniswap_v3_path, MAX_UNISWAP_V3_FEE};
+pub use validate::{parse_abi_strict, validate_abi, ValidationError};
+#[cfg(feature = "derive")]
+pub use zgen_abi_derive::{sol, EthError, EthEvent};
 
-use sha3::{Digest, Keccak256};
+// items used by the code generated by the derive macros
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::call::param_types;
+    pub use crate::embed::parse_embedded_abi;
+    pub use crate::error::encode_error;
+    pub use crate::event::decode_topic;
+}
 
-use std::fs::File;
-use std::io::BufReader;
+use std::borrow::Cow;
 use std::path::Path;
 
-pub fn transaction(
+// canonical input types of the called function, kept on the stack for typical calls
+#[cfg(feature = "smallvec")]
+type InputTypes<'a> = smallvec::SmallVec<[Cow<'a, str>; 4]>;
+#[cfg(not(feature = "smallvec"))]
+type InputTypes<'a> = Vec<Cow<'a, str>>;
+
+/// Encodes a call of `function_name` with the ABI json file at `path_to_abi`, which is read and
+/// parsed on every call. Load it once with [`Abi::from_file`] and use [`Abi::encode`] to encode
+/// many calls.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(path = %path_to_abi.display(), function = function_name),
+        err
+    )
+)]
+pub fn transaction<A: AsRef<[EthereumTypes]>>(
     path_to_abi: &Path,
     function_name: &str,
-    arguments: Vec<EthereumTypes>,
+    arguments: A,
 ) -> Result<Vec<u8>, String> {
-    let file = File::open(path_to_abi).map_err(|e| format!("Couldn't open file: {}", e))?;
-    let reader = BufReader::new(file);
-    let functions: serde_json::Value =
-        serde_json::from_reader(reader).map_err(|e| format!("Couldn't parse json: {}", e))?;
+    let mut json = read_abi_file(path_to_abi)?;
+    let abi = parse_abi(&mut json)?;
+    registry::register_loaded(path_to_abi, &abi);
+    transaction_with_abi(&abi, function_name, arguments)
+}
 
-    let mut i: usize = 0;
-    let mut function_found: bool = false;
+/// Same as [`transaction`] with the ABI loaded from any [`AbiSource`], fetching remote sources with
+/// `fetch`.
+pub fn transaction_from_source<F: Fetch, A: AsRef<[EthereumTypes]>>(
+    source: &AbiSource,
+    fetch: &F,
+    function_name: &str,
+    arguments: A,
+) -> Result<Vec<u8>, String> {
+    let abi = source.load_with(fetch)?;
+    transaction_with_abi(&abi, function_name, arguments)
+}
 
+/// Same as [`transaction`] with an already parsed ABI, e.g. one embedded with [`include_abi!`].
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(function = function_name, arguments = arguments.as_ref().len()),
+        err
+    )
+)]
+pub fn transaction_with_abi<A: AsRef<[EthereumTypes]>>(
+    abi: &Abi,
+    function_name: &str,
+    arguments: A,
+) -> Result<Vec<u8>, String> {
+    let arguments = arguments.as_ref();
     // find the function name in the parsed json file
-    while functions[i] != serde_json::Value::Null {
-        if functions[i]["name"] == function_name && functions[i]["type"] == "function" {
-            function_found = true;
-            break;
-        }
-        i += 1;
-    }
-
-    // if the given function name was not found, return an error
-    if !function_found {
-        Err(format!(
+    let function = abi.function(function_name).ok_or_else(|| {
+        format!(
             "Function name {} not found in the ABI json file, or its type is not a function.",
             function_name
-        ))
-    } else {
-        let name = &functions[i]["name"];
-        let mut inputs = Vec::<&str>::new();
-        // list all the inputs of the file while iterating over input parameter list (lenght and types should match)
-        for (j, arg) in arguments.iter().enumerate() {
-            // if the j^th input type is a string, append it to the inputs
-            if let Some(s) = functions[i]["inputs"][j]["type"].as_str() {
-                // check whether the input arguments match such that we avoid the following example:
-                // expected arguments: vec![Address, Address, U256]
-                // but the given inputs: vec!["address", "uint256", "address"]
-                if s != arg.name_as_str() {
-                    return Err(format!(
-                        "Input arguments doesn't match. Expected {}, found {}.",
-                        s,
-                        arg.name_as_str()
-                    ));
-                }
-                inputs.push(s);
-            } else {
-                return Err(format!(
-                    "Input type of function {} was not a String. ABI is not properly formatted.",
-                    name
-                ));
-            }
-        }
+        )
+    })?;
 
-        // construct the complete function signature
-        let mut signature = name.as_str().unwrap().to_owned() + "(";
-        for inp in inputs.iter() {
-            signature.push_str(inp);
-            signature.push(',');
+    let name = &function.name;
+    // a call with fewer arguments would silently select a different overload
+    if arguments.len() != function.inputs.len() {
+        return Err(format!(
+            "Function {} takes {} input arguments, found {}.",
+            name,
+            function.inputs.len(),
+            arguments.len()
+        ));
+    }
+    let mut inputs = InputTypes::new();
+    // list all the inputs of the file while iterating over input parameter list (types should match)
+    for (arg, input) in arguments.iter().zip(&function.inputs) {
+        let s = input.canonical_type();
+        // check whether the input arguments match such that we avoid the following example:
+        // expected arguments: vec![Address, Address, U256]
+        // but the given inputs: vec!["address", "uint256", "address"]
+        let matches = ParamType::from_name(&s).is_some_and(|kind| arg.type_check(&kind));
+        if !matches {
+            return Err(format!(
+                "Input arguments doesn't match. Expected {}, found {}.",
+                s,
+                arg.name_as_str()
+            ));
         }
-        signature.pop(); // pop the last ',' character as it is not needed
-        signature.push(')'); // pus the closing parenthesis
+        inputs.push(s);
+    }
 
-        // perform the keccak hashing
-        let mut keccak = Keccak256::new();
-        keccak.update(signature);
+    // take the first 4 bytes of the keccak hash representing the function signature
+    let mut first_4_bytes = Vec::with_capacity(4 + 32 * arguments.len());
+    let selector = selector::function_selector(name, inputs.iter().map(|t| t.as_ref()));
+    first_4_bytes.extend_from_slice(selector.as_bytes());
 
-        // take the first 4 bytes representing the function signature
-        let mut first_4_bytes = (&keccak.finalize()[0..4]).to_vec();
+    // append the provided input parameters
+    first_4_bytes.extend_from_slice(&encode(arguments));
 
-        // append the provided input parameters
-        for arg in arguments {
-            first_4_bytes.extend_from_slice(&arg.value_as_u256());
-        }
+    #[cfg(feature = "tracing")]
+    tracing::debug!(bytes = first_4_bytes.len(), "encoded call");
+    Ok(first_4_bytes)
+}
 
-        Ok(first_4_bytes)
-    }
+// read the whole ABI json file, the parsed ABI borrows its strings from this buffer
+#[cfg(not(feature = "mmap"))]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, err)
+)]
+pub(crate) fn read_abi_file(path_to_abi: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path_to_abi).map_err(|e| format!("Couldn't open file: {}", e))
+}
+
+// memory-map the ABI json file instead of reading it, which avoids copying very large
+// combined-json and artifact files into the heap up front
+#[cfg(feature = "mmap")]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, err)
+)]
+pub(crate) fn read_abi_file(path_to_abi: &Path) -> Result<memmap2::MmapMut, String> {
+    let file =
+        std::fs::File::open(path_to_abi).map_err(|e| format!("Couldn't open file: {}", e))?;
+    // SAFETY: the mapping is private (copy-on-write), so in-place parsing never writes back to
+    // the file. The file must not be truncated by another process while the ABI is parsed.
+    unsafe { memmap2::MmapOptions::new().map_copy(&file) }
+        .map_err(|e| format!("Couldn't map file: {}", e))
+}
+
+#[cfg(not(feature = "simd-json"))]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(bytes = json.len()))
+)]
+pub(crate) fn parse_abi(json: &mut [u8]) -> Result<Abi<'_>, String> {
+    serde_json::from_slice(json)
+        .map(|json: abi::AbiJson| json.0)
+        .map_err(|e| format!("Couldn't parse json: {}", e))
+}
+
+// simd-json is considerably faster on multi-megabyte artifact files, it parses the buffer in place
+#[cfg(feature = "simd-json")]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(bytes = json.len()))
+)]
+pub(crate) fn parse_abi(json: &mut [u8]) -> Result<Abi<'_>, String> {
+    simd_json::serde::from_slice(json)
+        .map(|json: abi::AbiJson| json.0)
+        .map_err(|e| format!("Couldn't parse json: {}", e))
 }
 
 #[cfg(test)]
+#[allow(clippy::mixed_case_hex_literals)]
 mod tests {
     use super::*;
     #[test]
@@ -98,7 +320,7 @@ mod tests {
             0x30, 0xE7, 0xd7, 0xFf, 0xF8, 0x5C, 0x8d, 0x0E, 0x77, 0x51, 0x40, 0xb1, 0xaD, 0x93,
             0xC2, 0x30, 0xD5, 0x59, 0x52, 0x07,
         ])];
-        let t = transaction(&path, function_name, arguments).unwrap();
+        let t = transaction(path, function_name, arguments).unwrap();
         assert_eq!(
             t,
             vec![
@@ -121,7 +343,7 @@ mod tests {
             EthereumTypes::u256_from_bytes(&20000000000_u64.to_be_bytes()),
         ];
         assert_eq!(
-            transaction(&path, function_name, arguments).unwrap(),
+            transaction(path, function_name, arguments).unwrap(),
             vec![
                 0xa9, 0x05, 0x9c, 0xbb, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
                 0x00, 0x00, 0x30, 0xe7, 0xd7, 0xff, 0xf8, 0x5c, 0x8d, 0x0e, 0x77, 0x51, 0x40, 0xb1,
@@ -132,6 +354,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn array_arguments_test() {
+        let path = Path::new("src/rust_abi.json");
+        let address = [0x11_u8; 20];
+        assert_eq!(
+            transaction(path, "balanceOf", [EthereumTypes::Address(address)]).unwrap(),
+            transaction(path, "balanceOf", vec![EthereumTypes::Address(address)]).unwrap()
+        );
+    }
+
     #[test]
     fn allowance_test() {
         let path = Path::new("src/rust_abi.json");
@@ -147,7 +379,7 @@ mod tests {
             ]),
         ];
         assert_eq!(
-            transaction(&path, function_name, arguments).unwrap(),
+            transaction(path, function_name, arguments).unwrap(),
             vec![
                 0xdd, 0x62, 0xed, 0x3e, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
                 0x00, 0x00, 0x30, 0xe7, 0xd7, 0xff, 0xf8, 0x5c, 0x8d, 0x0e, 0x77, 0x51, 0x40, 0xb1,
@@ -157,6 +389,52 @@ mod tests {
             ]
         );
     }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_test() {
+        use std::sync::{Arc, Mutex};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata};
+
+        // records the names of the created spans
+        #[derive(Clone, Default)]
+        struct Spans(Arc<Mutex<Vec<&'static str>>>);
+
+        impl tracing::Subscriber for Spans {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut names = self.0.lock().unwrap();
+                names.push(span.metadata().name());
+                Id::from_u64(names.len() as u64)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, _: &Event<'_>) {}
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let spans = Spans::default();
+        let arguments = [EthereumTypes::Address([0x11; 20])];
+        tracing::subscriber::with_default(spans.clone(), || {
+            transaction(Path::new("src/rust_abi.json"), "balanceOf", arguments).unwrap();
+            decode(&[ParamType::Address], &[0; 32]).unwrap();
+        });
+        assert_eq!(
+            *spans.0.lock().unwrap(),
+            [
+                "transaction",
+                "read_abi_file",
+                "parse_abi",
+                "transaction_with_abi",
+                "encode",
+                "decode"
+            ]
+        );
+    }
 }
 
 // NOTE TEST CASES
@@ -0,0 +1,12 @@
+use crate::Address;
+
+/// A log emitted by a contract, as returned in receipts and by `eth_getLogs`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Log {
+    /// Address of the emitting contract.
+    pub address: Address,
+    /// At most 4 topics, the first one is the event topic unless the event is anonymous.
+    pub topics: Vec<[u8; 32]>,
+    /// ABI encoded non-indexed event parameters.
+    pub data: Vec<u8>,
+}
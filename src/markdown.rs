@@ -0,0 +1,145 @@
+use crate::{hex, Abi, Param};
+
+use std::fmt::Write;
+
+/// Renders a Markdown reference of the ABI's functions and events under the given title.
+///
+/// Functions are listed with their selector, state mutability, inputs and outputs, events with
+/// their topic and parameters. NatSpec attached with [`Abi::attach_natspec`] is included.
+pub fn to_markdown(abi: &Abi, title: &str) -> String {
+    let mut md = format!("# {}\n", title);
+
+    if !abi.functions.is_empty() {
+        md.push_str("\n## Functions\n");
+    }
+    for function in abi.functions.iter() {
+        // writing into a String never fails
+        let _ = write!(
+            md,
+            "\n### `{}`\n\n- Selector: `{}`\n- State mutability: `{}`\n",
+            function.signature(),
+            hex::encode_prefixed(function.selector()),
+            function.state_mutability
+        );
+        natspec(&mut md, &function.notice, &function.details);
+        params_table(&mut md, "Inputs", &function.inputs, false);
+        params_table(&mut md, "Outputs", &function.outputs, false);
+    }
+
+    if !abi.events.is_empty() {
+        md.push_str("\n## Events\n");
+    }
+    for event in abi.events.iter() {
+        let _ = write!(md, "\n### `{}`\n\n", event.signature());
+        if event.anonymous {
+            md.push_str("- Anonymous, logs have no topic for the signature\n");
+        } else {
+            let _ = writeln!(md, "- Topic: `{}`", hex::encode_prefixed(event.topic()));
+        }
+        natspec(&mut md, &event.notice, &event.details);
+        params_table(&mut md, "Parameters", &event.inputs, true);
+    }
+
+    md
+}
+
+// the notice and developer details as separate paragraphs
+fn natspec(md: &mut String, notice: &Option<String>, details: &Option<String>) {
+    for paragraph in notice.iter().chain(details.iter()) {
+        let _ = write!(md, "\n{}\n", paragraph);
+    }
+}
+
+// a table of the parameters preceded by a bold caption, nothing for an empty list. The
+// description column is only added if at least one parameter is documented.
+fn params_table(md: &mut String, caption: &str, params: &[Param], indexed: bool) {
+    if params.is_empty() {
+        return;
+    }
+    let documented = params.iter().any(|p| p.doc.is_some());
+    let _ = write!(md, "\n**{}**\n\n| Name | Type |", caption);
+    if indexed {
+        md.push_str(" Indexed |");
+    }
+    if documented {
+        md.push_str(" Description |");
+    }
+    md.push_str("\n|---|---|");
+    if indexed {
+        md.push_str("---|");
+    }
+    if documented {
+        md.push_str("---|");
+    }
+    md.push('\n');
+
+    for param in params.iter() {
+        let name = if param.name.is_empty() {
+            "-"
+        } else {
+            &param.name
+        };
+        let _ = write!(md, "| `{}` | `{}` |", name, param.canonical_type());
+        if indexed {
+            md.push_str(if param.indexed { " yes |" } else { " no |" });
+        }
+        if documented {
+            let _ = write!(md, " {} |", cell(param.doc.as_deref().unwrap_or("")));
+        }
+        md.push('\n');
+    }
+}
+
+// text of a table cell, where a `|` would start the next cell and a newline end the row
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_test() {
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        let abi: Abi = serde_json::from_str(&json).unwrap();
+        let md = to_markdown(&abi, "ERC-20");
+
+        assert!(md.starts_with("# ERC-20\n\n## Functions\n"));
+        assert!(md.contains(
+            "### `transfer(address,uint256)`\n\n- Selector: `0xa9059cbb`\n- State mutability: `nonpayable`\n"
+        ));
+        assert!(md.contains("| `_to` | `address` |\n"));
+        assert!(md.contains(
+            "- Topic: `0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef`"
+        ));
+        assert!(md.contains("| `from` | `address` | yes |\n"));
+    }
+
+    #[test]
+    fn natspec_markdown_test() {
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        let mut abi: Abi = serde_json::from_str(&json).unwrap();
+        let natspec = crate::NatSpec::from_artifact(
+            r#"{
+                "devdoc": { "methods": {
+                    "balanceOf(address)": { "params": { "_owner": "The holder." } },
+                    "transfer(address,uint256)": { "params": { "_to": "Recipient | receiver,\nnot zero." } }
+                } },
+                "userdoc": { "methods": { "balanceOf(address)": { "notice": "Balance of a holder." } } }
+            }"#,
+        )
+        .unwrap();
+        abi.attach_natspec(&natspec);
+        let md = to_markdown(&abi, "ERC-20");
+
+        assert!(md.contains("- State mutability: `view`\n\nBalance of a holder.\n\n**Inputs**"));
+        assert!(md.contains(
+            "| Name | Type | Description |\n|---|---|---|\n| `_owner` | `address` | The holder. |\n"
+        ));
+        // cell content can't break the table
+        assert!(md.contains("| `_to` | `address` | Recipient \\| receiver,<br>not zero. |\n"));
+    }
+}
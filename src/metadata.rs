@@ -0,0 +1,221 @@
+use crate::hex;
+
+/// Compiler metadata appended to runtime bytecode by solc, as a CBOR map followed by its length.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BytecodeMetadata {
+    /// Multihash of the metadata file on IPFS.
+    pub ipfs: Option<Vec<u8>>,
+    /// Swarm hash of the metadata file, from solc 0.4.7 to 0.5.
+    pub bzzr0: Option<[u8; 32]>,
+    /// Swarm hash of the metadata file, from solc 0.5.9 to 0.6.
+    pub bzzr1: Option<[u8; 32]>,
+    /// Compiler version like `0.8.24`, or the full version string of prerelease builds.
+    pub solc: Option<String>,
+    /// Whether experimental features were enabled.
+    pub experimental: bool,
+}
+
+impl BytecodeMetadata {
+    /// Parses the metadata at the end of the runtime bytecode.
+    pub fn from_runtime_code(code: &[u8]) -> Result<Self, String> {
+        let cbor = metadata_section(code)?;
+        let mut reader = Reader { data: cbor, pos: 0 };
+        let entries = match reader.header()? {
+            (MAP, len) => len,
+            _ => return Err("Bytecode metadata is not a CBOR map.".to_owned()),
+        };
+
+        let mut metadata = Self::default();
+        for _ in 0..entries {
+            let key = match reader.value()? {
+                Value::Text(key) => key,
+                _ => return Err("Bytecode metadata has a non-text key.".to_owned()),
+            };
+            match (key, reader.value()?) {
+                ("ipfs", Value::Bytes(hash)) => metadata.ipfs = Some(hash.to_vec()),
+                ("bzzr0", Value::Bytes(hash)) => metadata.bzzr0 = Some(swarm_hash(hash)?),
+                ("bzzr1", Value::Bytes(hash)) => metadata.bzzr1 = Some(swarm_hash(hash)?),
+                ("solc", Value::Bytes(&[major, minor, patch])) => {
+                    metadata.solc = Some(format!("{}.{}.{}", major, minor, patch))
+                }
+                ("solc", Value::Text(version)) => metadata.solc = Some(version.to_owned()),
+                ("experimental", Value::Bool(experimental)) => metadata.experimental = experimental,
+                (key @ ("ipfs" | "bzzr0" | "bzzr1" | "solc" | "experimental"), _) => {
+                    return Err(format!("Bytecode metadata has an invalid {} value.", key))
+                }
+                // unknown keys are skipped to stay compatible with future compilers
+                _ => {}
+            }
+        }
+        Ok(metadata)
+    }
+
+    /// The IPFS CIDv0 (`Qm…`) of the metadata file, e.g. to fetch it from Sourcify or a gateway.
+    pub fn ipfs_cid(&self) -> Option<String> {
+        self.ipfs.as_deref().map(base58)
+    }
+}
+
+/// The runtime bytecode without the metadata section, or the whole code if it has none.
+pub fn strip_metadata(code: &[u8]) -> &[u8] {
+    match metadata_section(code) {
+        Ok(cbor) => &code[..code.len() - cbor.len() - 2],
+        Err(_) => code,
+    }
+}
+
+// the CBOR encoded section, whose length is stored big endian in the last two bytes
+fn metadata_section(code: &[u8]) -> Result<&[u8], String> {
+    let tail = code
+        .len()
+        .checked_sub(2)
+        .ok_or("Bytecode is too short to contain metadata.")?;
+    let len = usize::from(u16::from_be_bytes([code[tail], code[tail + 1]]));
+    let start = tail
+        .checked_sub(len)
+        .ok_or("Bytecode metadata length exceeds the code size.")?;
+    if len == 0 || code[start] >> 5 != MAP {
+        return Err("Bytecode has no CBOR metadata.".to_owned());
+    }
+    Ok(&code[start..tail])
+}
+
+fn swarm_hash(hash: &[u8]) -> Result<[u8; 32], String> {
+    let mut array = [0_u8; 32];
+    if hash.len() != 32 {
+        return Err(format!(
+            "Swarm hash has to be 32 bytes, found {}: {}",
+            hash.len(),
+            hex::encode(hash)
+        ));
+    }
+    array.copy_from_slice(hash);
+    Ok(array)
+}
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+fn base58(bytes: &[u8]) -> String {
+    // base 58 digits in little-endian order
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for byte in bytes {
+        let mut carry = u32::from(*byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    // every leading zero byte is written as a leading 1
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    std::iter::repeat_n(b'1', zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|d| BASE58_ALPHABET[usize::from(*d)]),
+        )
+        .map(char::from)
+        .collect()
+}
+
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const MAP: u8 = 5;
+const SIMPLE: u8 = 7;
+
+enum Value<'a> {
+    Bytes(&'a [u8]),
+    Text(&'a str),
+    Bool(bool),
+}
+
+// reads the subset of CBOR emitted by solc
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or("Bytecode metadata is truncated.")?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    // major type and argument of the next item
+    fn header(&mut self) -> Result<(u8, usize), String> {
+        let initial = self.take(1)?[0];
+        let argument = match initial & 0x1f {
+            n @ 0..=23 => usize::from(n),
+            24 => usize::from(self.take(1)?[0]),
+            25 => usize::from(u16::from_be_bytes([self.take(1)?[0], self.take(1)?[0]])),
+            n => return Err(format!("Unsupported CBOR argument size {}.", n)),
+        };
+        Ok((initial >> 5, argument))
+    }
+
+    fn value(&mut self) -> Result<Value<'a>, String> {
+        match self.header()? {
+            (BYTES, len) => Ok(Value::Bytes(self.take(len)?)),
+            (TEXT, len) => std::str::from_utf8(self.take(len)?)
+                .map(Value::Text)
+                .map_err(|e| format!("Invalid CBOR text: {}", e)),
+            (SIMPLE, 20) => Ok(Value::Bool(false)),
+            (SIMPLE, 21) => Ok(Value::Bool(true)),
+            (major, _) => Err(format!("Unsupported CBOR major type {}.", major)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // STOP, then {"ipfs": h'1220 11…11', "solc": h'000818'} and its length
+    fn code() -> Vec<u8> {
+        let mut code = vec![0x00, 0xa2, 0x64];
+        code.extend_from_slice(b"ipfs");
+        code.extend_from_slice(&[0x58, 0x22, 0x12, 0x20]);
+        code.extend_from_slice(&[0x11; 32]);
+        code.push(0x64);
+        code.extend_from_slice(b"solc");
+        code.extend_from_slice(&[0x43, 0x00, 0x08, 0x18, 0x00, 0x33]);
+        code
+    }
+
+    #[test]
+    fn metadata_test() {
+        let metadata = BytecodeMetadata::from_runtime_code(&code()).unwrap();
+        assert_eq!(metadata.solc.as_deref(), Some("0.8.24"));
+        assert_eq!(metadata.ipfs.as_ref().map(Vec::len), Some(34));
+        assert_eq!(
+            metadata.ipfs_cid().as_deref(),
+            Some("QmPVGjYFugq4XUyBfoTHG6c3qxfBS26jEdaFM1gdAVuMZ2")
+        );
+        assert!(!metadata.experimental);
+        assert_eq!(metadata.bzzr1, None);
+        assert_eq!(strip_metadata(&code()), [0x00]);
+    }
+
+    #[test]
+    fn no_metadata_test() {
+        assert!(BytecodeMetadata::from_runtime_code(&[0x60, 0x00, 0x00, 0x02]).is_err());
+        assert!(BytecodeMetadata::from_runtime_code(&[0x00]).is_err());
+        assert!(BytecodeMetadata::from_runtime_code(&[0x00, 0xff]).is_err());
+        assert_eq!(strip_metadata(&[0x60, 0x00]), [0x60, 0x00]);
+    }
+
+    #[test]
+    fn base58_test() {
+        assert_eq!(base58(&[0, 0, 1]), "112");
+        assert_eq!(base58(&[]), "");
+    }
+}
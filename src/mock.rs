@@ -0,0 +1,157 @@
+use crate::{EthereumTypes, OwnedAbi, ParamType, Selector};
+
+use std::collections::HashMap;
+
+// selector of `Error(string)`, the revert reason of `require` and `revert("...")`
+const ERROR_SELECTOR: Selector = Selector([0x08, 0xc3, 0x79, 0xa0]);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Response {
+    Return(Vec<u8>),
+    Revert(Vec<u8>),
+}
+
+/// Answers encoded calldata with canned, properly encoded return data, as if it was sent to a
+/// contract implementing the ABI.
+///
+/// Downstream crates can use it to unit test their call pipelines without a node.
+#[derive(Clone, Debug)]
+pub struct MockResponder {
+    abi: OwnedAbi,
+    responses: HashMap<Selector, Response>,
+}
+
+impl MockResponder {
+    pub fn new(abi: OwnedAbi) -> Self {
+        Self {
+            abi,
+            responses: HashMap::new(),
+        }
+    }
+
+    /// Makes calls of the function return the given values, which have to match its outputs.
+    pub fn returns(
+        &mut self,
+        function_name: &str,
+        values: Vec<EthereumTypes>,
+    ) -> Result<(), String> {
+        let function = self
+            .abi
+            .function(function_name)
+            .ok_or_else(|| format!("Function name {} not found in the ABI.", function_name))?;
+        if function.outputs.len() != values.len() {
+            return Err(format!(
+                "Function {} returns {} values, found {}.",
+                function_name,
+                function.outputs.len(),
+                values.len()
+            ));
+        }
+        for (output, value) in function.outputs.iter().zip(values.iter()) {
+            let kind = output.canonical_type();
+            if !ParamType::from_name(&kind).is_some_and(|kind| value.type_check(&kind)) {
+                return Err(format!(
+                    "Return values doesn't match. Expected {}, found {}.",
+                    kind,
+                    value.name_as_str()
+                ));
+            }
+        }
+
+        let data = crate::encode(&values);
+        self.responses
+            .insert(function.selector(), Response::Return(data));
+        Ok(())
+    }
+
+    /// Makes calls of the function revert with `Error(reason)`.
+    pub fn reverts(&mut self, function_name: &str, reason: &str) -> Result<(), String> {
+        self.reverts_with(function_name, encode_revert_reason(reason))
+    }
+
+    /// Makes calls of the function revert with raw revert data, e.g. an encoded custom error.
+    pub fn reverts_with(&mut self, function_name: &str, data: Vec<u8>) -> Result<(), String> {
+        let function = self
+            .abi
+            .function(function_name)
+            .ok_or_else(|| format!("Function name {} not found in the ABI.", function_name))?;
+        self.responses
+            .insert(function.selector(), Response::Revert(data));
+        Ok(())
+    }
+
+    /// Answers the calldata with the canned return data, or the revert data as the error.
+    ///
+    /// Calls of unknown functions or functions without a canned response revert without data,
+    /// like a contract without a fallback function would.
+    pub fn respond(&self, calldata: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+        let selector = Selector::from_calldata(calldata).ok_or_else(Vec::new)?;
+        match self.responses.get(&selector) {
+            Some(Response::Return(data)) => Ok(data.clone()),
+            Some(Response::Revert(data)) => Err(data.clone()),
+            None => Err(Vec::new()),
+        }
+    }
+}
+
+/// Encodes `Error(string)` revert data carrying the reason.
+pub fn encode_revert_reason(reason: &str) -> Vec<u8> {
+    let padded_len = reason.len().div_ceil(32) * 32;
+    let mut data = Vec::with_capacity(4 + 64 + padded_len);
+    data.extend_from_slice(&ERROR_SELECTOR.0);
+    data.extend_from_slice(&EthereumTypes::u256_from_bytes(&[0x20]).value_as_u256());
+    data.extend_from_slice(
+        &EthereumTypes::u256_from_bytes(&(reason.len() as u64).to_be_bytes()).value_as_u256(),
+    );
+    data.extend_from_slice(reason.as_bytes());
+    data.resize(4 + 64 + padded_len, 0);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Abi;
+
+    fn responder() -> MockResponder {
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        let abi: Abi = serde_json::from_str(&json).unwrap();
+        MockResponder::new(abi.into_owned())
+    }
+
+    #[test]
+    fn returns_test() {
+        let mut mock = responder();
+        let balance = EthereumTypes::u256_from_bytes(&[0x01, 0x00]);
+        mock.returns("balanceOf", vec![balance]).unwrap();
+
+        let calldata = crate::transaction(
+            std::path::Path::new("src/rust_abi.json"),
+            "balanceOf",
+            vec![EthereumTypes::Address([0x30; 20])],
+        )
+        .unwrap();
+        let mut expected = vec![0_u8; 32];
+        expected[30] = 0x01;
+        assert_eq!(mock.respond(&calldata), Ok(expected));
+        assert_eq!(mock.respond(&[0xa9, 0x05, 0x9c, 0xbb]), Err(Vec::new()));
+
+        assert!(mock.returns("allowance", vec![]).is_err());
+        assert!(mock
+            .returns("totalSupply", vec![EthereumTypes::Address([0; 20])])
+            .is_err());
+    }
+
+    #[test]
+    fn reverts_test() {
+        let mut mock = responder();
+        mock.reverts("transfer", "insufficient balance").unwrap();
+        let revert = mock.respond(&[0xa9, 0x05, 0x9c, 0xbb]).unwrap_err();
+
+        assert_eq!(revert.len(), 4 + 32 * 3);
+        assert_eq!(&revert[..4], &ERROR_SELECTOR.0);
+        assert_eq!(revert[35], 0x20);
+        assert_eq!(revert[67], 20);
+        assert_eq!(&revert[68..88], b"insufficient balance");
+    }
+}
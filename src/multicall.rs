@@ -0,0 +1,182 @@
+use crate::registry::{self, RegisteredCall};
+use crate::{decode, Address, EthereumTypes, ParamType, Selector, U256};
+
+// multicalls nested deeper than this are left undecoded
+const MAX_DEPTH: usize = 4;
+
+/// A call batched into Multicall3 calldata, see [`explode_multicall`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubCall {
+    pub target: Address,
+    /// Whether the batch goes on if the call reverts.
+    pub allow_failure: bool,
+    /// Ether sent along, only non-zero in `aggregate3Value`.
+    pub value: U256,
+    pub calldata: Vec<u8>,
+    /// The call decoded with a function of the [`registry`], if any matches its selector.
+    pub decoded: Option<RegisteredCall>,
+    /// The sub-calls of a nested multicall.
+    pub calls: Vec<SubCall>,
+}
+
+/// Splits calldata of the Multicall3 `aggregate`, `tryAggregate`, `blockAndAggregate`,
+/// `tryBlockAndAggregate`, `aggregate3` and `aggregate3Value` functions into the batched calls.
+///
+/// Each call is decoded with the [`registry`], calls that are multicalls themselves are exploded
+/// in turn. Returns `None` for calldata of any other function.
+pub fn explode_multicall(calldata: &[u8]) -> Option<Vec<SubCall>> {
+    explode(calldata, 0)
+}
+
+fn explode(calldata: &[u8], depth: usize) -> Option<Vec<SubCall>> {
+    let selector = Selector::from_calldata(calldata)?;
+    let signature = MULTICALL_SIGNATURES
+        .iter()
+        .find(|signature| crate::selector(signature) == selector)?;
+    let types = match signature
+        .find('(')
+        .and_then(|open| ParamType::from_name(&signature[open..]))
+    {
+        Some(ParamType::Tuple(types)) => types,
+        _ => unreachable!("{}", signature),
+    };
+    let mut arguments = decode(&types, &calldata[4..]).ok()?;
+    let calls = match arguments.pop()? {
+        EthereumTypes::Array(calls) => calls,
+        _ => return None,
+    };
+    // the leading `requireSuccess` of the `try` variants
+    let require_success = match arguments.first() {
+        Some(EthereumTypes::Bool(require_success)) => *require_success,
+        _ => true,
+    };
+
+    calls
+        .into_iter()
+        .map(|call| {
+            let mut fields = match call {
+                EthereumTypes::Tuple(fields) => fields,
+                _ => return None,
+            };
+            let calldata = match fields.pop()? {
+                EthereumTypes::Bytes(calldata) => calldata,
+                _ => return None,
+            };
+            let target = match fields.first()? {
+                EthereumTypes::Address(target) => Address(*target),
+                _ => return None,
+            };
+            // `(address,bool,bytes)` of aggregate3 and `(address,bool,uint256,bytes)` of
+            // aggregate3Value
+            let allow_failure = match fields.get(1) {
+                Some(EthereumTypes::Bool(allow_failure)) => *allow_failure,
+                _ => !require_success,
+            };
+            let value = match fields.get(2) {
+                Some(EthereumTypes::U256(value)) => U256(*value),
+                _ => U256::ZERO,
+            };
+            let calls = match depth + 1 < MAX_DEPTH {
+                true => explode(&calldata, depth + 1).unwrap_or_default(),
+                false => Vec::new(),
+            };
+            let decoded = registry::decode_call(&calldata);
+            Some(SubCall {
+                target,
+                allow_failure,
+                value,
+                calldata,
+                decoded,
+                calls,
+            })
+        })
+        .collect()
+}
+
+const MULTICALL_SIGNATURES: [&str; 6] = [
+    "aggregate((address,bytes)[])",
+    "tryAggregate(bool,(address,bytes)[])",
+    "blockAndAggregate((address,bytes)[])",
+    "tryBlockAndAggregate(bool,(address,bytes)[])",
+    "aggregate3((address,bool,bytes)[])",
+    "aggregate3Value((address,bool,uint256,bytes)[])",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode, selector, Abi};
+
+    fn multicall(signature: &str, arguments: &[EthereumTypes]) -> Vec<u8> {
+        let mut calldata = selector(signature).0.to_vec();
+        calldata.extend(encode(arguments));
+        calldata
+    }
+
+    fn transfer() -> Vec<u8> {
+        multicall(
+            "transfer(address,uint256)",
+            &[Address([0x11; 20]).into(), U256::ONE.into()],
+        )
+    }
+
+    #[test]
+    fn aggregate_test() {
+        let json = r#"[{"type":"function","name":"transfer","inputs":[
+            {"name":"to","type":"address"},{"name":"amount","type":"uint256"}],
+            "outputs":[{"name":"","type":"bool"}],"stateMutability":"nonpayable"}]"#;
+        let abi: Abi = serde_json::from_str(json).unwrap();
+        registry::register("MulticallTestToken", &abi);
+
+        let call = EthereumTypes::Tuple(vec![
+            Address([0xaa; 20]).into(),
+            EthereumTypes::Bytes(transfer()),
+        ]);
+        let calldata = multicall(
+            "tryAggregate(bool,(address,bytes)[])",
+            &[EthereumTypes::Bool(false), EthereumTypes::Array(vec![call])],
+        );
+        let calls = explode_multicall(&calldata).unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].target, Address([0xaa; 20]));
+        assert!(calls[0].allow_failure);
+        assert_eq!(calls[0].calldata, transfer());
+        let decoded = calls[0].decoded.as_ref().unwrap();
+        // other tests may have registered an ERC-20 with the same function first
+        assert_eq!(decoded.function.signature(), "transfer(address,uint256)");
+        assert_eq!(decoded.arguments[1], U256::ONE.into());
+
+        assert_eq!(explode_multicall(&transfer()), None);
+        assert_eq!(explode_multicall(&calldata[..40]), None);
+    }
+
+    #[test]
+    fn nested_aggregate3_test() {
+        let inner = multicall(
+            "aggregate3Value((address,bool,uint256,bytes)[])",
+            &[EthereumTypes::Array(vec![EthereumTypes::Tuple(vec![
+                Address([0xbb; 20]).into(),
+                EthereumTypes::Bool(false),
+                U256::from(7_u64).into(),
+                EthereumTypes::Bytes(vec![0xd0, 0xe3, 0x0d, 0xb0]),
+            ])])],
+        );
+        let calldata = multicall(
+            "aggregate3((address,bool,bytes)[])",
+            &[EthereumTypes::Array(vec![EthereumTypes::Tuple(vec![
+                Address([0xaa; 20]).into(),
+                EthereumTypes::Bool(true),
+                EthereumTypes::Bytes(inner),
+            ])])],
+        );
+        let calls = explode_multicall(&calldata).unwrap();
+        assert!(calls[0].allow_failure);
+        assert_eq!(calls[0].calls.len(), 1);
+        let nested = &calls[0].calls[0];
+        assert_eq!(nested.target, Address([0xbb; 20]));
+        assert!(!nested.allow_failure);
+        assert_eq!(nested.value, U256::from(7_u64));
+        assert_eq!(nested.calldata, [0xd0, 0xe3, 0x0d, 0xb0]);
+        assert!(nested.calls.is_empty());
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{Abi, Param};
+
+use serde::Deserialize;
+
+use std::collections::HashMap;
+
+/// The NatSpec `devdoc` and `userdoc` sections of a compiled contract.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct NatSpec {
+    #[serde(default)]
+    pub devdoc: DevDoc,
+    #[serde(default)]
+    pub userdoc: UserDoc,
+}
+
+/// Developer documentation (`@title`, `@author`, `@dev`, `@param` and `@return` tags).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct DevDoc {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub details: Option<String>,
+    /// Keyed by canonical function signature.
+    #[serde(default)]
+    pub methods: HashMap<String, DevMember>,
+    /// Keyed by canonical event signature.
+    #[serde(default)]
+    pub events: HashMap<String, DevMember>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct DevMember {
+    pub details: Option<String>,
+    #[serde(default)]
+    pub params: HashMap<String, String>,
+    /// Keyed by the name of the return value, or `_0`, `_1`, ... for unnamed ones.
+    #[serde(default)]
+    pub returns: HashMap<String, String>,
+}
+
+/// User documentation (`@notice` tags).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct UserDoc {
+    pub notice: Option<String>,
+    #[serde(default)]
+    pub methods: HashMap<String, UserMember>,
+    #[serde(default)]
+    pub events: HashMap<String, UserMember>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct UserMember {
+    pub notice: Option<String>,
+}
+
+impl NatSpec {
+    /// Reads the NatSpec sections of a solc output contract or a Foundry artifact.
+    ///
+    /// solc puts `devdoc` and `userdoc` at the top level, Foundry nests them in the contract
+    /// metadata, which itself may be a json string. Missing sections are left empty.
+    pub fn from_artifact(json: &str) -> Result<Self, String> {
+        let artifact: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| format!("Couldn't parse json: {}", e))?;
+        if artifact.get("devdoc").is_some() || artifact.get("userdoc").is_some() {
+            return Self::deserialize(&artifact).map_err(|e| format!("Invalid NatSpec: {}", e));
+        }
+
+        let metadata = match artifact
+            .get("metadata")
+            .or_else(|| artifact.get("rawMetadata"))
+        {
+            Some(serde_json::Value::String(raw)) => serde_json::from_str(raw)
+                .map_err(|e| format!("Couldn't parse contract metadata: {}", e))?,
+            Some(metadata) => metadata.clone(),
+            None => return Ok(Self::default()),
+        };
+        match metadata.get("output") {
+            Some(output) => {
+                Self::deserialize(output).map_err(|e| format!("Invalid NatSpec: {}", e))
+            }
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+impl<'a> Abi<'a> {
+    /// Attaches the NatSpec notices, details and parameter descriptions to the matching functions
+    /// and events of the ABI.
+    pub fn attach_natspec(&mut self, natspec: &NatSpec) {
+        for function in self.functions.iter_mut() {
+            let signature = function.signature();
+            if let Some(user) = natspec.userdoc.methods.get(&signature) {
+                function.notice = user.notice.clone();
+            }
+            if let Some(dev) = natspec.devdoc.methods.get(&signature) {
+                function.details = dev.details.clone();
+                attach_params(&mut function.inputs, &dev.params);
+                for (i, output) in function.outputs.iter_mut().enumerate() {
+                    let key = if output.name.is_empty() {
+                        format!("_{}", i)
+                    } else {
+                        output.name.to_string()
+                    };
+                    if let Some(doc) = dev.returns.get(&key) {
+                        output.doc = Some(doc.clone());
+                    }
+                }
+            }
+        }
+
+        for event in self.events.iter_mut() {
+            let signature = event.signature();
+            if let Some(user) = natspec.userdoc.events.get(&signature) {
+                event.notice = user.notice.clone();
+            }
+            if let Some(dev) = natspec.devdoc.events.get(&signature) {
+                event.details = dev.details.clone();
+                attach_params(&mut event.inputs, &dev.params);
+            }
+        }
+    }
+}
+
+fn attach_params(params: &mut [Param], docs: &HashMap<String, String>) {
+    for param in params.iter_mut() {
+        if let Some(doc) = docs.get(param.name.as_ref()) {
+            param.doc = Some(doc.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABI: &str = r#"[
+        {
+            "type": "function",
+            "name": "transfer",
+            "inputs": [{ "name": "to", "type": "address" }, { "name": "amount", "type": "uint256" }],
+            "outputs": [{ "name": "", "type": "bool" }],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "type": "event",
+            "name": "Transfer",
+            "inputs": [
+                { "name": "from", "type": "address", "indexed": true },
+                { "name": "to", "type": "address", "indexed": true },
+                { "name": "value", "type": "uint256", "indexed": false }
+            ],
+            "anonymous": false
+        }
+    ]"#;
+
+    const NATSPEC: &str = r#"{
+        "devdoc": {
+            "methods": {
+                "transfer(address,uint256)": {
+                    "details": "Reverts if the balance is too low.",
+                    "params": { "to": "The recipient.", "amount": "The amount of tokens." },
+                    "returns": { "_0": "Always true." }
+                }
+            },
+            "events": {
+                "Transfer(address,address,uint256)": { "params": { "value": "The amount." } }
+            }
+        },
+        "userdoc": {
+            "methods": { "transfer(address,uint256)": { "notice": "Sends tokens." } }
+        }
+    }"#;
+
+    #[test]
+    fn attach_natspec_test() {
+        let mut abi: Abi = serde_json::from_str(ABI).unwrap();
+        abi.attach_natspec(&NatSpec::from_artifact(NATSPEC).unwrap());
+
+        let transfer = &abi.functions[0];
+        assert_eq!(transfer.notice.as_deref(), Some("Sends tokens."));
+        assert_eq!(
+            transfer.details.as_deref(),
+            Some("Reverts if the balance is too low.")
+        );
+        assert_eq!(transfer.inputs[0].doc.as_deref(), Some("The recipient."));
+        assert_eq!(transfer.outputs[0].doc.as_deref(), Some("Always true."));
+        assert_eq!(abi.events[0].inputs[0].doc, None);
+        assert_eq!(abi.events[0].inputs[2].doc.as_deref(), Some("The amount."));
+    }
+
+    #[test]
+    fn foundry_artifact_test() {
+        let metadata = serde_json::json!({ "output": serde_json::from_str::<serde_json::Value>(NATSPEC).unwrap() });
+        let artifact = serde_json::json!({ "abi": [], "rawMetadata": metadata.to_string() });
+        let natspec = NatSpec::from_artifact(&artifact.to_string()).unwrap();
+        assert_eq!(
+            natspec.userdoc.methods["transfer(address,uint256)"]
+                .notice
+                .as_deref(),
+            Some("Sends tokens.")
+        );
+        assert_eq!(NatSpec::from_artifact("{}").unwrap(), NatSpec::default());
+    }
+}
@@ -0,0 +1,154 @@
+//! Addresses of well-known contracts per chain, for helpers that need a default target.
+//!
+//! ```ignore
+//! let multicall = presets::contracts(chain).and_then(|c| c.multicall3).ok_or("no Multicall3")?;
+//! ```
+//!
+//! Only the chains of [`ChainId::KNOWN`] are covered. Addresses are those of the canonical
+//! deployments, contracts that aren't deployed on a chain, or live at a different address there
+//! like on zkSync, are `None` or that address.
+
+use crate::{Address, ChainId, DETERMINISTIC_DEPLOYER};
+
+/// `Multicall3`, aggregating calls.
+pub const MULTICALL3: Address = address("ca11bde05977b3631167028862be2a173976ca11");
+/// Uniswap's `Permit2`, signature based token approvals.
+pub const PERMIT2: Address = address("000000000022d473030f116ddee9f6b43ac78ba3");
+/// The ERC-4337 v0.6 `EntryPoint`.
+pub const ENTRY_POINT_V06: Address = address("5ff137d4b0fdcd49dca30c7cf57e578a026d2789");
+/// The ERC-4337 v0.7 `EntryPoint`.
+pub const ENTRY_POINT_V07: Address = address("0000000071727de22e5e9d8baf0edac6f37da032");
+/// `WETH` of the OP stack chains, a predeploy.
+pub const OP_STACK_WETH: Address = address("4200000000000000000000000000000000000006");
+
+/// The well-known contracts of a chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainContracts {
+    pub multicall3: Option<Address>,
+    pub permit2: Option<Address>,
+    /// Wrapped ether, bridged to chains with another native currency.
+    pub weth: Option<Address>,
+    pub entry_point_v06: Option<Address>,
+    pub entry_point_v07: Option<Address>,
+    /// The deterministic deployment proxy, see [`DETERMINISTIC_DEPLOYER`].
+    pub deterministic_deployer: Option<Address>,
+}
+
+impl ChainContracts {
+    // the contracts deployed at the same address on most chains
+    const fn canonical(weth: Address) -> Self {
+        Self {
+            multicall3: Some(MULTICALL3),
+            permit2: Some(PERMIT2),
+            weth: Some(weth),
+            entry_point_v06: Some(ENTRY_POINT_V06),
+            entry_point_v07: Some(ENTRY_POINT_V07),
+            deterministic_deployer: Some(DETERMINISTIC_DEPLOYER),
+        }
+    }
+}
+
+/// The well-known contracts of one of the chains of [`ChainId::KNOWN`].
+pub fn contracts(chain: ChainId) -> Option<ChainContracts> {
+    let weth = match chain {
+        ChainId::MAINNET => address("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+        ChainId::SEPOLIA => address("fff9976782d46cc05630d1f6ebab18b2324d6b14"),
+        ChainId::HOLESKY => address("94373a4919b3240d86ea41593d5eba789fef3848"),
+        ChainId::OPTIMISM | ChainId::OPTIMISM_SEPOLIA | ChainId::BASE | ChainId::BASE_SEPOLIA => {
+            OP_STACK_WETH
+        }
+        ChainId::ARBITRUM => address("82af49447d8a07e3bd95bd0d56f35241523fbab1"),
+        ChainId::ARBITRUM_SEPOLIA => address("980b62da83eff3d4576c647993b0c1d7faf17c73"),
+        ChainId::POLYGON => address("7ceb23fd6bc0add59e62ac25578270cff1b9f619"),
+        ChainId::BSC => address("2170ed0880ac9a755fd29b2688956bd959f933f8"),
+        ChainId::GNOSIS => address("6a023ccd1ff6f2045c3309768ead9e68f978f6e1"),
+        ChainId::AVALANCHE => address("49d5c2bdffac6ce2bfdb6640f4f80f226bc10bab"),
+        ChainId::LINEA => address("e5d7c2a44ffddf6b295a15c148167daaaf5cf34f"),
+        ChainId::SCROLL => address("5300000000000000000000000000000000000004"),
+        // zkSync's create2 derives other addresses, and has native account abstraction
+        ChainId::ZKSYNC => {
+            return Some(ChainContracts {
+                multicall3: Some(address("f9cda624fbc7e059355ce98a31693d299facd963")),
+                permit2: Some(address("0000000000225e31d15943971f47ad3022f714fa")),
+                weth: Some(address("5aea5775959fbc2557cc8789bc1bf90a239d9a91")),
+                entry_point_v06: None,
+                entry_point_v07: None,
+                deterministic_deployer: None,
+            })
+        }
+        _ => return None,
+    };
+    Some(ChainContracts::canonical(weth))
+}
+
+/// `Multicall3` of the chain, if it's one of [`ChainId::KNOWN`].
+pub fn multicall3(chain: ChainId) -> Option<Address> {
+    contracts(chain)?.multicall3
+}
+
+/// `Permit2` of the chain, if it's one of [`ChainId::KNOWN`].
+pub fn permit2(chain: ChainId) -> Option<Address> {
+    contracts(chain)?.permit2
+}
+
+/// Wrapped ether of the chain, if it's one of [`ChainId::KNOWN`].
+pub fn weth(chain: ChainId) -> Option<Address> {
+    contracts(chain)?.weth
+}
+
+// lowercase hex without prefix, a const fn so the addresses stay readable
+const fn address(hex: &str) -> Address {
+    let hex = hex.as_bytes();
+    assert!(hex.len() == 40, "An address has 40 hex digits");
+    let mut bytes = [0_u8; 20];
+    let mut i = 0;
+    while i < 20 {
+        bytes[i] = (nibble(hex[2 * i]) << 4) | nibble(hex[2 * i + 1]);
+        i += 1;
+    }
+    Address(bytes)
+}
+
+const fn nibble(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => panic!("Invalid lowercase hex digit"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::to_checksum;
+
+    #[test]
+    fn contracts_test() {
+        let mainnet = contracts(ChainId::MAINNET).unwrap();
+        assert_eq!(
+            to_checksum(&mainnet.weth.unwrap()),
+            "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
+        );
+        assert_eq!(
+            to_checksum(&MULTICALL3),
+            "0xcA11bde05977b3631167028862bE2a173976CA11"
+        );
+        assert_eq!(
+            to_checksum(&ENTRY_POINT_V07),
+            "0x0000000071727De22E5E9d8BAf0edAc6f37da032"
+        );
+        assert_eq!(mainnet.deterministic_deployer, Some(DETERMINISTIC_DEPLOYER));
+        assert_eq!(weth(ChainId::BASE), Some(OP_STACK_WETH));
+        assert_eq!(contracts(ChainId::ZKSYNC).unwrap().entry_point_v07, None);
+        assert_eq!(multicall3(ChainId::from(999_999)), None);
+    }
+
+    #[test]
+    fn known_chains_test() {
+        for chain in ChainId::KNOWN.iter() {
+            let contracts = contracts(*chain).unwrap();
+            assert!(contracts.weth.is_some() && contracts.multicall3.is_some());
+            assert_eq!(permit2(*chain), contracts.permit2);
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use crate::selector::{selector, Selector};
+use crate::{Address, U256};
+
+use sha3::{Digest, Keccak256};
+
+/// EIP-1967 storage slot of the implementation address, `keccak256("eip1967.proxy.implementation") - 1`.
+pub const EIP1967_IMPLEMENTATION_SLOT: [u8; 32] = [
+    0x36, 0x08, 0x94, 0xa1, 0x3b, 0xa1, 0xa3, 0x21, 0x06, 0x67, 0xc8, 0x28, 0x49, 0x2d, 0xb9, 0x8d,
+    0xca, 0x3e, 0x20, 0x76, 0xcc, 0x37, 0x35, 0xa9, 0x20, 0xa3, 0xca, 0x50, 0x5d, 0x38, 0x2b, 0xbc,
+];
+
+/// EIP-1967 storage slot of the admin address, `keccak256("eip1967.proxy.admin") - 1`.
+pub const EIP1967_ADMIN_SLOT: [u8; 32] = [
+    0xb5, 0x31, 0x27, 0x68, 0x4a, 0x56, 0x8b, 0x31, 0x73, 0xae, 0x13, 0xb9, 0xf8, 0xa6, 0x01, 0x6e,
+    0x24, 0x3e, 0x63, 0xb6, 0xe8, 0xee, 0x11, 0x78, 0xd6, 0xa7, 0x17, 0x85, 0x0b, 0x5d, 0x61, 0x03,
+];
+
+/// EIP-1967 storage slot of the beacon address, `keccak256("eip1967.proxy.beacon") - 1`.
+///
+/// A beacon proxy asks the beacon for its implementation with [`IMPLEMENTATION_SELECTOR`].
+pub const EIP1967_BEACON_SLOT: [u8; 32] = [
+    0xa3, 0xf0, 0xad, 0x74, 0xe5, 0x42, 0x3a, 0xeb, 0xfd, 0x80, 0xd3, 0xef, 0x43, 0x46, 0x57, 0x83,
+    0x35, 0xa9, 0xa7, 0x2a, 0xea, 0xee, 0x59, 0xff, 0x6c, 0xb3, 0x58, 0x2b, 0x35, 0x13, 0x3d, 0x50,
+];
+
+/// Selector of `implementation()`, implemented by beacons.
+pub const IMPLEMENTATION_SELECTOR: Selector = Selector([0x5c, 0x60, 0xda, 0x1b]);
+
+/// Selector of `proxiableUUID()`, which UUPS implementations answer with
+/// [`EIP1967_IMPLEMENTATION_SLOT`].
+pub const PROXIABLE_UUID_SELECTOR: Selector = Selector([0x52, 0xd1, 0x90, 0x2d]);
+
+/// Interprets a 32 byte word, e.g. the value of an EIP-1967 slot from `eth_getStorageAt` or the
+/// return data of `implementation()`, as an address.
+///
+/// Returns `None` for an unset (zero) pointer or a word that isn't a left padded address.
+pub fn slot_address(word: &[u8; 32]) -> Option<Address> {
+    Address::from_word(word).filter(|address| !address.is_zero())
+}
+
+/// Whether the return data of `proxiableUUID()` identifies a UUPS implementation compatible with
+/// EIP-1967 proxies.
+pub fn is_proxiable_uuid(return_data: &[u8]) -> bool {
+    return_data == EIP1967_IMPLEMENTATION_SLOT
+}
+
+/// Encodes `upgradeTo(address)` calldata, used by UUPS implementations and transparent proxies
+/// before OpenZeppelin 5.
+pub fn encode_upgrade_to(implementation: &Address) -> Vec<u8> {
+    let mut data = Vec::with_capacity(36);
+    data.extend_from_slice(selector("upgradeTo(address)").as_bytes());
+    data.extend_from_slice(&implementation.to_word());
+    data
+}
+
+/// Encodes `upgradeToAndCall(address,bytes)` calldata, which upgrades and then delegatecalls the
+/// new implementation with `call`, e.g. an encoded initializer. OpenZeppelin 5 only provides this
+/// one, called with empty `call` for a plain upgrade.
+pub fn encode_upgrade_to_and_call(implementation: &Address, call: &[u8]) -> Vec<u8> {
+    let padded_len = call.len().div_ceil(32) * 32;
+    let mut data = Vec::with_capacity(4 + 96 + padded_len);
+    data.extend_from_slice(selector("upgradeToAndCall(address,bytes)").as_bytes());
+    data.extend_from_slice(&implementation.to_word());
+    // offset of the bytes argument, followed by its length and the padded content
+    data.extend_from_slice(&U256::from(0x40_u64).0);
+    data.extend_from_slice(&U256::from(call.len() as u64).0);
+    data.extend_from_slice(call);
+    data.resize(4 + 96 + padded_len, 0);
+    data
+}
+
+// EIP-1167 runtime code around the 20 byte implementation address
+const MINIMAL_PROXY_RUNTIME_PREFIX: [u8; 10] =
+    [0x36, 0x3d, 0x3d, 0x37, 0x3d, 0x3d, 0x3d, 0x36, 0x3d, 0x73];
+const MINIMAL_PROXY_RUNTIME_SUFFIX: [u8; 15] = [
+    0x5a, 0xf4, 0x3d, 0x82, 0x80, 0x3e, 0x90, 0x3d, 0x91, 0x60, 0x2b, 0x57, 0xfd, 0x5b, 0xf3,
+];
+// copies the 45 byte runtime code to memory and returns it
+const MINIMAL_PROXY_CREATION_PREFIX: [u8; 10] =
+    [0x3d, 0x60, 0x2d, 0x80, 0x60, 0x0a, 0x3d, 0x39, 0x81, 0xf3];
+
+/// Length of the EIP-1167 minimal proxy runtime code.
+pub const MINIMAL_PROXY_RUNTIME_LEN: usize = 45;
+
+/// Length of the EIP-1167 minimal proxy creation code.
+pub const MINIMAL_PROXY_CREATION_LEN: usize = 55;
+
+/// The EIP-1167 minimal proxy runtime code delegating every call to `implementation`.
+pub fn minimal_proxy_runtime_code(implementation: &Address) -> [u8; MINIMAL_PROXY_RUNTIME_LEN] {
+    let mut code = [0_u8; MINIMAL_PROXY_RUNTIME_LEN];
+    code[..10].copy_from_slice(&MINIMAL_PROXY_RUNTIME_PREFIX);
+    code[10..30].copy_from_slice(implementation.as_bytes());
+    code[30..].copy_from_slice(&MINIMAL_PROXY_RUNTIME_SUFFIX);
+    code
+}
+
+/// The EIP-1167 minimal proxy creation code, as deployed by OpenZeppelin's `Clones` and most
+/// clone factories.
+pub fn minimal_proxy_creation_code(implementation: &Address) -> [u8; MINIMAL_PROXY_CREATION_LEN] {
+    let mut code = [0_u8; MINIMAL_PROXY_CREATION_LEN];
+    code[..10].copy_from_slice(&MINIMAL_PROXY_CREATION_PREFIX);
+    code[10..].copy_from_slice(&minimal_proxy_runtime_code(implementation));
+    code
+}
+
+/// Keccak hash of the minimal proxy creation code, to predict clone addresses with
+/// [`create2_address`](crate::create2_address).
+pub fn minimal_proxy_init_code_hash(implementation: &Address) -> [u8; 32] {
+    Keccak256::digest(&minimal_proxy_creation_code(implementation)).into()
+}
+
+/// Recognizes EIP-1167 minimal proxy runtime code and returns the implementation it delegates to.
+///
+/// Besides the standard 45 byte code this also accepts the shorter variants deployed for
+/// implementations with leading zero bytes, which push fewer than 20 address bytes.
+pub fn minimal_proxy_implementation(runtime_code: &[u8]) -> Option<Address> {
+    let rest = runtime_code.strip_prefix(&MINIMAL_PROXY_RUNTIME_PREFIX[..9])?;
+    let (&push, rest) = rest.split_first()?;
+    // PUSH1 to PUSH20
+    if !(0x60..=0x73).contains(&push) {
+        return None;
+    }
+    let len = usize::from(push - 0x5f);
+    if rest.len() != len + MINIMAL_PROXY_RUNTIME_SUFFIX.len() {
+        return None;
+    }
+    let (pushed, suffix) = rest.split_at(len);
+    // the jump destination moves along with the shorter push
+    let mut expected_suffix = MINIMAL_PROXY_RUNTIME_SUFFIX;
+    expected_suffix[10] -= (20 - len) as u8;
+    if suffix != expected_suffix {
+        return None;
+    }
+    let mut implementation = Address::ZERO;
+    implementation.0[20 - len..].copy_from_slice(pushed);
+    Some(implementation)
+}
+
+// runtime code of a clone plus its immutable args has to fit the PUSH2 of the creation code
+const CLONE_MAX_ARGS_LEN: usize = 0xffff - MINIMAL_PROXY_RUNTIME_LEN;
+
+/// The creation code of a clone with immutable args, the EIP-1167 minimal proxy runtime code
+/// followed by the packed `args`, as deployed by Solady's `LibClone`.
+///
+/// The implementation reads the args from the code of the clone, starting right after the 45
+/// bytes of the proxy.
+pub fn clone_with_immutable_args_creation_code(
+    implementation: &Address,
+    args: &[u8],
+) -> Result<Vec<u8>, String> {
+    if args.len() > CLONE_MAX_ARGS_LEN {
+        return Err(format!(
+            "Immutable args of {} bytes exceed the maximum of {} bytes.",
+            args.len(),
+            CLONE_MAX_ARGS_LEN
+        ));
+    }
+    let runtime_len = (MINIMAL_PROXY_RUNTIME_LEN + args.len()) as u16;
+    let mut code = Vec::with_capacity(10 + usize::from(runtime_len));
+    // PUSH2 of the runtime length, then copies the runtime code to memory and returns it
+    code.push(0x61);
+    code.extend_from_slice(&runtime_len.to_be_bytes());
+    code.extend_from_slice(&[0x3d, 0x81, 0x60, 0x0a, 0x3d, 0x39, 0xf3]);
+    code.extend_from_slice(&minimal_proxy_runtime_code(implementation));
+    code.extend_from_slice(args);
+    Ok(code)
+}
+
+/// Keccak hash of the creation code of a clone with immutable args, to predict its address with
+/// [`create2_address`](crate::create2_address).
+pub fn clone_with_immutable_args_init_code_hash(
+    implementation: &Address,
+    args: &[u8],
+) -> Result<[u8; 32], String> {
+    let code = clone_with_immutable_args_creation_code(implementation, args)?;
+    Ok(Keccak256::digest(&code).into())
+}
+
+/// Recognizes the runtime code of a clone with immutable args and returns the implementation it
+/// delegates to along with the args appended to the proxy.
+///
+/// Plain EIP-1167 proxies are clones without args.
+pub fn clone_immutable_args(runtime_code: &[u8]) -> Option<(Address, &[u8])> {
+    if runtime_code.len() < MINIMAL_PROXY_RUNTIME_LEN {
+        return None;
+    }
+    let (proxy, args) = runtime_code.split_at(MINIMAL_PROXY_RUNTIME_LEN);
+    if proxy[..10] != MINIMAL_PROXY_RUNTIME_PREFIX || proxy[30..] != MINIMAL_PROXY_RUNTIME_SUFFIX {
+        return None;
+    }
+    let mut implementation = Address::ZERO;
+    implementation.0.copy_from_slice(&proxy[10..30]);
+    Some((implementation, args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create2_address, hex};
+
+    #[test]
+    fn minimal_proxy_code_test() {
+        let implementation = Address([0xbe; 20]);
+        assert_eq!(
+            hex::encode(minimal_proxy_creation_code(&implementation)),
+            "3d602d80600a3d3981f3363d3d373d3d3d363d73bebebebebebebebebebebebebebebebebebebebe5af43d82803e903d91602b57fd5bf3"
+        );
+        assert_eq!(
+            minimal_proxy_creation_code(&implementation)[10..],
+            minimal_proxy_runtime_code(&implementation)
+        );
+    }
+
+    #[test]
+    fn init_code_hash_test() {
+        let implementation = Address([0x11; 20]);
+        let hash = minimal_proxy_init_code_hash(&implementation);
+        let expected: [u8; 32] =
+            Keccak256::digest(&minimal_proxy_creation_code(&implementation)).into();
+        assert_eq!(hash, expected);
+        assert_ne!(
+            create2_address(&Address([0x22; 20]), &[0; 32], &hash),
+            create2_address(
+                &Address([0x22; 20]),
+                &[0; 32],
+                &minimal_proxy_init_code_hash(&Address([0x12; 20]))
+            )
+        );
+    }
+
+    #[test]
+    fn minimal_proxy_implementation_test() {
+        let implementation = Address([0xbe; 20]);
+        let runtime = minimal_proxy_runtime_code(&implementation);
+        assert_eq!(minimal_proxy_implementation(&runtime), Some(implementation));
+        // creation code or trailing bytes are not runtime code of a proxy
+        assert_eq!(
+            minimal_proxy_implementation(&minimal_proxy_creation_code(&implementation)),
+            None
+        );
+        assert_eq!(minimal_proxy_implementation(&runtime[..44]), None);
+        assert_eq!(minimal_proxy_implementation(&[]), None);
+
+        // vanity variant for an implementation with two leading zero bytes
+        let short = hex::decode(
+            "363d3d373d3d3d363d71bebebebebebebebebebebebebebebebebebe5af43d82803e903d91602957fd5bf3",
+        )
+        .unwrap();
+        let mut expected = Address([0xbe; 20]);
+        expected.0[..2].copy_from_slice(&[0, 0]);
+        assert_eq!(minimal_proxy_implementation(&short), Some(expected));
+    }
+
+    #[test]
+    fn clone_with_immutable_args_test() {
+        let implementation = Address([0xbe; 20]);
+        let args = [0x11, 0x22, 0x33];
+        let code = clone_with_immutable_args_creation_code(&implementation, &args).unwrap();
+        assert_eq!(
+            hex::encode(&code),
+            "6100303d81600a3d39f3363d3d373d3d3d363d73bebebebebebebebebebebebebebebebebebebebe5af43d82803e903d91602b57fd5bf3112233"
+        );
+        let runtime = &code[10..];
+        assert_eq!(
+            clone_immutable_args(runtime),
+            Some((implementation, &args[..]))
+        );
+        // a plain minimal proxy has no args
+        let plain = minimal_proxy_runtime_code(&implementation);
+        assert_eq!(
+            clone_immutable_args(&plain),
+            Some((implementation, &[][..]))
+        );
+        assert_eq!(clone_immutable_args(&code), None);
+        assert_eq!(clone_immutable_args(&plain[..44]), None);
+
+        assert_ne!(
+            clone_with_immutable_args_init_code_hash(&implementation, &args).unwrap(),
+            minimal_proxy_init_code_hash(&implementation)
+        );
+        assert!(clone_with_immutable_args_creation_code(&implementation, &[0; 0xffd2]).is_ok());
+        assert!(clone_with_immutable_args_creation_code(&implementation, &[0; 0xffd3]).is_err());
+    }
+
+    #[test]
+    fn eip1967_slots_test() {
+        for (label, slot) in [
+            ("eip1967.proxy.implementation", EIP1967_IMPLEMENTATION_SLOT),
+            ("eip1967.proxy.admin", EIP1967_ADMIN_SLOT),
+            ("eip1967.proxy.beacon", EIP1967_BEACON_SLOT),
+        ] {
+            let hash: [u8; 32] = Keccak256::digest(label.as_bytes()).into();
+            assert_eq!(U256(hash).checked_sub(U256::ONE), Some(U256(slot)));
+        }
+        assert_eq!(selector("implementation()"), IMPLEMENTATION_SELECTOR);
+        assert_eq!(selector("proxiableUUID()"), PROXIABLE_UUID_SELECTOR);
+        assert!(is_proxiable_uuid(&EIP1967_IMPLEMENTATION_SLOT));
+        assert!(!is_proxiable_uuid(&EIP1967_ADMIN_SLOT));
+    }
+
+    #[test]
+    fn slot_address_test() {
+        let mut word = [0_u8; 32];
+        assert_eq!(slot_address(&word), None);
+        word[12..].copy_from_slice(&[0xbe; 20]);
+        assert_eq!(slot_address(&word), Some(Address([0xbe; 20])));
+        word[0] = 1;
+        assert_eq!(slot_address(&word), None);
+    }
+
+    #[test]
+    fn upgrade_to_test() {
+        let implementation = Address([0xbe; 20]);
+        assert_eq!(
+            hex::encode(encode_upgrade_to(&implementation)),
+            "3659cfe6000000000000000000000000bebebebebebebebebebebebebebebebebebebebe"
+        );
+        let data = encode_upgrade_to_and_call(&implementation, &[0x81, 0x29, 0xfc, 0x1c]);
+        assert_eq!(
+            hex::encode(&data),
+            concat!(
+                "4f1ef286",
+                "000000000000000000000000bebebebebebebebebebebebebebebebebebebebe",
+                "0000000000000000000000000000000000000000000000000000000000000040",
+                "0000000000000000000000000000000000000000000000000000000000000004",
+                "8129fc1c00000000000000000000000000000000000000000000000000000000"
+            )
+        );
+        assert_eq!(
+            encode_upgrade_to_and_call(&implementation, &[]).len(),
+            4 + 96
+        );
+    }
+}
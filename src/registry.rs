@@ -0,0 +1,480 @@
+//! Process-wide registry of function selectors and event topics for reverse lookups.
+//!
+//! ABIs are registered explicitly with [`register`], or on every load through [`transaction`] and
+//! [`include_abi!`] once [`enable`] was called, so an indexer decoding arbitrary traffic can find
+//! the function behind a selector with `lookup_selector(selector)`. Custom errors aren't part of
+//! parsed ABIs and are registered by signature with [`register_error`].
+//!
+//! Lookups that find nothing are reported to the hooks registered with [`on_unknown`], e.g. to
+//! count unknown selectors or resolve them through a signature database in the background.
+//!
+//! The whole registry can be exported as a signature database with [`export_csv`] or
+//! [`export_json`], e.g. for offline selector lookups.
+//!
+//! [`transaction`]: crate::transaction
+//! [`include_abi!`]: crate::include_abi
+
+use crate::{hex, selector, Abi, EthereumTypes, Event, Function, Selector};
+
+use serde_json::{json, Value};
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// A function declared by a registered contract ABI.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisteredFunction {
+    pub contract: String,
+    pub function: Function<'static>,
+}
+
+/// An event declared by a registered contract ABI.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisteredEvent {
+    pub contract: String,
+    pub event: Event<'static>,
+}
+
+/// Calldata decoded with a registered function, see [`decode_call`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisteredCall {
+    pub contract: String,
+    pub function: Function<'static>,
+    pub arguments: Vec<EthereumTypes>,
+}
+
+/// A custom error registered with [`register_error`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegisteredError {
+    pub contract: String,
+    /// Canonical signature, e.g. `ERC20InsufficientBalance(address,uint256,uint256)`.
+    pub signature: String,
+}
+
+/// A row of the signature database, see [`export_csv`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SignatureEntry {
+    /// `function`, `event` or `error`.
+    pub kind: &'static str,
+    /// The selector of functions and errors, the topic of events.
+    pub hash: Vec<u8>,
+    pub signature: String,
+    pub contract: String,
+}
+
+/// A selector or topic a lookup didn't find in the registry, see [`on_unknown`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Unknown {
+    Selector(Selector),
+    Topic([u8; 32]),
+    Error(Selector),
+}
+
+type UnknownHook = Arc<dyn Fn(&Unknown) + Send + Sync>;
+
+#[derive(Default)]
+struct Registry {
+    functions: HashMap<Selector, Vec<RegisteredFunction>>,
+    events: HashMap<[u8; 32], Vec<RegisteredEvent>>,
+    errors: HashMap<Selector, Vec<RegisteredError>>,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+fn hooks() -> &'static RwLock<Vec<UnknownHook>> {
+    static HOOKS: OnceLock<RwLock<Vec<UnknownHook>>> = OnceLock::new();
+    HOOKS.get_or_init(Default::default)
+}
+
+/// Registers every ABI loaded from now on, named after its file.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Registers the functions and events of a contract ABI. Registering the same contract again only
+/// adds the items it didn't declare before.
+pub fn register(contract: &str, abi: &Abi) {
+    // a poisoned lock still holds a consistent registry, every update is a single push
+    let mut registry = registry().write().unwrap_or_else(|e| e.into_inner());
+    for function in abi.functions.iter() {
+        let entries = registry.functions.entry(function.selector()).or_default();
+        if !entries
+            .iter()
+            .any(|e| e.contract == contract && e.function == *function)
+        {
+            entries.push(RegisteredFunction {
+                contract: contract.to_owned(),
+                function: function.clone().into_owned(),
+            });
+        }
+    }
+    for event in abi.events.iter().filter(|e| !e.anonymous) {
+        let entries = registry.events.entry(event.topic()).or_default();
+        if !entries
+            .iter()
+            .any(|e| e.contract == contract && e.event == *event)
+        {
+            entries.push(RegisteredEvent {
+                contract: contract.to_owned(),
+                event: event.clone().into_owned(),
+            });
+        }
+    }
+}
+
+/// Registers a custom error of a contract by its canonical signature.
+pub fn register_error(contract: &str, signature: &str) {
+    let mut registry = registry().write().unwrap_or_else(|e| e.into_inner());
+    let entries = registry.errors.entry(selector(signature)).or_default();
+    if !entries
+        .iter()
+        .any(|e| e.contract == contract && e.signature == signature)
+    {
+        entries.push(RegisteredError {
+            contract: contract.to_owned(),
+            signature: signature.to_owned(),
+        });
+    }
+}
+
+// called wherever an ABI is loaded, the contract is named after the file stem
+pub(crate) fn register_loaded(path: &Path, abi: &Abi) {
+    let stem = path.file_stem().unwrap_or(path.as_os_str());
+    register_loaded_as(&stem.to_string_lossy(), abi);
+}
+
+// called when an ABI is loaded from other sources than a file
+pub(crate) fn register_loaded_as(contract: &str, abi: &Abi) {
+    if is_enabled() {
+        register(contract, abi);
+    }
+}
+
+/// Functions with the given selector in the order their contracts were registered, usually a
+/// single one unless several contracts share it like the ERC-20 `transfer`.
+pub fn lookup_selector<S: Into<Selector>>(selector: S) -> Vec<RegisteredFunction> {
+    let selector = selector.into();
+    let functions = {
+        let registry = registry().read().unwrap_or_else(|e| e.into_inner());
+        registry.functions.get(&selector).cloned()
+    };
+    #[cfg(feature = "tracing")]
+    tracing::trace!(%selector, found = functions.as_ref().map_or(0, Vec::len), "lookup_selector");
+    functions.unwrap_or_else(|| report(Unknown::Selector(selector)))
+}
+
+/// Decodes calldata with the first function registered for its selector that accepts it.
+pub fn decode_call(calldata: &[u8]) -> Option<RegisteredCall> {
+    let selector = Selector::from_calldata(calldata)?;
+    lookup_selector(selector)
+        .into_iter()
+        .find_map(|registered| {
+            let arguments = registered.function.decode_input(calldata).ok()?;
+            Some(RegisteredCall {
+                contract: registered.contract,
+                function: registered.function,
+                arguments,
+            })
+        })
+}
+
+/// Events with the given `topic0` in the order their contracts were registered.
+pub fn lookup_topic(topic: &[u8; 32]) -> Vec<RegisteredEvent> {
+    let events = {
+        let registry = registry().read().unwrap_or_else(|e| e.into_inner());
+        registry.events.get(topic).cloned()
+    };
+    #[cfg(feature = "tracing")]
+    tracing::trace!(
+        topic = %hex::encode_prefixed(topic),
+        found = events.as_ref().map_or(0, Vec::len),
+        "lookup_topic"
+    );
+    events.unwrap_or_else(|| report(Unknown::Topic(*topic)))
+}
+
+/// Errors with the given selector in the order their contracts were registered.
+pub fn lookup_error<S: Into<Selector>>(selector: S) -> Vec<RegisteredError> {
+    let selector = selector.into();
+    let errors = {
+        let registry = registry().read().unwrap_or_else(|e| e.into_inner());
+        registry.errors.get(&selector).cloned()
+    };
+    #[cfg(feature = "tracing")]
+    tracing::trace!(%selector, found = errors.as_ref().map_or(0, Vec::len), "lookup_error");
+    errors.unwrap_or_else(|| report(Unknown::Error(selector)))
+}
+
+/// Calls `hook` whenever a lookup, and with it [`decode_call`] or a multicall explosion, doesn't
+/// find a selector or topic.
+///
+/// Hooks run on the thread of the lookup after the registry lock is released, so they may
+/// [`register`] what they resolve. Slow work like querying a signature database belongs on
+/// another thread, a hook should only hand the unknown over.
+pub fn on_unknown<F: Fn(&Unknown) + Send + Sync + 'static>(hook: F) {
+    let mut hooks = hooks().write().unwrap_or_else(|e| e.into_inner());
+    hooks.push(Arc::new(hook));
+}
+
+/// Removes every hook registered with [`on_unknown`].
+pub fn clear_unknown_hooks() {
+    hooks().write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+// empty lookup results, after telling the hooks
+fn report<T>(unknown: Unknown) -> Vec<T> {
+    // the hooks are cloned out of the lock, a hook registering another hook must not deadlock
+    let hooks: Vec<UnknownHook> = hooks().read().unwrap_or_else(|e| e.into_inner()).clone();
+    for hook in hooks {
+        hook(&unknown);
+    }
+    Vec::new()
+}
+
+/// Every registered signature, sorted by kind, hash, signature and contract.
+pub fn entries() -> Vec<SignatureEntry> {
+    let registry = registry().read().unwrap_or_else(|e| e.into_inner());
+    let functions = registry.functions.iter().flat_map(|(selector, entries)| {
+        entries.iter().map(move |e| SignatureEntry {
+            kind: "function",
+            hash: selector.0.to_vec(),
+            signature: e.function.signature(),
+            contract: e.contract.clone(),
+        })
+    });
+    let events = registry.events.iter().flat_map(|(topic, entries)| {
+        entries.iter().map(move |e| SignatureEntry {
+            kind: "event",
+            hash: topic.to_vec(),
+            signature: e.event.signature(),
+            contract: e.contract.clone(),
+        })
+    });
+    let errors = registry.errors.iter().flat_map(|(selector, entries)| {
+        entries.iter().map(move |e| SignatureEntry {
+            kind: "error",
+            hash: selector.0.to_vec(),
+            signature: e.signature.clone(),
+            contract: e.contract.clone(),
+        })
+    });
+    let mut entries: Vec<SignatureEntry> = functions.chain(events).chain(errors).collect();
+    entries.sort();
+    entries
+}
+
+/// The signature database as CSV with a `kind,hash,signature,contract` header, hashes are `0x`
+/// prefixed hex.
+pub fn export_csv() -> String {
+    let mut csv = String::from("kind,hash,signature,contract\n");
+    for entry in entries() {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            entry.kind,
+            hex::encode_prefixed(&entry.hash),
+            csv_field(&entry.signature),
+            csv_field(&entry.contract)
+        ));
+    }
+    csv
+}
+
+/// The signature database as a json array of `{kind, hash, signature, contract}` objects.
+pub fn export_json() -> Value {
+    let entries: Vec<Value> = entries()
+        .into_iter()
+        .map(|entry| {
+            json!({
+                "kind": entry.kind,
+                "hash": hex::encode_prefixed(&entry.hash),
+                "signature": entry.signature,
+                "contract": entry.contract,
+            })
+        })
+        .collect();
+    Value::Array(entries)
+}
+
+// signatures contain commas, quotes are doubled
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Removes every registered function, event and error, the hooks stay registered.
+pub fn clear() {
+    let mut registry = registry().write().unwrap_or_else(|e| e.into_inner());
+    registry.functions.clear();
+    registry.events.clear();
+    registry.errors.clear();
+}
+
+// tests toggling the process-wide flag hold the guard, which restores the previous state
+#[cfg(test)]
+pub(crate) struct EnabledGuard {
+    previous: bool,
+    _lock: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(test)]
+pub(crate) fn set_enabled(enabled: bool) -> EnabledGuard {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let previous = is_enabled();
+    ENABLED.store(enabled, Ordering::Relaxed);
+    EnabledGuard {
+        previous,
+        _lock: lock,
+    }
+}
+
+#[cfg(test)]
+impl Drop for EnabledGuard {
+    fn drop(&mut self) {
+        ENABLED.store(self.previous, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event_topic, selector, transaction};
+
+    // the registry is shared by the tests running in parallel, hence the unique names
+    const ABI: &str = r#"[
+        {"type":"function","name":"registryTestPing","inputs":[{"name":"n","type":"uint256"}],
+         "outputs":[],"stateMutability":"nonpayable"},
+        {"type":"event","name":"RegistryTestPong","inputs":[],"anonymous":false}
+    ]"#;
+
+    #[test]
+    fn register_test() {
+        let abi: Abi = serde_json::from_str(ABI).unwrap();
+        register("Ping", &abi);
+        register("Ping", &abi);
+        register("OtherPing", &abi);
+
+        let functions = lookup_selector(selector("registryTestPing(uint256)"));
+        let contracts: Vec<&str> = functions.iter().map(|f| f.contract.as_str()).collect();
+        assert_eq!(contracts, ["Ping", "OtherPing"]);
+        assert_eq!(functions[0].function.name, "registryTestPing");
+
+        let events = lookup_topic(&event_topic("RegistryTestPong()"));
+        assert_eq!(events.len(), 2);
+        assert!(lookup_selector([0xde, 0xad, 0xbe, 0xef]).is_empty());
+    }
+
+    #[test]
+    fn export_test() {
+        let abi = ABI
+            .replace("registryTest", "exportTest")
+            .replace("RegistryTest", "ExportTest");
+        let abi: Abi = serde_json::from_str(&abi).unwrap();
+        register("Export", &abi);
+        register_error("Export", "RegistryTestDenied(address,uint8)");
+        register_error("Export", "RegistryTestDenied(address,uint8)");
+        assert_eq!(
+            lookup_error(selector("RegistryTestDenied(address,uint8)")).len(),
+            1
+        );
+
+        let rows: Vec<SignatureEntry> = entries()
+            .into_iter()
+            .filter(|e| e.contract == "Export")
+            .collect();
+        let kinds: Vec<&str> = rows.iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, ["error", "event", "function"]);
+
+        let csv = export_csv();
+        assert!(csv.starts_with("kind,hash,signature,contract\n"));
+        let row = format!(
+            "error,{},\"RegistryTestDenied(address,uint8)\",Export\n",
+            selector("RegistryTestDenied(address,uint8)")
+        );
+        assert!(csv.contains(&row));
+        assert!(csv.contains(",exportTestPing(uint256),Export\n"));
+
+        let json = export_json();
+        assert!(json.as_array().unwrap().iter().any(|e| e
+            == &json!({
+                "kind": "event",
+                "hash": hex::encode_prefixed(event_topic("ExportTestPong()")),
+                "signature": "ExportTestPong()",
+                "contract": "Export",
+            })));
+        assert_eq!(csv_field("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn register_loaded_test() {
+        let path = std::env::temp_dir().join("registry_loaded_test.json");
+        let abi = ABI
+            .replace("registryTest", "loadedTest")
+            .replace("RegistryTest", "LoadedTest");
+        std::fs::write(&path, abi).unwrap();
+        let arguments = [EthereumTypes::U256([0; 32])];
+        let ping = selector("loadedTestPing(uint256)");
+
+        let guard = set_enabled(false);
+        transaction(&path, "loadedTestPing", arguments.clone()).unwrap();
+        assert!(lookup_selector(ping).is_empty());
+        drop(guard);
+
+        let guard = set_enabled(true);
+        transaction(&path, "loadedTestPing", arguments).unwrap();
+        drop(guard);
+        let functions = lookup_selector(ping);
+        assert_eq!(functions.len(), 1);
+        assert_eq!(functions[0].contract, "registry_loaded_test");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn on_unknown_test() {
+        use std::sync::Mutex;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let hook_seen = Arc::clone(&seen);
+        on_unknown(move |unknown| hook_seen.lock().unwrap().push(*unknown));
+
+        let unknown = selector("unknownTestMissing(uint256)");
+        assert!(lookup_selector(unknown).is_empty());
+        assert!(lookup_error(unknown).is_empty());
+        assert!(lookup_topic(&event_topic("UnknownTestMissing()")).is_empty());
+        // once registered, e.g. by whatever consumed the reported unknowns, lookups succeed
+        let abi = ABI
+            .replace("registryTest", "unknownTest")
+            .replace("RegistryTest", "UnknownTest");
+        let abi: Abi = serde_json::from_str(&abi).unwrap();
+        register("Resolved", &abi);
+        assert_eq!(
+            lookup_selector(selector("unknownTestPing(uint256)")).len(),
+            1
+        );
+
+        // other tests look up unknowns concurrently
+        let seen = seen.lock().unwrap();
+        assert!(seen.contains(&Unknown::Selector(unknown)));
+        assert!(seen.contains(&Unknown::Error(unknown)));
+        assert!(seen.contains(&Unknown::Topic(event_topic("UnknownTestMissing()"))));
+        assert!(!seen.contains(&Unknown::Selector(selector("unknownTestPing(uint256)"))));
+    }
+}
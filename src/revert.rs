@@ -0,0 +1,393 @@
+use crate::{decode, hex, selector, EthError, EthereumTypes, ParamType, Selector, U256};
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+// wrappers are unwrapped at most this deep, revert data can't be nested indefinitely anyway
+const MAX_DEPTH: usize = 8;
+
+/// A decoded layer of revert data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RevertReason {
+    /// A revert without data, e.g. `revert()` or running out of gas.
+    Empty,
+    /// `Error(string)`, the reason of `require` and `revert("…")`.
+    Reason(String),
+    /// `Panic(uint256)`, raised for failed assertions, overflows and the like.
+    Panic(U256),
+    /// A custom error registered with the [`RevertDecoder`].
+    Custom {
+        signature: String,
+        arguments: Vec<EthereumTypes>,
+    },
+    Unknown(Vec<u8>),
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("reverted without data"),
+            Self::Reason(reason) => f.write_str(reason),
+            Self::Panic(code) => {
+                write!(f, "panic {:#x}", code)?;
+                match panic_description(code) {
+                    Some(description) => write!(f, " ({})", description),
+                    None => Ok(()),
+                }
+            }
+            Self::Custom { signature, .. } => f.write_str(signature),
+            Self::Unknown(data) => write!(f, "unknown revert {}", hex::encode_prefixed(data)),
+        }
+    }
+}
+
+fn panic_description(code: &U256) -> Option<&'static str> {
+    if code.leading_zeros() < 248 {
+        return None;
+    }
+    Some(match code.0[31] {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on an empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "too much memory allocated",
+        0x51 => "call to a zero internal function",
+        _ => return None,
+    })
+}
+
+/// Decodes revert data, unwrapping errors that carry the revert data of a failed inner call.
+///
+/// Besides custom errors with a `bytes` parameter holding the inner revert data, e.g. the
+/// `FailedOpWithRevert` of ERC-4337 entry points, this unwraps the `Error(string)` of contracts
+/// bubbling up reverts as strings: raw revert data as the string, its hex encoding, or a prefix
+/// like `GS013: 0x…` followed by the hex. Unknown errors whose only parameter is `bytes` holding
+/// revert data are unwrapped as well.
+#[derive(Clone, Debug)]
+pub struct RevertDecoder {
+    errors: HashMap<Selector, (String, Vec<ParamType>)>,
+}
+
+impl Default for RevertDecoder {
+    fn default() -> Self {
+        let mut decoder = Self {
+            errors: HashMap::new(),
+        };
+        for signature in ["Error(string)", "Panic(uint256)"] {
+            decoder = decoder
+                .error(signature)
+                .unwrap_or_else(|e| unreachable!("{}", e));
+        }
+        decoder
+    }
+}
+
+impl RevertDecoder {
+    /// A decoder of `Error(string)` and `Panic(uint256)`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a custom error by its canonical signature, e.g. `ExecutionFailed(bytes)`.
+    pub fn error(mut self, signature: &str) -> Result<Self, String> {
+        let types = signature
+            .find('(')
+            .and_then(|open| ParamType::from_name(&signature[open..]))
+            .and_then(|tuple| match tuple {
+                ParamType::Tuple(types) => Some(types),
+                _ => None,
+            })
+            .ok_or_else(|| format!("Invalid error signature {}.", signature))?;
+        self.errors
+            .insert(selector(signature), (signature.to_owned(), types));
+        Ok(self)
+    }
+
+    /// Registers the errors of an [`EthError`], e.g. one derived for a contract.
+    pub fn eth_error<E: EthError>(self) -> Result<Self, String> {
+        E::signatures()
+            .iter()
+            .try_fold(self, |decoder, signature| decoder.error(signature))
+    }
+
+    /// The innermost meaningful reason of the revert.
+    pub fn decode(&self, data: &[u8]) -> RevertReason {
+        self.unwrap(data).pop().unwrap_or(RevertReason::Empty)
+    }
+
+    /// Every layer of the revert from the outermost error to the innermost one, at most 8.
+    pub fn unwrap(&self, data: &[u8]) -> Vec<RevertReason> {
+        let mut layers = Vec::new();
+        let mut data = Cow::Borrowed(data);
+        loop {
+            // the layers left to unwrap below this one
+            let depth = MAX_DEPTH - layers.len() - 1;
+            let (reason, inner) = match &data {
+                Cow::Borrowed(data) => self.decode_layer(data, depth),
+                Cow::Owned(data) => {
+                    let (reason, inner) = self.decode_layer(data, depth);
+                    (reason, inner.map(|inner| Cow::Owned(inner.into_owned())))
+                }
+            };
+            layers.push(reason);
+            match inner {
+                Some(inner) => data = inner,
+                None => return layers,
+            }
+        }
+    }
+
+    // whether the data is revert data this decoder understands, looking at most `depth` layers
+    // deeper for raw revert data bubbled up as a string, what is deeper counts as understood
+    fn recognizes(&self, data: &[u8], depth: usize) -> bool {
+        let (signature, types) =
+            match Selector::from_calldata(data).and_then(|s| self.errors.get(&s)) {
+                Some(error) => error,
+                None => return false,
+            };
+        if signature == "Error(string)" {
+            return single_bytes(&data[4..]).is_some_and(|bytes| {
+                std::str::from_utf8(bytes).is_ok()
+                    || depth == 0
+                    || self.recognizes(bytes, depth - 1)
+            });
+        }
+        decode(types, &data[4..]).is_ok()
+    }
+
+    // whether the inner data of a layer is unwrapped, with `depth` layers left below the layer
+    fn unwraps(&self, inner: &[u8], depth: usize) -> bool {
+        depth > 0 && self.recognizes(inner, depth - 1)
+    }
+
+    // the reason of the outermost error and the revert data it wraps, if any
+    fn decode_layer<'d>(
+        &self,
+        data: &'d [u8],
+        depth: usize,
+    ) -> (RevertReason, Option<Cow<'d, [u8]>>) {
+        let selector = match Selector::from_calldata(data) {
+            Some(selector) => selector,
+            None if data.is_empty() => return (RevertReason::Empty, None),
+            None => return (RevertReason::Unknown(data.to_vec()), None),
+        };
+        let (signature, types) = match self.errors.get(&selector) {
+            Some(error) => error,
+            None => {
+                // an unknown wrapper of a single `bytes` parameter
+                let inner = single_bytes(&data[4..]).filter(|inner| self.unwraps(inner, depth));
+                return (
+                    RevertReason::Unknown(data.to_vec()),
+                    inner.map(Cow::Borrowed),
+                );
+            }
+        };
+        if signature == "Error(string)" {
+            return self.decode_reason(data, depth);
+        }
+        let arguments = match decode(types, &data[4..]) {
+            Ok(arguments) => arguments,
+            Err(_) => return (RevertReason::Unknown(data.to_vec()), None),
+        };
+        if signature == "Panic(uint256)" {
+            return (
+                RevertReason::Panic(U256(arguments[0].value_as_u256())),
+                None,
+            );
+        }
+        let inner = arguments.iter().find_map(|argument| match argument {
+            EthereumTypes::Bytes(inner) if self.unwraps(inner, depth) => Some(inner.clone()),
+            EthereumTypes::String(s) => embedded_hex(s).filter(|inner| self.unwraps(inner, depth)),
+            _ => None,
+        });
+        let reason = RevertReason::Custom {
+            signature: signature.clone(),
+            arguments,
+        };
+        (reason, inner.map(Cow::Owned))
+    }
+
+    fn decode_reason<'d>(
+        &self,
+        data: &'d [u8],
+        depth: usize,
+    ) -> (RevertReason, Option<Cow<'d, [u8]>>) {
+        // the string of `Error(string)` is encoded like `bytes`, and may not even be utf-8 if it
+        // is raw revert data
+        let bytes = match single_bytes(&data[4..]) {
+            Some(bytes) => bytes,
+            None => return (RevertReason::Unknown(data.to_vec()), None),
+        };
+        if self.unwraps(bytes, depth) {
+            return (
+                RevertReason::Reason(hex::encode_prefixed(bytes)),
+                Some(Cow::Borrowed(bytes)),
+            );
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(reason) => {
+                let inner = embedded_hex(reason).filter(|inner| self.unwraps(inner, depth));
+                (
+                    RevertReason::Reason(reason.to_owned()),
+                    inner.map(Cow::Owned),
+                )
+            }
+            Err(_) => (RevertReason::Unknown(data.to_vec()), None),
+        }
+    }
+}
+
+// the content of a single `bytes` parameter, borrowed from the data
+fn single_bytes(data: &[u8]) -> Option<&[u8]> {
+    let offset = read_usize(data, 0)?;
+    let len = read_usize(data, offset)?;
+    data.get(offset.checked_add(32)?..)?.get(..len)
+}
+
+fn read_usize(data: &[u8], at: usize) -> Option<usize> {
+    let word = data.get(at..at.checked_add(32)?)?;
+    if word[..28].iter().any(|b| *b != 0) {
+        return None;
+    }
+    Some(u32::from_be_bytes([word[28], word[29], word[30], word[31]]) as usize)
+}
+
+// the revert data of a string that is, or ends with, `0x` followed by hex
+fn embedded_hex(s: &str) -> Option<Vec<u8>> {
+    let start = s.rfind("0x")?;
+    let digits = s[start..].trim_end();
+    // at least a selector, and whole bytes since a missing digit means the data was truncated
+    if digits.len() < 10 || digits.len() % 2 == 1 {
+        return None;
+    }
+    hex::decode(digits).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_revert_reason;
+    use crate::error::encode_error;
+
+    #[test]
+    fn decode_test() {
+        let decoder = RevertDecoder::new();
+        assert_eq!(decoder.decode(&[]), RevertReason::Empty);
+        assert_eq!(
+            decoder.decode(&encode_revert_reason("Ownable: caller is not the owner")),
+            RevertReason::Reason("Ownable: caller is not the owner".to_owned())
+        );
+        let panic = encode_error("Panic(uint256)", &[U256::from(0x11_u64).into()]);
+        assert_eq!(
+            decoder.decode(&panic).to_string(),
+            "panic 0x11 (arithmetic overflow or underflow)"
+        );
+        assert_eq!(
+            decoder.decode(&[0xde, 0xad, 0xbe, 0xef]),
+            RevertReason::Unknown(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        assert!(RevertDecoder::new().error("Broken(uint257)").is_err());
+    }
+
+    #[test]
+    fn nested_reason_test() {
+        let decoder = RevertDecoder::new();
+        let inner = encode_revert_reason("ERC20: transfer amount exceeds balance");
+        let expected = RevertReason::Reason("ERC20: transfer amount exceeds balance".to_owned());
+
+        // raw revert data bubbled up as the string
+        let raw = encode_error("Error(string)", &[EthereumTypes::Bytes(inner.clone())]);
+        assert_eq!(decoder.unwrap(&raw).len(), 2);
+        assert_eq!(decoder.decode(&raw), expected);
+
+        // its hex encoding, with or without a prefix like the codes of Safe
+        let hex = encode_revert_reason(&hex::encode_prefixed(&inner));
+        assert_eq!(decoder.decode(&hex), expected);
+        let safe = encode_revert_reason(&format!("GS013: {}", hex::encode_prefixed(&inner)));
+        let layers = decoder.unwrap(&safe);
+        assert!(
+            matches!(&layers[0], RevertReason::Reason(r) if r.starts_with("GS013: 0x08c379a0"))
+        );
+        assert_eq!(layers[1], expected);
+
+        // a string merely containing 0x isn't unwrapped
+        let address = encode_revert_reason("not allowed: 0x1234");
+        assert_eq!(decoder.unwrap(&address).len(), 1);
+        // neither is truncated hex
+        let hex = hex::encode_prefixed(&inner);
+        assert_eq!(embedded_hex(&hex[..hex.len() - 1]), None);
+    }
+
+    #[test]
+    fn custom_wrapper_test() {
+        let panic = encode_error("Panic(uint256)", &[U256::ONE.into()]);
+        let wrapped = encode_error(
+            "FailedOpWithRevert(uint256,string,bytes)",
+            &[
+                U256::ZERO.into(),
+                EthereumTypes::String("AA23 reverted".to_owned()),
+                EthereumTypes::Bytes(panic.clone()),
+            ],
+        );
+        // unknown wrappers with more than a `bytes` parameter aren't unwrapped
+        assert_eq!(
+            RevertDecoder::new().unwrap(&wrapped),
+            [RevertReason::Unknown(wrapped.clone())]
+        );
+
+        let decoder = RevertDecoder::new()
+            .error("FailedOpWithRevert(uint256,string,bytes)")
+            .unwrap();
+        let layers = decoder.unwrap(&wrapped);
+        assert_eq!(layers.len(), 2);
+        assert!(
+            matches!(&layers[0], RevertReason::Custom { signature, arguments }
+            if signature == "FailedOpWithRevert(uint256,string,bytes)" && arguments.len() == 3)
+        );
+        assert_eq!(layers[1], RevertReason::Panic(U256::ONE));
+
+        // an unknown error wrapping only the inner revert data
+        let unknown = encode_error("ExecutionFailed(bytes)", &[EthereumTypes::Bytes(wrapped)]);
+        assert_eq!(decoder.unwrap(&unknown).len(), 3);
+        assert_eq!(decoder.decode(&unknown), RevertReason::Panic(U256::ONE));
+    }
+
+    #[test]
+    fn deeply_nested_test() {
+        // thousands of layers of raw revert data bubbled up through `Error(string)`, of which only
+        // the first few are looked at
+        let decoder = RevertDecoder::new();
+        let innermost = encode_revert_reason("innermost");
+        // the layers are built around the innermost data at once, encoding the growing data for
+        // every layer would be quadratic itself
+        let (mut prefixes, mut paddings) = (Vec::new(), Vec::new());
+        let mut len = innermost.len();
+        for _ in 0..5000 {
+            let mut prefix = selector("Error(string)").0.to_vec();
+            prefix.extend_from_slice(&U256::from(0x20_u64).0);
+            prefix.extend_from_slice(&U256::from(len as u64).0);
+            let padding = (32 - len % 32) % 32;
+            len += prefix.len() + padding;
+            prefixes.push(prefix);
+            paddings.push(padding);
+        }
+        let mut data: Vec<u8> = prefixes.into_iter().rev().flatten().collect();
+        data.extend_from_slice(&innermost);
+        for padding in paddings {
+            data.resize(data.len() + padding, 0);
+        }
+        assert_eq!(data.len(), len);
+        let wrapped = encode_error("ExecutionFailed(bytes)", &[EthereumTypes::Bytes(data)]);
+        assert!(wrapped.len() > 450_000);
+        let layers = decoder.unwrap(&wrapped);
+        assert_eq!(layers.len(), MAX_DEPTH);
+        assert!(matches!(&layers[0], RevertReason::Unknown(_)));
+        assert!(matches!(&layers[1], RevertReason::Reason(r) if r.starts_with("0x08c379a0")));
+        assert!(matches!(&layers[MAX_DEPTH - 1], RevertReason::Unknown(_)));
+    }
+}
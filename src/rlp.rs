@@ -0,0 +1,59 @@
+// the RLP encoding of typed transactions and their fields
+
+pub(crate) fn string(bytes: &[u8]) -> Vec<u8> {
+    // a single byte below 0x80 is its own encoding
+    if let [byte] = bytes {
+        if *byte < 0x80 {
+            return vec![*byte];
+        }
+    }
+    let mut encoded = header(0x80, bytes.len());
+    encoded.extend_from_slice(bytes);
+    encoded
+}
+
+/// Big-endian integers without leading zeros, zero is the empty string.
+pub(crate) fn uint(be_bytes: &[u8]) -> Vec<u8> {
+    let leading = be_bytes.iter().take_while(|b| **b == 0).count();
+    string(&be_bytes[leading..])
+}
+
+pub(crate) fn list(payload: &[u8]) -> Vec<u8> {
+    let mut encoded = header(0xc0, payload.len());
+    encoded.extend_from_slice(payload);
+    encoded
+}
+
+fn header(offset: u8, len: usize) -> Vec<u8> {
+    if len < 56 {
+        return vec![offset + len as u8];
+    }
+    let bytes = (len as u64).to_be_bytes();
+    let leading = bytes.iter().take_while(|b| **b == 0).count();
+    let mut header = vec![offset + 55 + (8 - leading) as u8];
+    header.extend_from_slice(&bytes[leading..]);
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_test() {
+        assert_eq!(string(&[]), [0x80]);
+        assert_eq!(string(&[0x7f]), [0x7f]);
+        assert_eq!(string(&[0x80]), [0x81, 0x80]);
+        assert_eq!(string(b"dog"), [0x83, b'd', b'o', b'g']);
+        let long = [0xaa; 56];
+        assert_eq!(string(&long)[..2], [0xb8, 56]);
+    }
+
+    #[test]
+    fn uint_test() {
+        assert_eq!(uint(&0_u64.to_be_bytes()), [0x80]);
+        assert_eq!(uint(&15_u64.to_be_bytes()), [0x0f]);
+        assert_eq!(uint(&1024_u64.to_be_bytes()), [0x82, 0x04, 0x00]);
+        assert_eq!(list(&[]), [0xc0]);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::eip712::{self, hash_struct, type_hash, typed_data_hash, Eip712Domain};
+use crate::{Address, ChainId, U256};
+
+/// Seaport 1.6, deployed at the same address on every supported chain.
+pub const SEAPORT_1_6: Address = Address([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x68, 0xf1, 0x16, 0xa8, 0x94, 0x98, 0x4e, 0x2d, 0xb1,
+    0x12, 0x3e, 0xb3, 0x95,
+]);
+
+const OFFER_ITEM_TYPE: &str = "OfferItem(uint8 itemType,address token,uint256 identifierOrCriteria,uint256 startAmount,uint256 endAmount)";
+const CONSIDERATION_ITEM_TYPE: &str = "ConsiderationItem(uint8 itemType,address token,uint256 identifierOrCriteria,uint256 startAmount,uint256 endAmount,address recipient)";
+const ORDER_COMPONENTS_TYPE: &str = "OrderComponents(address offerer,address zone,OfferItem[] offer,ConsiderationItem[] consideration,uint8 orderType,uint256 startTime,uint256 endTime,bytes32 zoneHash,uint256 salt,bytes32 conduitKey,uint256 counter)";
+
+/// The kind of asset of an offer or consideration item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemType {
+    Native = 0,
+    Erc20 = 1,
+    Erc721 = 2,
+    Erc1155 = 3,
+    Erc721WithCriteria = 4,
+    Erc1155WithCriteria = 5,
+}
+
+/// Whether an order can be partially filled and who may fulfill it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OrderType {
+    FullOpen = 0,
+    PartialOpen = 1,
+    FullRestricted = 2,
+    PartialRestricted = 3,
+    Contract = 4,
+}
+
+/// An item offered by the offerer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OfferItem {
+    pub item_type: ItemType,
+    pub token: Address,
+    pub identifier_or_criteria: U256,
+    pub start_amount: U256,
+    pub end_amount: U256,
+}
+
+/// An item the offerer requires to be sent to the recipient.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsiderationItem {
+    pub item_type: ItemType,
+    pub token: Address,
+    pub identifier_or_criteria: U256,
+    pub start_amount: U256,
+    pub end_amount: U256,
+    pub recipient: Address,
+}
+
+/// The signed part of a Seaport order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderComponents {
+    pub offerer: Address,
+    pub zone: Address,
+    pub offer: Vec<OfferItem>,
+    pub consideration: Vec<ConsiderationItem>,
+    pub order_type: OrderType,
+    pub start_time: U256,
+    pub end_time: U256,
+    pub zone_hash: [u8; 32],
+    pub salt: U256,
+    pub conduit_key: [u8; 32],
+    /// The offerer's counter at signing time, bumping it cancels all of their orders.
+    pub counter: U256,
+}
+
+/// The EIP-712 domain of [`SEAPORT_1_6`] on the given chain.
+pub fn seaport_domain(chain: ChainId) -> Eip712Domain {
+    Eip712Domain {
+        name: Some("Seaport".to_owned()),
+        version: Some("1.6".to_owned()),
+        chain_id: Some(chain),
+        verifying_contract: Some(SEAPORT_1_6),
+        salt: None,
+    }
+}
+
+impl OfferItem {
+    pub fn struct_hash(&self) -> [u8; 32] {
+        hash_struct(
+            &type_hash(OFFER_ITEM_TYPE),
+            &[
+                small_word(self.item_type as u8),
+                address_word(&self.token),
+                self.identifier_or_criteria.0,
+                self.start_amount.0,
+                self.end_amount.0,
+            ],
+        )
+    }
+}
+
+impl ConsiderationItem {
+    pub fn struct_hash(&self) -> [u8; 32] {
+        hash_struct(
+            &type_hash(CONSIDERATION_ITEM_TYPE),
+            &[
+                small_word(self.item_type as u8),
+                address_word(&self.token),
+                self.identifier_or_criteria.0,
+                self.start_amount.0,
+                self.end_amount.0,
+                address_word(&self.recipient),
+            ],
+        )
+    }
+}
+
+impl OrderComponents {
+    /// The type hash, with the referenced item types appended in alphabetical order.
+    pub fn type_hash() -> [u8; 32] {
+        type_hash(&format!(
+            "{}{}{}",
+            ORDER_COMPONENTS_TYPE, CONSIDERATION_ITEM_TYPE, OFFER_ITEM_TYPE
+        ))
+    }
+
+    /// The order hash, as returned by Seaport's `getOrderHash`.
+    pub fn order_hash(&self) -> [u8; 32] {
+        let offer: Vec<[u8; 32]> = self.offer.iter().map(OfferItem::struct_hash).collect();
+        let consideration: Vec<[u8; 32]> = self
+            .consideration
+            .iter()
+            .map(ConsiderationItem::struct_hash)
+            .collect();
+        hash_struct(
+            &Self::type_hash(),
+            &[
+                address_word(&self.offerer),
+                address_word(&self.zone),
+                eip712::hash_words(&offer),
+                eip712::hash_words(&consideration),
+                small_word(self.order_type as u8),
+                self.start_time.0,
+                self.end_time.0,
+                self.zone_hash,
+                self.salt.0,
+                self.conduit_key,
+                self.counter.0,
+            ],
+        )
+    }
+
+    /// The hash the offerer signs, for the order on the given Seaport domain.
+    pub fn signing_hash(&self, domain: &Eip712Domain) -> [u8; 32] {
+        typed_data_hash(&domain.separator(), &self.order_hash())
+    }
+}
+
+fn address_word(address: &Address) -> [u8; 32] {
+    address.to_word()
+}
+
+fn small_word(value: u8) -> [u8; 32] {
+    U256::from(u64::from(value)).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hex, parse_address};
+
+    fn order() -> OrderComponents {
+        OrderComponents {
+            offerer: Address([0x11; 20]),
+            zone: Address([0; 20]),
+            offer: vec![OfferItem {
+                item_type: ItemType::Erc721,
+                token: Address([0x22; 20]),
+                identifier_or_criteria: U256::from(7_u64),
+                start_amount: U256::ONE,
+                end_amount: U256::ONE,
+            }],
+            consideration: vec![ConsiderationItem {
+                item_type: ItemType::Native,
+                token: Address([0; 20]),
+                identifier_or_criteria: U256::ZERO,
+                start_amount: U256::from(10_u64.pow(18)),
+                end_amount: U256::from(10_u64.pow(18)),
+                recipient: Address([0x11; 20]),
+            }],
+            order_type: OrderType::FullOpen,
+            start_time: U256::ZERO,
+            end_time: U256::MAX,
+            zone_hash: [0; 32],
+            salt: U256::ONE,
+            conduit_key: [0; 32],
+            counter: U256::ZERO,
+        }
+    }
+
+    #[test]
+    fn type_hashes_test() {
+        assert_eq!(
+            hex::encode(type_hash(OFFER_ITEM_TYPE)),
+            "a66999307ad1bb4fde44d13a5d710bd7718e0c87c1eef68a571629fbf5b93d02"
+        );
+        assert_eq!(
+            hex::encode(type_hash(CONSIDERATION_ITEM_TYPE)),
+            "42d81c6929ffdc4eb27a0808e40e82516ad42296c166065de7f812492304ff6e"
+        );
+        assert_eq!(
+            hex::encode(OrderComponents::type_hash()),
+            "fa445660b7e21515a59617fcd68910b487aa5808b8abda3d78bc85df364b2c2f"
+        );
+        assert_eq!(
+            SEAPORT_1_6,
+            parse_address("0x0000000000000068F116a894984e2DB1123eB395").unwrap()
+        );
+    }
+
+    #[test]
+    fn order_hash_test() {
+        let order = order();
+        let mut other = order.clone();
+        other.counter = U256::ONE;
+        assert_ne!(order.order_hash(), other.order_hash());
+
+        let domain = seaport_domain(ChainId::MAINNET);
+        assert_eq!(
+            order.signing_hash(&domain),
+            typed_data_hash(&domain.separator(), &order.order_hash())
+        );
+        assert_ne!(
+            order.signing_hash(&domain),
+            order.signing_hash(&seaport_domain(ChainId::BASE))
+        );
+    }
+}
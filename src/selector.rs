@@ -0,0 +1,308 @@
+use crate::hex;
+use crate::types::split_components;
+
+use sha3::{Digest, Keccak256};
+
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "lru")]
+use std::sync::{Mutex, OnceLock};
+
+/// Maximum number of canonical signatures kept in the hash cache.
+#[cfg(feature = "lru")]
+pub const SIGNATURE_CACHE_CAPACITY: usize = 1024;
+
+/// A 4 byte function or custom error selector, the first 4 bytes of the signature hash.
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Selector(pub [u8; 4]);
+
+impl Selector {
+    #[inline]
+    pub const fn new(bytes: [u8; 4]) -> Self {
+        Self(bytes)
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 4] {
+        &self.0
+    }
+
+    /// The selector of the first 4 bytes of calldata, if there are that many.
+    #[inline]
+    pub fn from_calldata(calldata: &[u8]) -> Option<Self> {
+        match calldata {
+            [a, b, c, d, ..] => Some(Self([*a, *b, *c, *d])),
+            _ => None,
+        }
+    }
+}
+
+impl From<[u8; 4]> for Selector {
+    fn from(bytes: [u8; 4]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Selector> for [u8; 4] {
+    fn from(selector: Selector) -> Self {
+        selector.0
+    }
+}
+
+impl PartialEq<[u8; 4]> for Selector {
+    fn eq(&self, other: &[u8; 4]) -> bool {
+        self.0 == *other
+    }
+}
+
+impl AsRef<[u8]> for Selector {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl FromStr for Selector {
+    type Err = String;
+
+    /// Parses 4 bytes of hex, with or without the `0x` prefix.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        hex::decode_to_array(s)
+            .map(Self)
+            .map_err(|e| format!("Invalid selector {}: {}", s, e))
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode_prefixed(self.0))
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Selector {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let mut hex = [0_u8; 10];
+        defmt::write!(f, "{=str}", hex::encode_prefixed_into(&self.0, &mut hex))
+    }
+}
+
+impl fmt::Debug for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Selector({})", self)
+    }
+}
+
+/// Returns the 4 byte function selector of a canonical signature, e.g. `transfer(address,uint256)`.
+#[inline]
+pub fn selector(signature: &str) -> Selector {
+    let hash = signature_hash(signature);
+    Selector([hash[0], hash[1], hash[2], hash[3]])
+}
+
+/// Returns the `topic0` of an event's logs, the keccak hash of its canonical signature.
+///
+/// Besides canonical signatures like `Transfer(address,address,uint256)` this accepts
+/// human-readable ones like `event Transfer(address indexed from, address indexed to, uint256)`,
+/// whose keyword, parameter names and `indexed` markers aren't part of the hash. The `uint` and
+/// `int` aliases are hashed as `uint256` and `int256`.
+pub fn event_topic(signature: &str) -> [u8; 32] {
+    signature_hash(&canonical_event_signature(signature))
+}
+
+fn canonical_event_signature(signature: &str) -> String {
+    let signature = signature.trim();
+    let signature = signature.strip_prefix("event ").unwrap_or(signature);
+    match (signature.find('('), signature.rfind(')')) {
+        (Some(open), Some(close)) if open < close => format!(
+            "{}({})",
+            signature[..open].trim(),
+            canonical_params(&signature[open + 1..close])
+        ),
+        _ => signature.to_owned(),
+    }
+}
+
+// the types of comma separated parameters, without names and `indexed` markers
+fn canonical_params(params: &str) -> String {
+    let params = split_components(params.trim()).unwrap_or_else(|| vec![params]);
+    let types: Vec<String> = params
+        .iter()
+        .map(|param| {
+            let param = param.trim();
+            match param.strip_prefix('(') {
+                Some(rest) => {
+                    // a tuple and its array suffix, e.g. `(address to, uint256 amount)[] batch`
+                    let close = rest.rfind(')').unwrap_or(rest.len());
+                    let suffix = rest[close..].trim_start_matches(')');
+                    let suffix = suffix.split_whitespace().next().unwrap_or("");
+                    format!("({}){}", canonical_params(&rest[..close]), suffix)
+                }
+                None => {
+                    let kind = param.split_whitespace().next().unwrap_or("");
+                    match kind.find('[') {
+                        Some(i) => format!("{}{}", alias(&kind[..i]), &kind[i..]),
+                        None => alias(kind).to_owned(),
+                    }
+                }
+            }
+        })
+        .collect();
+    types.join(",")
+}
+
+// `uint` and `int` are aliases of their 256 bit types
+fn alias(kind: &str) -> &str {
+    match kind {
+        "uint" => "uint256",
+        "int" => "int256",
+        kind => kind,
+    }
+}
+
+// selector of the function `name` taking the given canonical input types. The signature is fed
+// into the hasher piece by piece instead of being concatenated into a temporary string first.
+#[cfg(not(feature = "lru"))]
+pub(crate) fn function_selector<'t, I>(name: &str, types: I) -> Selector
+where
+    I: IntoIterator<Item = &'t str>,
+{
+    let mut keccak = Keccak256::new();
+    keccak.update(name);
+    keccak.update("(");
+    for (i, ty) in types.into_iter().enumerate() {
+        if i > 0 {
+            keccak.update(",");
+        }
+        keccak.update(ty);
+    }
+    keccak.update(")");
+    let hash = keccak.finalize();
+    Selector([hash[0], hash[1], hash[2], hash[3]])
+}
+
+// the cache is keyed by the full signature, so it is written into a reused per-thread buffer
+#[cfg(feature = "lru")]
+pub(crate) fn function_selector<'t, I>(name: &str, types: I) -> Selector
+where
+    I: IntoIterator<Item = &'t str>,
+{
+    thread_local! {
+        static SIGNATURE: std::cell::RefCell<String> = const { std::cell::RefCell::new(String::new()) };
+    }
+    SIGNATURE.with(|signature| {
+        let mut signature = signature.borrow_mut();
+        signature.clear();
+        signature.push_str(name);
+        signature.push('(');
+        for (i, ty) in types.into_iter().enumerate() {
+            if i > 0 {
+                signature.push(',');
+            }
+            signature.push_str(ty);
+        }
+        signature.push(')');
+        selector(&signature)
+    })
+}
+
+// keccak hash of the canonical signature, memoized in an LRU cache if the feature is enabled
+#[cfg(not(feature = "lru"))]
+pub(crate) fn signature_hash(signature: &str) -> [u8; 32] {
+    keccak256(signature.as_bytes())
+}
+
+#[cfg(feature = "lru")]
+pub(crate) fn signature_hash(signature: &str) -> [u8; 32] {
+    static CACHE: OnceLock<Mutex<lru::LruCache<String, [u8; 32]>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| {
+        let capacity = std::num::NonZeroUsize::new(SIGNATURE_CACHE_CAPACITY).unwrap();
+        Mutex::new(lru::LruCache::new(capacity))
+    });
+
+    // a poisoned lock only means another thread panicked mid-update, the cached hashes are still valid
+    let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(hash) = cache.get(signature) {
+        return *hash;
+    }
+    let hash = keccak256(signature.as_bytes());
+    cache.put(signature.to_owned(), hash);
+    hash
+}
+
+#[inline]
+fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    let mut keccak = Keccak256::new();
+    keccak.update(bytes);
+    keccak.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selector_test() {
+        assert_eq!(selector("balanceOf(address)"), [0x70, 0xa0, 0x82, 0x31]);
+        assert_eq!(
+            selector("transfer(address,uint256)"),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+        // a second lookup of the same signature has to produce the same result (cache hit)
+        assert_eq!(
+            selector("transfer(address,uint256)"),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+    }
+
+    #[test]
+    fn event_topic_test() {
+        let transfer = hex::decode_to_array::<32>(
+            "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+        )
+        .unwrap();
+        assert_eq!(event_topic("Transfer(address,address,uint256)"), transfer);
+        assert_eq!(
+            event_topic("event Transfer(address indexed from, address indexed to, uint value)"),
+            transfer
+        );
+        assert_eq!(
+            event_topic("Batch((address to, uint256[] ids)[] items, bytes)"),
+            event_topic("Batch((address,uint256[])[],bytes)")
+        );
+        assert_eq!(event_topic("Paused( )"), event_topic("Paused()"));
+        // aliases are resolved without any whitespace too
+        assert_eq!(event_topic("Transfer(address,address,uint)"), transfer);
+        assert_eq!(
+            event_topic("Values(int[],uint[2])"),
+            event_topic("Values(int256[],uint256[2])")
+        );
+    }
+
+    #[test]
+    fn selector_type_test() {
+        let transfer = selector("transfer(address,uint256)");
+        assert_eq!(transfer.to_string(), "0xa9059cbb");
+        assert_eq!("0xa9059cbb".parse(), Ok(transfer));
+        assert!("0xa9059c".parse::<Selector>().is_err());
+        assert!("0xa9059cb".parse::<Selector>().is_err());
+        assert_eq!(
+            Selector::from_calldata(&[0xa9, 0x05, 0x9c, 0xbb, 0x00]),
+            Some(transfer)
+        );
+        assert_eq!(Selector::from_calldata(&[0xa9]), None);
+    }
+
+    #[test]
+    fn function_selector_test() {
+        assert_eq!(
+            function_selector("transfer", vec!["address", "uint256"]),
+            selector("transfer(address,uint256)")
+        );
+        assert_eq!(
+            function_selector("totalSupply", vec![]),
+            [0x18, 0x16, 0x0d, 0xdd]
+        );
+    }
+}
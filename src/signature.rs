@@ -0,0 +1,195 @@
+use crate::{ChainId, U256};
+
+/// Order of the secp256k1 group.
+pub const SECP256K1_N: U256 = U256([
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+]);
+
+// half of the group order, rounded down. Signatures with a larger s are malleable (EIP-2).
+const SECP256K1_HALF_N: U256 = U256([
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+]);
+
+/// A recoverable ECDSA signature over secp256k1.
+///
+/// The recovery id is stored as the y parity, so signatures with any `v` convention compare equal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Signature {
+    pub r: U256,
+    pub s: U256,
+    pub y_parity: bool,
+}
+
+impl Signature {
+    /// Creates a signature from `r`, `s` and a `v` value in any of the common conventions: the
+    /// bare y parity (0 or 1), Ethereum's 27 or 28, or an EIP-155 `chain_id * 2 + 35 + parity`.
+    pub fn from_rsv(r: U256, s: U256, v: u64) -> Result<Self, String> {
+        let y_parity = match v {
+            0 | 27 => false,
+            1 | 28 => true,
+            v if v >= 35 => (v - 35) % 2 == 1,
+            v => return Err(format!("Invalid signature v value {}.", v)),
+        };
+        Ok(Self { r, s, y_parity })
+    }
+
+    /// Parses the 65 byte `r ++ s ++ v` representation used by `eth_sign` and most wallets.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != 65 {
+            return Err(format!(
+                "Signature has to be 65 bytes long, found {}.",
+                bytes.len()
+            ));
+        }
+        let mut r = [0_u8; 32];
+        let mut s = [0_u8; 32];
+        r.copy_from_slice(&bytes[..32]);
+        s.copy_from_slice(&bytes[32..64]);
+        Self::from_rsv(U256(r), U256(s), u64::from(bytes[64]))
+    }
+
+    /// The 65 byte `r ++ s ++ v` representation with `v` being 27 or 28, as expected by `ecrecover`
+    /// based verifiers like permit, Safe and ERC-4337 accounts.
+    pub fn to_bytes(&self) -> [u8; 65] {
+        let mut bytes = [0_u8; 65];
+        bytes[..32].copy_from_slice(&self.r.0);
+        bytes[32..64].copy_from_slice(&self.s.0);
+        bytes[64] = self.v();
+        bytes
+    }
+
+    /// The canonical `v` value, 27 or 28.
+    #[inline]
+    pub fn v(&self) -> u8 {
+        27 + u8::from(self.y_parity)
+    }
+
+    /// The `v` value of an EIP-155 replay protected legacy transaction on the given chain.
+    #[inline]
+    pub fn eip155_v(&self, chain: ChainId) -> u64 {
+        chain.0 * 2 + 35 + u64::from(self.y_parity)
+    }
+
+    /// Whether `s` is in the lower half of the group order, as required by EIP-2 and OpenZeppelin's
+    /// `ECDSA.recover`.
+    #[inline]
+    pub fn is_low_s(&self) -> bool {
+        self.s <= SECP256K1_HALF_N
+    }
+
+    /// Replaces a high `s` with `n - s` and flips the y parity, which yields the other valid
+    /// signature of the same message and key. Low-s signatures are returned unchanged.
+    pub fn normalize_s(self) -> Self {
+        if self.is_low_s() {
+            return self;
+        }
+        // s < n for valid signatures, an invalid s is left as is such that it still fails to verify
+        match SECP256K1_N.checked_sub(self.s) {
+            Some(s) => Self {
+                r: self.r,
+                s,
+                y_parity: !self.y_parity,
+            },
+            None => self,
+        }
+    }
+
+    /// Whether `r` and `s` are both in the range `[1, n - 1]`.
+    pub fn is_valid(&self) -> bool {
+        !self.r.is_zero() && !self.s.is_zero() && self.r < SECP256K1_N && self.s < SECP256K1_N
+    }
+
+    /// Compares the `r ++ s ++ v` bytes in constant time, see [`constant_time_eq`].
+    #[cfg(feature = "subtle")]
+    pub fn constant_time_eq(&self, other: &Self) -> bool {
+        bool::from(subtle::ConstantTimeEq::ct_eq(self, other))
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for Signature {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        subtle::ConstantTimeEq::ct_eq(&self.to_bytes()[..], &other.to_bytes()[..])
+    }
+}
+
+/// Compares two byte strings in time independent of their content with `subtle`, e.g. digests
+/// or MACs checked by verification services. Only the lengths, which are public, may end the
+/// comparison early.
+#[cfg(feature = "subtle")]
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    bool::from(subtle::ConstantTimeEq::ct_eq(a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v_conventions_test() {
+        let (r, s) = (U256::from(1_u64), U256::from(2_u64));
+        let expected = Signature {
+            r,
+            s,
+            y_parity: true,
+        };
+        assert_eq!(Signature::from_rsv(r, s, 1), Ok(expected));
+        assert_eq!(Signature::from_rsv(r, s, 28), Ok(expected));
+        assert_eq!(Signature::from_rsv(r, s, 38), Ok(expected));
+        assert_eq!(Signature::from_rsv(r, s, 27).unwrap().v(), 27);
+        assert!(Signature::from_rsv(r, s, 29).is_err());
+        assert_eq!(expected.eip155_v(ChainId::MAINNET), 38);
+    }
+
+    #[test]
+    fn normalize_s_test() {
+        let high = Signature {
+            r: U256::ONE,
+            s: SECP256K1_N.checked_sub(U256::from(5_u64)).unwrap(),
+            y_parity: false,
+        };
+        assert!(!high.is_low_s());
+        let low = high.normalize_s();
+        assert!(low.is_low_s());
+        assert_eq!(low.s, U256::from(5_u64));
+        assert!(low.y_parity);
+        assert_eq!(low.normalize_s(), low);
+
+        // the half order itself is still a low s
+        let half = Signature {
+            s: SECP256K1_HALF_N,
+            ..low
+        };
+        assert!(half.is_low_s());
+        assert!(!Signature {
+            s: SECP256K1_N,
+            ..low
+        }
+        .is_valid());
+    }
+
+    #[test]
+    fn bytes_roundtrip_test() {
+        let mut bytes = [0x11_u8; 65];
+        bytes[64] = 0x1c;
+        let signature = Signature::from_bytes(&bytes).unwrap();
+        assert!(signature.y_parity);
+        assert_eq!(signature.to_bytes(), bytes);
+        assert!(Signature::from_bytes(&bytes[..64]).is_err());
+    }
+
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn constant_time_eq_test() {
+        assert!(constant_time_eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2]));
+        assert!(constant_time_eq(&[], &[]));
+
+        let signature = Signature::from_rsv(U256::ONE, U256::ONE, 27).unwrap();
+        assert!(signature.constant_time_eq(&Signature::from_rsv(U256::ONE, U256::ONE, 0).unwrap()));
+        assert!(!signature.constant_time_eq(&Signature::from_rsv(U256::ONE, U256::ONE, 1).unwrap()));
+    }
+}
@@ -0,0 +1,310 @@
+use crate::{parse_address, parse_prefixed_address, registry, Abi, Address, ChainId, OwnedAbi};
+
+use serde::Deserialize;
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Where an ABI is loaded from, such that applications configure the source instead of branching
+/// on it.
+///
+/// Every source yields a plain ABI array or an artifact holding it in its `abi` field. The crate
+/// doesn't do network requests itself, remote sources are fetched by a [`Fetch`] implementation
+/// passed to [`AbiSource::load_with`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AbiSource {
+    File(PathBuf),
+    /// Json embedded into the binary, e.g. with `include_str!`.
+    Embedded(&'static str),
+    Url(String),
+    /// The verified ABI of a contract, fetched from the Etherscan v2 API.
+    Etherscan {
+        chain: ChainId,
+        address: Address,
+        api_key: Option<String>,
+    },
+    Stdin,
+}
+
+/// Performs the HTTP GET requests of the remote [`AbiSource`]s, returning the response body.
+///
+/// Implemented for closures, e.g. wrapping a blocking HTTP client.
+pub trait Fetch {
+    fn get(&self, url: &str) -> Result<String, String>;
+}
+
+impl<F: Fn(&str) -> Result<String, String>> Fetch for F {
+    fn get(&self, url: &str) -> Result<String, String> {
+        self(url)
+    }
+}
+
+// fetcher of `AbiSource::load`, which only loads local sources
+struct NoFetch;
+
+impl Fetch for NoFetch {
+    fn get(&self, url: &str) -> Result<String, String> {
+        Err(format!("Fetching {} requires AbiSource::load_with.", url))
+    }
+}
+
+#[derive(Deserialize)]
+struct EtherscanResponse {
+    status: String,
+    result: String,
+}
+
+impl AbiSource {
+    /// Loads the ABI of a local source, fails for [`AbiSource::Url`] and
+    /// [`AbiSource::Etherscan`].
+    pub fn load(&self) -> Result<OwnedAbi, String> {
+        self.load_with(&NoFetch)
+    }
+
+    /// Loads the ABI, fetching remote sources with `fetch`, and adds it to the
+    /// [`registry`] under the name of the source once the registry is enabled.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(source = %self.name()), err)
+    )]
+    pub fn load_with<F: Fetch>(&self, fetch: &F) -> Result<OwnedAbi, String> {
+        let json = match self {
+            Self::File(path) => {
+                std::fs::read_to_string(path).map_err(|e| format!("Couldn't open file: {}", e))?
+            }
+            Self::Embedded(json) => (*json).to_owned(),
+            Self::Url(url) => fetch.get(url)?,
+            Self::Etherscan { .. } => {
+                let url = self.url().unwrap_or_default();
+                let response = fetch.get(&url)?;
+                let response: EtherscanResponse = serde_json::from_str(&response)
+                    .map_err(|e| format!("Unexpected Etherscan response: {}", e))?;
+                // a failed request carries the error message as its result
+                if response.status != "1" {
+                    return Err(format!("Etherscan request failed: {}", response.result));
+                }
+                response.result
+            }
+            Self::Stdin => {
+                let mut json = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut json)
+                    .map_err(|e| format!("Couldn't read stdin: {}", e))?;
+                json
+            }
+        };
+        let abi = Abi::from_str(&json)?.into_owned();
+        match self {
+            Self::File(path) => registry::register_loaded(path, &abi),
+            source => registry::register_loaded_as(&source.name(), &abi),
+        }
+        Ok(abi)
+    }
+
+    /// The url requested for remote sources.
+    pub fn url(&self) -> Option<String> {
+        match self {
+            Self::Url(url) => Some(url.clone()),
+            Self::Etherscan {
+                chain,
+                address,
+                api_key,
+            } => {
+                let mut url = format!(
+                    "https://api.etherscan.io/v2/api?chainid={}&module=contract&action=getabi&address={}",
+                    chain.as_u64(),
+                    address
+                );
+                if let Some(key) = api_key {
+                    url.push_str("&apikey=");
+                    url.push_str(key);
+                }
+                Some(url)
+            }
+            _ => None,
+        }
+    }
+
+    /// The contract name the loaded ABI is registered under, the file stem of files and urls, the
+    /// address for Etherscan.
+    pub fn name(&self) -> String {
+        match self {
+            Self::File(path) => stem(path),
+            Self::Embedded(_) => "embedded".to_owned(),
+            Self::Url(url) => stem(Path::new(url.split(['?', '#']).next().unwrap_or(url))),
+            Self::Etherscan { address, .. } => address.to_string(),
+            Self::Stdin => "stdin".to_owned(),
+        }
+    }
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+impl From<PathBuf> for AbiSource {
+    fn from(path: PathBuf) -> Self {
+        Self::File(path)
+    }
+}
+
+impl From<&Path> for AbiSource {
+    fn from(path: &Path) -> Self {
+        Self::File(path.to_owned())
+    }
+}
+
+/// Parses a source from a configuration value or command line argument: `-` for stdin, an
+/// `http://` or `https://` url, `etherscan:<address>` for mainnet or
+/// `etherscan:<chain short name>:<address>`, and a file path otherwise.
+impl FromStr for AbiSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "-" {
+            Ok(Self::Stdin)
+        } else if s.starts_with("http://") || s.starts_with("https://") {
+            Ok(Self::Url(s.to_owned()))
+        } else if let Some(address) = s.strip_prefix("etherscan:") {
+            let (chain, address) = if address.contains(':') {
+                parse_prefixed_address(address)?
+            } else {
+                (ChainId::MAINNET, parse_address(address)?)
+            };
+            Ok(Self::Etherscan {
+                chain,
+                address,
+                api_key: None,
+            })
+        } else {
+            Ok(Self::File(PathBuf::from(s)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WETH: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+
+    #[test]
+    fn from_str_test() {
+        assert_eq!("-".parse(), Ok(AbiSource::Stdin));
+        assert_eq!(
+            "src/rust_abi.json".parse(),
+            Ok(AbiSource::File(PathBuf::from("src/rust_abi.json")))
+        );
+        assert_eq!(
+            "https://example.com/IERC20.json".parse(),
+            Ok(AbiSource::Url("https://example.com/IERC20.json".to_owned()))
+        );
+        let weth = parse_address(WETH).unwrap();
+        assert_eq!(
+            format!("etherscan:{}", WETH).parse(),
+            Ok(AbiSource::Etherscan {
+                chain: ChainId::MAINNET,
+                address: weth,
+                api_key: None,
+            })
+        );
+        assert_eq!(
+            format!("etherscan:arb1:{}", WETH).parse::<AbiSource>(),
+            Ok(AbiSource::Etherscan {
+                chain: ChainId::ARBITRUM,
+                address: weth,
+                api_key: None,
+            })
+        );
+        assert!("etherscan:0x1234".parse::<AbiSource>().is_err());
+    }
+
+    #[test]
+    fn load_local_test() {
+        let file = AbiSource::from(Path::new("src/rust_abi.json"))
+            .load()
+            .unwrap();
+        let embedded = AbiSource::Embedded(include_str!("rust_abi.json"))
+            .load()
+            .unwrap();
+        assert_eq!(file, embedded);
+        assert_eq!(AbiSource::File("rust_abi.json".into()).name(), "rust_abi");
+        assert!(AbiSource::File("missing.json".into()).load().is_err());
+        assert!(AbiSource::Url("https://example.com/a.json".to_owned())
+            .load()
+            .is_err());
+    }
+
+    #[test]
+    fn load_registered_test() {
+        let json = r#"[{"name":"sourceTestPing","inputs":[]}]"#;
+        let ping = crate::selector("sourceTestPing()");
+
+        let guard = registry::set_enabled(false);
+        AbiSource::Embedded(json).load().unwrap();
+        let fetch = |_: &str| Ok(json.to_owned());
+        AbiSource::Url("https://example.com/Source.json".to_owned())
+            .load_with(&fetch)
+            .unwrap();
+        assert!(registry::lookup_selector(ping).is_empty());
+        drop(guard);
+
+        let guard = registry::set_enabled(true);
+        AbiSource::Embedded(json).load().unwrap();
+        drop(guard);
+        let contracts: Vec<String> = registry::lookup_selector(ping)
+            .into_iter()
+            .map(|f| f.contract)
+            .collect();
+        assert_eq!(contracts, ["embedded"]);
+    }
+
+    #[test]
+    fn load_remote_test() {
+        let url = AbiSource::Url("https://example.com/abi/IERC20.json?raw=1".to_owned());
+        assert_eq!(url.name(), "IERC20");
+        let fetch = |requested: &str| {
+            assert_eq!(requested, "https://example.com/abi/IERC20.json?raw=1");
+            Ok(r#"{"abi":[{"name":"totalSupply","inputs":[]}]}"#.to_owned())
+        };
+        assert!(url
+            .load_with(&fetch)
+            .unwrap()
+            .function("totalSupply")
+            .is_some());
+
+        let etherscan = AbiSource::Etherscan {
+            chain: ChainId::BASE,
+            address: parse_address(WETH).unwrap(),
+            api_key: Some("KEY".to_owned()),
+        };
+        assert_eq!(
+            etherscan.url().unwrap(),
+            format!(
+                "https://api.etherscan.io/v2/api?chainid=8453&module=contract&action=getabi&address={}&apikey=KEY",
+                WETH
+            )
+        );
+        let fetch = |_: &str| {
+            Ok(r#"{"status":"1","message":"OK","result":"[{\"name\":\"deposit\",\"inputs\":[]}]"}"#.to_owned())
+        };
+        assert!(etherscan
+            .load_with(&fetch)
+            .unwrap()
+            .function("deposit")
+            .is_some());
+        let fetch = |_: &str| {
+            Ok(
+                r#"{"status":"0","message":"NOTOK","result":"Contract source code not verified"}"#
+                    .to_owned(),
+            )
+        };
+        assert_eq!(
+            etherscan.load_with(&fetch),
+            Err("Etherscan request failed: Contract source code not verified".to_owned())
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use crate::selector::{selector, Selector};
+use crate::Abi;
+
+use std::collections::HashSet;
+
+/// Token and vault interfaces that can be detected from an ABI.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Standard {
+    Erc20,
+    Erc721,
+    Erc1155,
+    Erc4626,
+}
+
+/// Result of checking an ABI against a [`Standard`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StandardReport {
+    pub standard: Standard,
+    /// Canonical signatures of the required functions and events missing from the ABI.
+    pub missing: Vec<&'static str>,
+}
+
+const ERC20_FUNCTIONS: &[&str] = &[
+    "totalSupply()",
+    "balanceOf(address)",
+    "transfer(address,uint256)",
+    "transferFrom(address,address,uint256)",
+    "approve(address,uint256)",
+    "allowance(address,address)",
+];
+const ERC20_EVENTS: &[&str] = &[
+    "Transfer(address,address,uint256)",
+    "Approval(address,address,uint256)",
+];
+
+const ERC721_FUNCTIONS: &[&str] = &[
+    "balanceOf(address)",
+    "ownerOf(uint256)",
+    "safeTransferFrom(address,address,uint256,bytes)",
+    "safeTransferFrom(address,address,uint256)",
+    "transferFrom(address,address,uint256)",
+    "approve(address,uint256)",
+    "setApprovalForAll(address,bool)",
+    "getApproved(uint256)",
+    "isApprovedForAll(address,address)",
+    "supportsInterface(bytes4)",
+];
+const ERC721_EVENTS: &[&str] = &[
+    "Transfer(address,address,uint256)",
+    "Approval(address,address,uint256)",
+    "ApprovalForAll(address,address,bool)",
+];
+
+const ERC1155_FUNCTIONS: &[&str] = &[
+    "safeTransferFrom(address,address,uint256,uint256,bytes)",
+    "safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)",
+    "balanceOf(address,uint256)",
+    "balanceOfBatch(address[],uint256[])",
+    "setApprovalForAll(address,bool)",
+    "isApprovedForAll(address,address)",
+    "supportsInterface(bytes4)",
+];
+const ERC1155_EVENTS: &[&str] = &[
+    "TransferSingle(address,address,address,uint256,uint256)",
+    "TransferBatch(address,address,address,uint256[],uint256[])",
+    "ApprovalForAll(address,address,bool)",
+    "URI(string,uint256)",
+];
+
+// ERC-4626 vaults are ERC-20 tokens as well, those members are checked in addition to these
+const ERC4626_FUNCTIONS: &[&str] = &[
+    "asset()",
+    "totalAssets()",
+    "convertToShares(uint256)",
+    "convertToAssets(uint256)",
+    "maxDeposit(address)",
+    "previewDeposit(uint256)",
+    "deposit(uint256,address)",
+    "maxMint(address)",
+    "previewMint(uint256)",
+    "mint(uint256,address)",
+    "maxWithdraw(address)",
+    "previewWithdraw(uint256)",
+    "withdraw(uint256,address,address)",
+    "maxRedeem(address)",
+    "previewRedeem(uint256)",
+    "redeem(uint256,address,address)",
+];
+const ERC4626_EVENTS: &[&str] = &[
+    "Deposit(address,address,uint256,uint256)",
+    "Withdraw(address,address,address,uint256,uint256)",
+];
+
+impl Standard {
+    pub const ALL: [Standard; 4] = [
+        Standard::Erc20,
+        Standard::Erc721,
+        Standard::Erc1155,
+        Standard::Erc4626,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Erc20 => "ERC-20",
+            Self::Erc721 => "ERC-721",
+            Self::Erc1155 => "ERC-1155",
+            Self::Erc4626 => "ERC-4626",
+        }
+    }
+
+    /// Canonical signatures of the functions required by the standard.
+    pub fn functions(&self) -> Vec<&'static str> {
+        match self {
+            Self::Erc20 => ERC20_FUNCTIONS.to_vec(),
+            Self::Erc721 => ERC721_FUNCTIONS.to_vec(),
+            Self::Erc1155 => ERC1155_FUNCTIONS.to_vec(),
+            Self::Erc4626 => [ERC20_FUNCTIONS, ERC4626_FUNCTIONS].concat(),
+        }
+    }
+
+    /// Canonical signatures of the events required by the standard.
+    pub fn events(&self) -> Vec<&'static str> {
+        match self {
+            Self::Erc20 => ERC20_EVENTS.to_vec(),
+            Self::Erc721 => ERC721_EVENTS.to_vec(),
+            Self::Erc1155 => ERC1155_EVENTS.to_vec(),
+            Self::Erc4626 => [ERC20_EVENTS, ERC4626_EVENTS].concat(),
+        }
+    }
+}
+
+impl StandardReport {
+    /// Whether the ABI contains every required member of the standard.
+    pub fn is_satisfied(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Checks the ABI against every known [`Standard`], matching functions by selector and events by
+/// canonical signature.
+pub fn detect_standards(abi: &Abi) -> Vec<StandardReport> {
+    let selectors: HashSet<Selector> = abi.functions.iter().map(|f| f.selector()).collect();
+    let events: HashSet<String> = abi.events.iter().map(|e| e.signature()).collect();
+
+    Standard::ALL
+        .iter()
+        .map(|standard| {
+            let missing_functions = standard
+                .functions()
+                .into_iter()
+                .filter(|f| !selectors.contains(&selector(f)));
+            let missing_events = standard
+                .events()
+                .into_iter()
+                .filter(|e| !events.contains(*e));
+            StandardReport {
+                standard: *standard,
+                missing: missing_functions.chain(missing_events).collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_erc20_test() {
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        let abi: Abi = serde_json::from_str(&json).unwrap();
+        let reports = detect_standards(&abi);
+
+        assert_eq!(reports.len(), Standard::ALL.len());
+        assert!(reports[0].is_satisfied());
+        assert!(reports[1]
+            .missing
+            .contains(&"setApprovalForAll(address,bool)"));
+        assert!(reports[3].missing.contains(&"totalAssets()"));
+        assert!(!reports[3].missing.contains(&"totalSupply()"));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{hex, Address, U256};
+
+use serde_json::{json, Map, Value};
+
+use std::collections::BTreeMap;
+
+/// The state override set of geth's `eth_call`, replacing the balance, nonce, code or storage of
+/// accounts for the duration of the call.
+///
+/// ```ignore
+/// // what if the router had the owner's approval
+/// let overrides = StateOverride::new().storage(token, allowance_slot(&owner, &router, U256::ONE), U256::MAX);
+/// let params = eth_call_params(&token, &calldata, "latest", &overrides);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateOverride {
+    pub accounts: BTreeMap<Address, AccountOverride>,
+}
+
+/// Overrides of a single account, unset fields keep their actual value.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountOverride {
+    pub balance: Option<U256>,
+    pub nonce: Option<u64>,
+    pub code: Option<Vec<u8>>,
+    /// Replaces the whole storage, slots missing here are zero.
+    pub state: Option<BTreeMap<U256, U256>>,
+    /// Replaces single slots, the other slots keep their value.
+    pub state_diff: BTreeMap<U256, U256>,
+}
+
+impl StateOverride {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The overrides of the account, to set several fields at once.
+    pub fn account(&mut self, address: Address) -> &mut AccountOverride {
+        self.accounts.entry(address).or_default()
+    }
+
+    pub fn balance(mut self, address: Address, balance: U256) -> Self {
+        self.account(address).balance = Some(balance);
+        self
+    }
+
+    pub fn nonce(mut self, address: Address, nonce: u64) -> Self {
+        self.account(address).nonce = Some(nonce);
+        self
+    }
+
+    /// Replaces the runtime code, e.g. to mock a contract.
+    pub fn code(mut self, address: Address, code: Vec<u8>) -> Self {
+        self.account(address).code = Some(code);
+        self
+    }
+
+    /// Sets a single storage slot, e.g. one computed with the [`storage`](crate::storage)
+    /// helpers.
+    pub fn storage(mut self, address: Address, slot: U256, value: U256) -> Self {
+        self.account(address).state_diff.insert(slot, value);
+        self
+    }
+
+    /// Replaces the whole storage of the account with the given slots.
+    pub fn replace_storage(mut self, address: Address, slots: BTreeMap<U256, U256>) -> Self {
+        self.account(address).state = Some(slots);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// The state override object, the third parameter of `eth_call`.
+    ///
+    /// Quantities are hex without leading zeros, storage slots and values 32 byte words. Geth
+    /// rejects accounts overriding both `state` and `stateDiff`, which is left to the node.
+    pub fn to_json(&self) -> Value {
+        let mut accounts = Map::new();
+        for (address, account) in self.accounts.iter() {
+            let mut fields = Map::new();
+            if let Some(balance) = account.balance {
+                fields.insert("balance".to_owned(), json!(format!("{:#x}", balance)));
+            }
+            if let Some(nonce) = account.nonce {
+                fields.insert("nonce".to_owned(), json!(format!("{:#x}", nonce)));
+            }
+            if let Some(code) = &account.code {
+                fields.insert("code".to_owned(), json!(hex::encode_prefixed(code)));
+            }
+            if let Some(state) = &account.state {
+                fields.insert("state".to_owned(), slots(state));
+            }
+            if !account.state_diff.is_empty() {
+                fields.insert("stateDiff".to_owned(), slots(&account.state_diff));
+            }
+            accounts.insert(hex::encode_prefixed(address), Value::Object(fields));
+        }
+        Value::Object(accounts)
+    }
+}
+
+fn slots(slots: &BTreeMap<U256, U256>) -> Value {
+    slots
+        .iter()
+        .map(|(slot, value)| {
+            (
+                hex::encode_prefixed(slot.0),
+                json!(hex::encode_prefixed(value.0)),
+            )
+        })
+        .collect::<Map<_, _>>()
+        .into()
+}
+
+/// The params array of an `eth_call(call, block, overrides)` request, leaving out empty
+/// overrides for nodes that don't support them.
+pub fn eth_call_params(
+    to: &Address,
+    calldata: &[u8],
+    block: &str,
+    overrides: &StateOverride,
+) -> Value {
+    let call = json!({
+        "to": hex::encode_prefixed(to),
+        "data": hex::encode_prefixed(calldata),
+    });
+    if overrides.is_empty() {
+        json!([call, block])
+    } else {
+        json!([call, block, overrides.to_json()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::allowance_slot;
+
+    #[test]
+    fn to_json_test() {
+        let (token, owner) = (Address([0xaa; 20]), Address([0x11; 20]));
+        let slot = allowance_slot(&owner, &Address([0x22; 20]), U256::ONE);
+        let overrides = StateOverride::new()
+            .balance(owner, U256::from(10_u64.pow(18)))
+            .nonce(owner, 7)
+            .code(token, vec![0x60, 0x00])
+            .storage(token, slot, U256::MAX);
+
+        assert_eq!(
+            overrides.to_json(),
+            json!({
+                "0x1111111111111111111111111111111111111111": {
+                    "balance": "0xde0b6b3a7640000",
+                    "nonce": "0x7",
+                },
+                "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa": {
+                    "code": "0x6000",
+                    "stateDiff": {
+                        hex::encode_prefixed(slot.0): hex::encode_prefixed(U256::MAX.0),
+                    },
+                },
+            })
+        );
+
+        let mut state = BTreeMap::new();
+        state.insert(U256::ZERO, U256::ONE);
+        let replaced = StateOverride::new().replace_storage(token, state).to_json();
+        assert_eq!(
+            replaced[&hex::encode_prefixed(token)]["state"][&hex::encode_prefixed([0; 32])],
+            hex::encode_prefixed(U256::ONE.0)
+        );
+    }
+
+    #[test]
+    fn eth_call_params_test() {
+        let to = Address([0xaa; 20]);
+        let params = eth_call_params(&to, &[0x12, 0x34], "latest", &StateOverride::new());
+        assert_eq!(params.as_array().unwrap().len(), 2);
+
+        let overrides = StateOverride::new().balance(to, U256::ZERO);
+        let params = eth_call_params(&to, &[0x12, 0x34], "latest", &overrides);
+        assert_eq!(params[0]["data"], "0x1234");
+        assert_eq!(params[2][&hex::encode_prefixed(to)]["balance"], "0x0");
+    }
+}
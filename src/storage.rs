@@ -0,0 +1,123 @@
+//! Storage slot computation following the Solidity storage layout.
+//!
+//! The resulting slots are the storage keys of `eth_getStorageAt` and `eth_getProof` requests, e.g.
+//! the allowance of owner to spender of a token keeping `allowance` at slot 1 is
+//! `nested_mapping_slot(&[address_key(&owner), address_key(&spender)], U256::ONE)`.
+
+use crate::{hex, Address, U256};
+
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+
+/// A mapping key of type `address`, left padded to a 32 byte word.
+#[inline]
+pub fn address_key(address: &Address) -> [u8; 32] {
+    address.to_word()
+}
+
+/// Slot of `mapping[key]` for a mapping declared at `slot`, with a value type key encoded as a
+/// 32 byte word.
+pub fn mapping_slot(key: &[u8; 32], slot: U256) -> U256 {
+    let mut hasher = Keccak256::new();
+    hasher.update(key);
+    hasher.update(slot.0);
+    U256(hasher.finalize().into())
+}
+
+/// Slot of `mapping[key]` for a `string` or `bytes` key, which is hashed unpadded.
+pub fn bytes_mapping_slot(key: &[u8], slot: U256) -> U256 {
+    let mut hasher = Keccak256::new();
+    hasher.update(key);
+    hasher.update(slot.0);
+    U256(hasher.finalize().into())
+}
+
+/// Slot of `mapping[keys[0]][keys[1]]…` for nested mappings declared at `slot`.
+pub fn nested_mapping_slot(keys: &[[u8; 32]], slot: U256) -> U256 {
+    keys.iter().fold(slot, |slot, key| mapping_slot(key, slot))
+}
+
+/// Slot of the first word of `array[index]` for a dynamic array declared at `slot`, whose elements
+/// take `element_words` slots each. Elements smaller than a word are packed and not handled here.
+pub fn array_element_slot(slot: U256, index: u64, element_words: u64) -> U256 {
+    let start = U256(Keccak256::digest(&slot.0).into());
+    let offset = U256::from(u128::from(index) * u128::from(element_words));
+    // storage slots wrap around, like in the EVM
+    start.overflowing_add(offset).0
+}
+
+/// Slot of `balanceOf[owner]` for an ERC-20 keeping its balances at `slot`, e.g. 0 for
+/// OpenZeppelin's `ERC20`.
+#[inline]
+pub fn balance_slot(owner: &Address, slot: U256) -> U256 {
+    mapping_slot(&address_key(owner), slot)
+}
+
+/// Slot of `allowance[owner][spender]` for an ERC-20 keeping its allowances at `slot`, e.g. 1 for
+/// OpenZeppelin's `ERC20`.
+#[inline]
+pub fn allowance_slot(owner: &Address, spender: &Address, slot: U256) -> U256 {
+    nested_mapping_slot(&[address_key(owner), address_key(spender)], slot)
+}
+
+/// The params array of an `eth_getProof(address, storageKeys, block)` request, `block` being a tag
+/// like `latest` or a hex block number.
+pub fn get_proof_params(address: &Address, keys: &[U256], block: &str) -> Value {
+    let keys: Vec<String> = keys.iter().map(|key| hex::encode_prefixed(key.0)).collect();
+    json!([hex::encode_prefixed(address), keys, block])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_address;
+
+    #[test]
+    fn mapping_slot_test() {
+        // keccak256(pad(owner) ++ uint256(0))
+        let owner = parse_address("0x30E7d7FfF85C8d0E775140b1aD93C230D5595207").unwrap();
+        let mut preimage = address_key(&owner).to_vec();
+        preimage.extend_from_slice(&[0; 32]);
+        let expected = U256(Keccak256::digest(&preimage).into());
+        assert_eq!(balance_slot(&owner, U256::ZERO), expected);
+        assert_eq!(bytes_mapping_slot(&preimage[..32], U256::ZERO), expected);
+    }
+
+    #[test]
+    fn nested_mapping_slot_test() {
+        let (owner, spender) = (Address([0x11; 20]), Address([0x22; 20]));
+        let inner = mapping_slot(&address_key(&owner), U256::ONE);
+        assert_eq!(
+            allowance_slot(&owner, &spender, U256::ONE),
+            mapping_slot(&address_key(&spender), inner)
+        );
+        assert_eq!(nested_mapping_slot(&[], U256::ONE), U256::ONE);
+    }
+
+    #[test]
+    fn array_element_slot_test() {
+        // keccak256(uint256(0))
+        let start = hex::decode_to_array::<32>(
+            "290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563",
+        )
+        .unwrap();
+        assert_eq!(array_element_slot(U256::ZERO, 0, 1), U256(start));
+        assert_eq!(
+            array_element_slot(U256::ZERO, 3, 2),
+            U256(start).checked_add(U256::from(6_u64)).unwrap()
+        );
+    }
+
+    #[test]
+    fn get_proof_params_test() {
+        let params = get_proof_params(&Address([0x11; 20]), &[U256::ONE], "latest");
+        assert_eq!(
+            params,
+            json!([
+                "0x1111111111111111111111111111111111111111",
+                ["0x0000000000000000000000000000000000000000000000000000000000000001"],
+                "latest"
+            ])
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Round-trip helpers for property testing code built on top of the encoder and decoder.
+//!
+//! Downstream crates can check their own types and generated bindings against this crate's
+//! encoding by generating random values, encoding and decoding them and asserting equality.
+//!
+//! The fixture generators replace hand-typed 20 byte literals in unit tests, [`fuzz_calldata`]
+//! generates calls of every function of an ABI for fuzzing contracts or RPC middleware.
+
+use crate::abi::param_types;
+use crate::{decode, encode, Abi, Address, EthereumTypes, Function, ParamType, U256};
+
+use rand::Rng;
+use std::ops::Range;
+
+/// Bounds of generated values, e.g. the lengths a contract or middleware accepts when fuzzing it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FuzzBounds {
+    /// Upper bound of the length of `bytes` values.
+    pub max_bytes_len: usize,
+    /// Upper bound of the number of characters of `string` values, which are valid UTF-8.
+    pub max_string_chars: usize,
+    /// Upper bound of the length of dynamic arrays, nested arrays included.
+    pub max_array_len: usize,
+    /// Probability of generating an edge value instead of a uniformly random one: zero, the
+    /// minimum or maximum of integer types, or the smallest or largest length.
+    pub edge_probability: f64,
+}
+
+/// The bounds of [`random_token`], lengths of at most 64 and at most 4 array elements without
+/// edge values.
+impl Default for FuzzBounds {
+    fn default() -> Self {
+        Self {
+            max_bytes_len: 64,
+            max_string_chars: 64,
+            max_array_len: 4,
+            edge_probability: 0.0,
+        }
+    }
+}
+
+/// Generates a random value of the given type.
+///
+/// Words are uniformly random, byte strings and strings get a random length of at most 64, which
+/// covers values spanning several words, dynamic arrays at most 4 elements.
+pub fn random_token<R: Rng + ?Sized>(kind: &ParamType, rng: &mut R) -> EthereumTypes {
+    random_bounded_token(kind, &FuzzBounds::default(), rng)
+}
+
+/// Generates a random value of the given type within the bounds.
+pub fn random_bounded_token<R: Rng + ?Sized>(
+    kind: &ParamType,
+    bounds: &FuzzBounds,
+    rng: &mut R,
+) -> EthereumTypes {
+    let edge = bounds.edge_probability > 0.0 && rng.random_bool(bounds.edge_probability);
+    let len = |max: usize, rng: &mut R| match edge {
+        true if rng.random() => max,
+        true => 0,
+        false => rng.random_range(0..=max),
+    };
+    match kind {
+        ParamType::Address => random_address(rng).into(),
+        ParamType::U256 | ParamType::Uint(_) => {
+            let bits = match kind {
+                ParamType::Uint(bits) => *bits as u32,
+                _ => 256,
+            };
+            let word = match edge {
+                false => U256(rng.random()),
+                true if rng.random() => U256::ZERO,
+                true => U256::MAX,
+            };
+            (word >> (256 - bits)).into()
+        }
+        ParamType::Int(bits) => {
+            let padding = 32 - bits / 8;
+            let mut word: [u8; 32] = rng.random();
+            if edge {
+                // zero, -1, the minimum or the maximum
+                let (fill, top) = match rng.random_range(0..4) {
+                    0 => (0x00, 0x00),
+                    1 => (0xff, 0xff),
+                    2 => (0x00, 0x80),
+                    _ => (0xff, 0x7f),
+                };
+                word[padding..].fill(fill);
+                word[padding] = top;
+            }
+            // sign extend the random `bits` bits
+            let sign = if word[padding] & 0x80 == 0 { 0 } else { 0xff };
+            word[..padding].fill(sign);
+            EthereumTypes::I256(word)
+        }
+        ParamType::Bool => EthereumTypes::Bool(rng.random()),
+        ParamType::FixedBytes(size) => EthereumTypes::FixedBytes(random_bytes(*size, rng)),
+        ParamType::Bytes => {
+            let len = len(bounds.max_bytes_len, rng);
+            EthereumTypes::Bytes(random_bytes(len, rng))
+        }
+        ParamType::String => {
+            let len = len(bounds.max_string_chars, rng);
+            EthereumTypes::String((0..len).map(|_| rng.random::<char>()).collect())
+        }
+        ParamType::Array(element) => {
+            let len = len(bounds.max_array_len, rng);
+            EthereumTypes::Array(
+                (0..len)
+                    .map(|_| random_bounded_token(element, bounds, rng))
+                    .collect(),
+            )
+        }
+        ParamType::FixedArray(element, size) => EthereumTypes::FixedArray(
+            (0..*size)
+                .map(|_| random_bounded_token(element, bounds, rng))
+                .collect(),
+        ),
+        ParamType::Tuple(components) => EthereumTypes::Tuple(
+            components
+                .iter()
+                .map(|kind| random_bounded_token(kind, bounds, rng))
+                .collect(),
+        ),
+    }
+}
+
+/// Generates one random value for each type.
+pub fn random_tokens<R: Rng + ?Sized>(types: &[ParamType], rng: &mut R) -> Vec<EthereumTypes> {
+    types.iter().map(|kind| random_token(kind, rng)).collect()
+}
+
+/// Random calldata of a call of the function, type-valid arguments within the bounds.
+///
+/// Fails if one of the input types is not supported.
+pub fn random_calldata<R: Rng + ?Sized>(
+    function: &Function,
+    bounds: &FuzzBounds,
+    rng: &mut R,
+) -> Result<Vec<u8>, String> {
+    let types = param_types(&function.inputs)?;
+    let arguments: Vec<EthereumTypes> = types
+        .iter()
+        .map(|kind| random_bounded_token(kind, bounds, rng))
+        .collect();
+    let mut calldata = function.selector().0.to_vec();
+    calldata.extend_from_slice(&encode(&arguments));
+    Ok(calldata)
+}
+
+/// Random calldata for each function of the ABI, e.g. one round of fuzzing a contract.
+pub fn fuzz_calldata<'a, 'b, R: Rng + ?Sized>(
+    abi: &'a Abi<'b>,
+    bounds: &FuzzBounds,
+    rng: &mut R,
+) -> Result<Vec<(&'a Function<'b>, Vec<u8>)>, String> {
+    abi.functions
+        .iter()
+        .map(|function| Ok((function, random_calldata(function, bounds, rng)?)))
+        .collect()
+}
+
+fn random_bytes<R: Rng + ?Sized>(len: usize, rng: &mut R) -> Vec<u8> {
+    (0..len).map(|_| rng.random()).collect()
+}
+
+impl Address {
+    /// A deterministic address holding `n` in its low 8 bytes, e.g. `0x…0001` for 1.
+    pub fn from_low_u64(n: u64) -> Self {
+        let mut address = Self::ZERO;
+        address.0[12..].copy_from_slice(&n.to_be_bytes());
+        address
+    }
+
+    /// A uniformly random address from the thread local generator.
+    pub fn random() -> Self {
+        random_address(&mut rand::rng())
+    }
+}
+
+/// Generates a uniformly random address.
+pub fn random_address<R: Rng + ?Sized>(rng: &mut R) -> Address {
+    Address(rng.random())
+}
+
+/// Generates a uniformly random number in the half-open range.
+///
+/// Panics if the range is empty.
+pub fn random_u256<R: Rng + ?Sized>(range: Range<U256>, rng: &mut R) -> U256 {
+    let span = range
+        .end
+        .checked_sub(range.start)
+        .filter(|span| !span.is_zero())
+        .expect("Cannot sample from an empty range");
+    // sample below the next power of two and reject values outside of the span
+    let max = span.checked_sub(U256::ONE).unwrap();
+    let mask = U256::MAX >> max.leading_zeros();
+    loop {
+        let candidate = U256(rng.random()) & mask;
+        if candidate < span {
+            return range.start.checked_add(candidate).unwrap();
+        }
+    }
+}
+
+/// Encodes the values and decodes them as the declared `types`, returning the decoded ones.
+///
+/// Decoding as the declared types checks the ranges of `uintN` and `intN` values and the width of
+/// `bytesN` values, which the types of the values themselves don't tell.
+pub fn roundtrip(
+    types: &[ParamType],
+    tokens: &[EthereumTypes],
+) -> Result<Vec<EthereumTypes>, String> {
+    decode(types, &encode(tokens))
+}
+
+/// Panics with both values if the tokens don't survive an encode-decode round trip as the
+/// declared `types` unchanged.
+pub fn assert_roundtrip(types: &[ParamType], tokens: &[EthereumTypes]) {
+    match roundtrip(types, tokens) {
+        Ok(decoded) => assert_eq!(
+            decoded, tokens,
+            "Decoded values differ from the encoded ones"
+        ),
+        Err(e) => panic!("Couldn't decode encoded values {:?}: {}", tokens, e),
+    }
+}
+
+/// Runs [`assert_roundtrip`] on `iterations` sets of random values of the given types.
+pub fn check_random_roundtrips<R: Rng + ?Sized>(
+    types: &[ParamType],
+    rng: &mut R,
+    iterations: usize,
+) {
+    for _ in 0..iterations {
+        assert_roundtrip(types, &random_tokens(types, rng));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn fixtures_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        assert_eq!(Address::from_low_u64(0x0102).0[18..], [0x01, 0x02]);
+        assert_eq!(Address::from_low_u64(0x0102).0[..18], [0; 18]);
+        assert_ne!(random_address(&mut rng), random_address(&mut rng));
+        assert_ne!(Address::random(), Address::random());
+
+        let (low, high) = (U256::from(1000_u64), U256::from(1010_u64));
+        for _ in 0..100 {
+            let value = random_u256(low..high, &mut rng);
+            assert!(low <= value && value < high);
+        }
+        assert_eq!(
+            random_u256(low..low.checked_add(U256::ONE).unwrap(), &mut rng),
+            low
+        );
+        random_u256(U256::ZERO..U256::MAX, &mut rng);
+    }
+
+    #[test]
+    fn fuzz_calldata_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        let abi: Abi = serde_json::from_str(&json).unwrap();
+        let bounds = FuzzBounds {
+            edge_probability: 0.5,
+            ..FuzzBounds::default()
+        };
+        for _ in 0..20 {
+            let calls = fuzz_calldata(&abi, &bounds, &mut rng).unwrap();
+            assert_eq!(calls.len(), abi.functions.len());
+            for (function, calldata) in calls {
+                assert!(function.decode_input(&calldata).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn bounded_token_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let bounds = FuzzBounds {
+            max_bytes_len: 3,
+            max_string_chars: 2,
+            max_array_len: 2,
+            edge_probability: 1.0,
+        };
+        let kinds = ["uint8", "int16", "bytes", "string", "uint64[][]"];
+        for kind in kinds.iter() {
+            let kind = ParamType::from_name(kind).unwrap();
+            for _ in 0..50 {
+                let token = random_bounded_token(&kind, &bounds, &mut rng);
+                assert!(token.type_check(&kind));
+                match token {
+                    EthereumTypes::U256(word) => {
+                        assert!(word[31] == 0 || word[31] == 0xff)
+                    }
+                    EthereumTypes::Bytes(bytes) => assert!(bytes.is_empty() || bytes.len() == 3),
+                    EthereumTypes::String(s) => assert!(s.chars().count() % 2 == 0),
+                    EthereumTypes::Array(elements) => assert!(elements.len() <= 2),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn random_roundtrip_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        check_random_roundtrips(&[ParamType::Address, ParamType::U256], &mut rng, 100);
+        let types = [
+            "bytes",
+            "string",
+            "bytes7",
+            "uint40",
+            "int24",
+            "int256[]",
+            "(bool,uint256[])[2]",
+            "(address,bytes)[]",
+        ];
+        let types: Vec<ParamType> = types
+            .iter()
+            .map(|t| ParamType::from_name(t).unwrap())
+            .collect();
+        check_random_roundtrips(&types, &mut rng, 100);
+        check_random_roundtrips(&[], &mut rng, 1);
+
+        // values are decoded as the declared types, not as the types of the values
+        let uint8 = [ParamType::Uint(8)];
+        assert!(roundtrip(&uint8, &[U256::from(255_u64).into()]).is_ok());
+        assert!(roundtrip(&uint8, &[U256::from(256_u64).into()]).is_err());
+        let bytes4 = [ParamType::FixedBytes(4)];
+        assert!(roundtrip(&bytes4, &[EthereumTypes::FixedBytes(vec![1; 5])]).is_err());
+    }
+}
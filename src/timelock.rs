@@ -0,0 +1,188 @@
+use crate::selector::selector;
+use crate::{encode, Address, EthereumTypes, ProposalAction, U256};
+
+use sha3::{Digest, Keccak256};
+
+/// An operation of an OpenZeppelin `TimelockController`, scheduled first and executable once its
+/// delay passed.
+///
+/// Single calls go through `schedule` and `execute`, batches through `scheduleBatch` and
+/// `executeBatch`. Both hash their arguments differently, so a batch of one call has another id
+/// than the same call scheduled on its own.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimelockOperation {
+    pub actions: Vec<ProposalAction>,
+    /// Id of an operation that has to be executed first, zero for none.
+    pub predecessor: [u8; 32],
+    /// Distinguishes operations that are otherwise the same.
+    pub salt: [u8; 32],
+    batch: bool,
+}
+
+impl TimelockOperation {
+    /// An operation of a single call.
+    pub fn call(target: Address, value: U256, calldata: Vec<u8>) -> Self {
+        Self {
+            actions: vec![ProposalAction {
+                target,
+                value,
+                calldata,
+            }],
+            predecessor: [0; 32],
+            salt: [0; 32],
+            batch: false,
+        }
+    }
+
+    /// A batch without calls, executing the calls of its [`action`](Self::action)s in order.
+    pub fn batch() -> Self {
+        Self {
+            actions: Vec::new(),
+            predecessor: [0; 32],
+            salt: [0; 32],
+            batch: true,
+        }
+    }
+
+    /// Appends a call, turning a single call into a batch.
+    pub fn action(mut self, target: Address, value: U256, calldata: Vec<u8>) -> Self {
+        self.actions.push(ProposalAction {
+            target,
+            value,
+            calldata,
+        });
+        self.batch = true;
+        self
+    }
+
+    pub fn predecessor(mut self, predecessor: [u8; 32]) -> Self {
+        self.predecessor = predecessor;
+        self
+    }
+
+    pub fn salt(mut self, salt: [u8; 32]) -> Self {
+        self.salt = salt;
+        self
+    }
+
+    pub fn is_batch(&self) -> bool {
+        self.batch
+    }
+
+    /// The operation id, the `hashOperation` or `hashOperationBatch` of the timelock.
+    pub fn id(&self) -> [u8; 32] {
+        Keccak256::digest(&encode(&self.tokens())).into()
+    }
+
+    /// Encodes `schedule` or `scheduleBatch` calldata with a delay in seconds, at least the
+    /// minimum delay of the timelock.
+    pub fn encode_schedule(&self, delay: u64) -> Vec<u8> {
+        let mut tokens = self.tokens();
+        tokens.push(U256::from(delay).into());
+        let signature = match self.batch {
+            true => "scheduleBatch(address[],uint256[],bytes[],bytes32,bytes32,uint256)",
+            false => "schedule(address,uint256,bytes,bytes32,bytes32,uint256)",
+        };
+        call(signature, &tokens)
+    }
+
+    /// Encodes `execute` or `executeBatch` calldata, sent along with the sum of the values.
+    pub fn encode_execute(&self) -> Vec<u8> {
+        let signature = match self.batch {
+            true => "executeBatch(address[],uint256[],bytes[],bytes32,bytes32)",
+            false => "execute(address,uint256,bytes,bytes32,bytes32)",
+        };
+        call(signature, &self.tokens())
+    }
+
+    /// Encodes `cancel(bytes32)` calldata of the pending operation.
+    pub fn encode_cancel(&self) -> Vec<u8> {
+        call(
+            "cancel(bytes32)",
+            &[EthereumTypes::FixedBytes(self.id().to_vec())],
+        )
+    }
+
+    // the call or the parallel arrays of the batch, followed by the predecessor and salt
+    fn tokens(&self) -> Vec<EthereumTypes> {
+        let mut tokens = match (self.batch, self.actions.first()) {
+            (false, Some(action)) => vec![
+                action.target.into(),
+                action.value.into(),
+                EthereumTypes::Bytes(action.calldata.clone()),
+            ],
+            _ => {
+                let targets = self.actions.iter().map(|a| EthereumTypes::from(a.target));
+                let values = self.actions.iter().map(|a| EthereumTypes::from(a.value));
+                let calldatas = self
+                    .actions
+                    .iter()
+                    .map(|a| EthereumTypes::Bytes(a.calldata.clone()));
+                vec![
+                    EthereumTypes::Array(targets.collect()),
+                    EthereumTypes::Array(values.collect()),
+                    EthereumTypes::Array(calldatas.collect()),
+                ]
+            }
+        };
+        tokens.push(EthereumTypes::FixedBytes(self.predecessor.to_vec()));
+        tokens.push(EthereumTypes::FixedBytes(self.salt.to_vec()));
+        tokens
+    }
+}
+
+fn call(signature: &str, tokens: &[EthereumTypes]) -> Vec<u8> {
+    let mut data = selector(signature).0.to_vec();
+    data.extend_from_slice(&encode(tokens));
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grant_role() -> Vec<u8> {
+        vec![0x2f, 0x2f, 0xf1, 0x5d]
+    }
+
+    #[test]
+    fn single_call_test() {
+        let operation =
+            TimelockOperation::call(Address([0x11; 20]), U256::ZERO, grant_role()).salt([0x01; 32]);
+        assert!(!operation.is_batch());
+
+        let schedule = operation.encode_schedule(172_800);
+        assert_eq!(schedule[..4], [0x01, 0xd5, 0x06, 0x2a]);
+        let execute = operation.encode_execute();
+        assert_eq!(execute[..4], [0x13, 0x40, 0x08, 0xd3]);
+        // the id hashes the arguments of execute, which schedule takes along with the delay
+        let expected: [u8; 32] = Keccak256::digest(&execute[4..]).into();
+        assert_eq!(operation.id(), expected);
+        assert_eq!(schedule[4..4 + 2 * 32], execute[4..4 + 2 * 32]);
+        assert_eq!(schedule[4 + 4 * 32..4 + 5 * 32], [0x01; 32]);
+        assert_eq!(schedule[4 + 5 * 32..4 + 6 * 32], U256::from(172_800_u64).0);
+
+        let cancel = operation.encode_cancel();
+        assert_eq!(cancel[..4], [0xc4, 0xd2, 0x52, 0xf5]);
+        assert_eq!(cancel[4..], operation.id());
+    }
+
+    #[test]
+    fn batch_test() {
+        let single = TimelockOperation::call(Address([0x11; 20]), U256::ZERO, grant_role());
+        let batch =
+            TimelockOperation::batch().action(Address([0x11; 20]), U256::ZERO, grant_role());
+        assert!(batch.is_batch());
+        assert_ne!(single.id(), batch.id());
+
+        let batch = batch
+            .action(Address([0x22; 20]), U256::ONE, Vec::new())
+            .predecessor(single.id());
+        assert_eq!(batch.actions.len(), 2);
+        assert_eq!(batch.encode_schedule(0)[..4], [0x8f, 0x2a, 0x0b, 0xb0]);
+        let execute = batch.encode_execute();
+        assert_eq!(execute[..4], [0xe3, 0x83, 0x35, 0xe5]);
+        let expected: [u8; 32] = Keccak256::digest(&execute[4..]).into();
+        assert_eq!(batch.id(), expected);
+    }
+}
@@ -0,0 +1,741 @@
+use crate::types::fits_int;
+use crate::{decode, encode, selector, Address, EthereumTypes, Int, ParamType, Uint, I256, U256};
+
+use std::convert::TryFrom;
+
+/// A Rust type with a fixed ABI type, convertible to and from values of that type.
+///
+/// The derive macros map struct fields onto ABI parameters with it.
+pub trait AbiType: Sized {
+    fn param_type() -> ParamType;
+
+    fn into_token(self) -> EthereumTypes;
+
+    /// Fails if the value isn't of [`AbiType::param_type`].
+    fn from_token(token: EthereumTypes) -> Result<Self, String>;
+}
+
+// the error of a token of the wrong type
+pub(crate) fn mismatch<T: AbiType>(token: &EthereumTypes) -> String {
+    format!(
+        "Expected a value of type {}, found {}.",
+        T::param_type().name_as_str(),
+        token.name_as_str()
+    )
+}
+
+impl AbiType for Address {
+    fn param_type() -> ParamType {
+        ParamType::Address
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        self.into()
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        match token {
+            EthereumTypes::Address(address) => Ok(Address(address)),
+            token => Err(mismatch::<Self>(&token)),
+        }
+    }
+}
+
+impl AbiType for U256 {
+    fn param_type() -> ParamType {
+        ParamType::U256
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        self.into()
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        match token {
+            EthereumTypes::U256(word) => Ok(U256(word)),
+            token => Err(mismatch::<Self>(&token)),
+        }
+    }
+}
+
+impl<const BITS: usize> AbiType for Uint<BITS> {
+    fn param_type() -> ParamType {
+        // the inherent function
+        Uint::<BITS>::param_type()
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        self.into()
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        match token {
+            EthereumTypes::U256(word) => Self::new(U256(word)),
+            token => Err(mismatch::<Self>(&token)),
+        }
+    }
+}
+
+impl<const BITS: usize> AbiType for Int<BITS> {
+    fn param_type() -> ParamType {
+        // the inherent function
+        Int::<BITS>::param_type()
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        self.into()
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        match token {
+            EthereumTypes::I256(word) => Self::new(I256(word)),
+            token => Err(mismatch::<Self>(&token)),
+        }
+    }
+}
+
+impl AbiType for I256 {
+    fn param_type() -> ParamType {
+        ParamType::Int(256)
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        self.into()
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        match token {
+            EthereumTypes::I256(word) => Ok(I256(word)),
+            token => Err(mismatch::<Self>(&token)),
+        }
+    }
+}
+
+impl AbiType for bool {
+    fn param_type() -> ParamType {
+        ParamType::Bool
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        EthereumTypes::Bool(self)
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        match token {
+            EthereumTypes::Bool(value) => Ok(value),
+            token => Err(mismatch::<Self>(&token)),
+        }
+    }
+}
+
+/// `bytesN` with `1 <= N <= 32`, e.g. `bytes32` for hashes and identifiers.
+impl<const N: usize> AbiType for [u8; N] {
+    fn param_type() -> ParamType {
+        const {
+            assert!(
+                N >= 1 && N <= 32,
+                "Fixed size byte arrays have 1 to 32 bytes"
+            )
+        };
+        ParamType::FixedBytes(N)
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        EthereumTypes::FixedBytes(self.to_vec())
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        match token {
+            EthereumTypes::FixedBytes(bytes) if bytes.len() == N => {
+                let mut array = [0_u8; N];
+                array.copy_from_slice(&bytes);
+                Ok(array)
+            }
+            token => Err(mismatch::<Self>(&token)),
+        }
+    }
+}
+
+/// `bytes`
+impl AbiType for Vec<u8> {
+    fn param_type() -> ParamType {
+        ParamType::Bytes
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        EthereumTypes::Bytes(self)
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        match token {
+            EthereumTypes::Bytes(bytes) => Ok(bytes),
+            token => Err(mismatch::<Self>(&token)),
+        }
+    }
+}
+
+impl AbiType for String {
+    fn param_type() -> ParamType {
+        ParamType::String
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        EthereumTypes::String(self)
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        match token {
+            EthereumTypes::String(string) => Ok(string),
+            token => Err(mismatch::<Self>(&token)),
+        }
+    }
+}
+
+macro_rules! native_uint {
+    ($($ty:ty => $bits:expr),*) => {$(
+        impl AbiType for $ty {
+            fn param_type() -> ParamType {
+                ParamType::Uint($bits)
+            }
+
+            fn into_token(self) -> EthereumTypes {
+                U256::from(u128::from(self)).into()
+            }
+
+            fn from_token(token: EthereumTypes) -> Result<Self, String> {
+                let word = Uint::<$bits>::from_token(token)?.value().0;
+                let mut bytes = [0_u8; $bits / 8];
+                bytes.copy_from_slice(&word[32 - $bits / 8..]);
+                Ok(<$ty>::from_be_bytes(bytes))
+            }
+        }
+    )*};
+}
+
+native_uint!(u8 => 8, u16 => 16, u32 => 32, u64 => 64, u128 => 128);
+
+macro_rules! native_int {
+    ($($ty:ty => $bits:expr),*) => {$(
+        impl AbiType for $ty {
+            fn param_type() -> ParamType {
+                ParamType::Int($bits)
+            }
+
+            fn into_token(self) -> EthereumTypes {
+                I256::from(i128::from(self)).into()
+            }
+
+            fn from_token(token: EthereumTypes) -> Result<Self, String> {
+                match token {
+                    EthereumTypes::I256(word) if fits_int(&word, $bits) => {
+                        let mut bytes = [0_u8; $bits / 8];
+                        bytes.copy_from_slice(&word[32 - $bits / 8..]);
+                        Ok(<$ty>::from_be_bytes(bytes))
+                    }
+                    EthereumTypes::I256(_) => {
+                        Err(format!("Value doesn't fit into an int{}.", $bits))
+                    }
+                    token => Err(mismatch::<Self>(&token)),
+                }
+            }
+        }
+    )*};
+}
+
+native_int!(i8 => 8, i16 => 16, i32 => 32, i64 => 64, i128 => 128);
+
+/// Types that can be elements of `Vec<T>` and `[T; N]` arrays, every [`AbiType`] but `u8`.
+///
+/// Byte vectors and arrays are the `bytes` and `bytesN` types instead of `uint8` arrays.
+pub trait ArrayElement: AbiType {}
+
+macro_rules! array_element {
+    ($($ty:ty),*) => {
+        $(impl ArrayElement for $ty {})*
+    };
+}
+
+array_element!(
+    Address,
+    U256,
+    I256,
+    bool,
+    String,
+    Vec<u8>,
+    u16,
+    u32,
+    u64,
+    u128,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128
+);
+
+impl<const BITS: usize> ArrayElement for Uint<BITS> {}
+impl<const BITS: usize> ArrayElement for Int<BITS> {}
+impl<const N: usize> ArrayElement for [u8; N] {}
+impl<T: ArrayElement> ArrayElement for Vec<T> {}
+impl<T: ArrayElement, const N: usize> ArrayElement for [T; N] {}
+
+/// `T[]`
+impl<T: ArrayElement> AbiType for Vec<T> {
+    fn param_type() -> ParamType {
+        ParamType::Array(Box::new(T::param_type()))
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        EthereumTypes::Array(self.into_iter().map(T::into_token).collect())
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        match token {
+            EthereumTypes::Array(elements) => elements.into_iter().map(T::from_token).collect(),
+            token => Err(mismatch::<Self>(&token)),
+        }
+    }
+}
+
+/// `T[N]` with `N >= 1`
+impl<T: ArrayElement, const N: usize> AbiType for [T; N] {
+    fn param_type() -> ParamType {
+        const { assert!(N >= 1, "Fixed size arrays have at least one element") };
+        ParamType::FixedArray(Box::new(T::param_type()), N)
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        EthereumTypes::FixedArray(IntoIterator::into_iter(self).map(T::into_token).collect())
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        match token {
+            EthereumTypes::FixedArray(elements) if elements.len() == N => {
+                let elements: Vec<T> = elements
+                    .into_iter()
+                    .map(T::from_token)
+                    .collect::<Result<_, _>>()?;
+                // the length was checked above
+                Ok(<[T; N]>::try_from(elements).ok().unwrap())
+            }
+            token => Err(mismatch::<Self>(&token)),
+        }
+    }
+}
+
+/// A value type of a downstream crate encoded as one of the ABI types, e.g. a Solidity user
+/// defined value type like `type Price is uint128`, a domain newtype or a different U256.
+///
+/// Implementors get [`AbiType`], [`AbiEncode`] and [`AbiDecode`], so they can be arguments, return
+/// values, array elements, tuple components and fields of derived structs. The ABI type, and so
+/// the canonical type name in signatures, is always the one of the representation.
+///
+/// ```ignore
+/// struct Price(u128);
+///
+/// impl AbiValueType for Price {
+///     type Repr = u128;
+///
+///     fn into_repr(self) -> u128 {
+///         self.0
+///     }
+///
+///     fn from_repr(repr: u128) -> Result<Self, String> {
+///         Ok(Price(repr))
+///     }
+/// }
+/// ```
+pub trait AbiValueType: Sized {
+    type Repr: AbiType;
+
+    fn into_repr(self) -> Self::Repr;
+
+    /// Fails if the decoded value isn't a valid value of the type, e.g. one out of its domain.
+    fn from_repr(repr: Self::Repr) -> Result<Self, String>;
+}
+
+impl<T: AbiValueType> AbiType for T {
+    fn param_type() -> ParamType {
+        T::Repr::param_type()
+    }
+
+    fn into_token(self) -> EthereumTypes {
+        self.into_repr().into_token()
+    }
+
+    fn from_token(token: EthereumTypes) -> Result<Self, String> {
+        T::from_repr(T::Repr::from_token(token)?)
+    }
+}
+
+impl<T: AbiValueType> ArrayElement for T {}
+
+impl<T: AbiValueType> AbiEncode for T {
+    fn into_tokens(self) -> Vec<EthereumTypes> {
+        vec![self.into_token()]
+    }
+}
+
+impl<T: AbiValueType> AbiDecode for T {
+    fn param_types() -> Vec<ParamType> {
+        vec![T::param_type()]
+    }
+
+    fn from_tokens(tokens: Vec<EthereumTypes>) -> Result<Self, String> {
+        single_from_tokens(tokens)
+    }
+}
+
+/// Rust values that ABI encode as a list of parameters, e.g. the arguments of a call.
+///
+/// A single value is a list of one parameter.
+pub trait AbiEncode {
+    fn into_tokens(self) -> Vec<EthereumTypes>;
+
+    fn abi_encode(self) -> Vec<u8>
+    where
+        Self: Sized,
+    {
+        encode(&self.into_tokens())
+    }
+}
+
+/// Rust values decoded from an ABI encoded list of parameters, e.g. the return data of a call.
+///
+/// A single value is decoded from a list of one parameter.
+pub trait AbiDecode: Sized {
+    fn param_types() -> Vec<ParamType>;
+
+    /// Fails if the number or the types of the values don't match [`AbiDecode::param_types`].
+    fn from_tokens(tokens: Vec<EthereumTypes>) -> Result<Self, String>;
+
+    fn abi_decode(data: &[u8]) -> Result<Self, String> {
+        Self::from_tokens(decode(&Self::param_types(), data)?)
+    }
+}
+
+fn single_from_tokens<T: AbiType>(tokens: Vec<EthereumTypes>) -> Result<T, String> {
+    let mut tokens = tokens.into_iter();
+    match (tokens.next(), tokens.next()) {
+        (Some(token), None) => T::from_token(token),
+        _ => Err("Expected a single value.".to_owned()),
+    }
+}
+
+macro_rules! single_value {
+    ($([$($generic:tt)*] $ty:ty),* $(,)?) => {$(
+        impl<$($generic)*> AbiEncode for $ty {
+            fn into_tokens(self) -> Vec<EthereumTypes> {
+                vec![self.into_token()]
+            }
+        }
+
+        impl<$($generic)*> AbiDecode for $ty {
+            fn param_types() -> Vec<ParamType> {
+                vec![<$ty as AbiType>::param_type()]
+            }
+
+            fn from_tokens(tokens: Vec<EthereumTypes>) -> Result<Self, String> {
+                single_from_tokens(tokens)
+            }
+        }
+    )*};
+}
+
+single_value!(
+    [] Address,
+    [] U256,
+    [] I256,
+    [] bool,
+    [] String,
+    [] Vec<u8>,
+    [] u8,
+    [] u16,
+    [] u32,
+    [] u64,
+    [] u128,
+    [] i8,
+    [] i16,
+    [] i32,
+    [] i64,
+    [] i128,
+    [const BITS: usize] Uint<BITS>,
+    [const BITS: usize] Int<BITS>,
+    [const N: usize] [u8; N],
+    [T: ArrayElement] Vec<T>,
+    [T: ArrayElement, const N: usize] [T; N],
+);
+
+// tuples are ABI tuples as values and parameter lists as arguments, the empty tuple is only the
+// empty parameter list
+macro_rules! tuple {
+    ($($name:ident),+) => {
+        impl<$($name: AbiType),+> AbiType for ($($name,)+) {
+            fn param_type() -> ParamType {
+                ParamType::Tuple(vec![$($name::param_type()),+])
+            }
+
+            fn into_token(self) -> EthereumTypes {
+                EthereumTypes::Tuple(self.into_tokens())
+            }
+
+            fn from_token(token: EthereumTypes) -> Result<Self, String> {
+                match token {
+                    EthereumTypes::Tuple(components) => Self::from_tokens(components),
+                    token => Err(mismatch::<Self>(&token)),
+                }
+            }
+        }
+
+        impl<$($name: AbiType),+> ArrayElement for ($($name,)+) {}
+
+        impl<$($name: AbiType),+> AbiEncode for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn into_tokens(self) -> Vec<EthereumTypes> {
+                let ($($name,)+) = self;
+                vec![$($name.into_token()),+]
+            }
+        }
+
+        impl<$($name: AbiType),+> AbiDecode for ($($name,)+) {
+            fn param_types() -> Vec<ParamType> {
+                vec![$($name::param_type()),+]
+            }
+
+            fn from_tokens(tokens: Vec<EthereumTypes>) -> Result<Self, String> {
+                let expected = [$(stringify!($name)),+].len();
+                if tokens.len() != expected {
+                    return Err(format!("Expected {} values, found {}.", expected, tokens.len()));
+                }
+                let mut tokens = tokens.into_iter();
+                Ok(($($name::from_token(tokens.next().unwrap())?,)+))
+            }
+        }
+    };
+}
+
+tuple!(A);
+tuple!(A, B);
+tuple!(A, B, C);
+tuple!(A, B, C, D);
+tuple!(A, B, C, D, E);
+tuple!(A, B, C, D, E, F);
+tuple!(A, B, C, D, E, F, G);
+tuple!(A, B, C, D, E, F, G, H);
+tuple!(A, B, C, D, E, F, G, H, I);
+tuple!(A, B, C, D, E, F, G, H, I, J);
+tuple!(A, B, C, D, E, F, G, H, I, J, K);
+tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+impl AbiEncode for () {
+    fn into_tokens(self) -> Vec<EthereumTypes> {
+        Vec::new()
+    }
+}
+
+impl AbiDecode for () {
+    fn param_types() -> Vec<ParamType> {
+        Vec::new()
+    }
+
+    fn from_tokens(tokens: Vec<EthereumTypes>) -> Result<Self, String> {
+        match tokens.len() {
+            0 => Ok(()),
+            n => Err(format!("Expected no values, found {}.", n)),
+        }
+    }
+}
+
+/// Calldata of a call of the canonical `signature`, e.g. `transfer(address,uint256)`, with the
+/// arguments given as a tuple like `(to, amount)`.
+///
+/// Fails if the arguments don't match the declared input types.
+pub fn encode_call<A: AbiEncode>(signature: &str, arguments: A) -> Result<Vec<u8>, String> {
+    let open = signature
+        .find('(')
+        .ok_or_else(|| format!("Invalid function signature {}.", signature))?;
+    let inputs = match ParamType::from_name(&signature[open..]) {
+        Some(ParamType::Tuple(inputs)) => inputs,
+        _ => return Err(format!("Invalid function signature {}.", signature)),
+    };
+    let tokens = arguments.into_tokens();
+    if tokens.len() != inputs.len() {
+        return Err(format!(
+            "Function {} takes {} input arguments, found {}.",
+            signature,
+            inputs.len(),
+            tokens.len()
+        ));
+    }
+    for (token, kind) in tokens.iter().zip(&inputs) {
+        if !token.type_check(kind) {
+            return Err(format!(
+                "Input arguments doesn't match. Expected {}, found {}.",
+                kind.name_as_str(),
+                token.name_as_str()
+            ));
+        }
+    }
+    let mut calldata = selector(signature).0.to_vec();
+    calldata.extend_from_slice(&encode(&tokens));
+    Ok(calldata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_conversion_test() {
+        let address = Address([0x11; 20]);
+        assert_eq!(Address::from_token(address.into_token()), Ok(address));
+        assert_eq!(
+            <Uint<64>>::from_token(U256::MAX.into_token()),
+            Err("Value doesn't fit into a uint64.".to_owned())
+        );
+        assert_eq!(
+            bool::from_token(EthereumTypes::U256([0; 32])),
+            Err("Expected a value of type bool, found uint256.".to_owned())
+        );
+        assert_eq!(<[u8; 32]>::param_type().name_as_str(), "bytes32");
+        assert!(<[u8; 32]>::from_token(EthereumTypes::FixedBytes(vec![0; 4])).is_err());
+    }
+
+    #[test]
+    fn native_integer_test() {
+        assert_eq!(u8::param_type(), ParamType::Uint(8));
+        assert_eq!(u64::abi_decode(&u64::MAX.abi_encode()), Ok(u64::MAX));
+        assert_eq!(u128::abi_decode(&7_u128.abi_encode()), Ok(7));
+        assert!(u8::abi_decode(&256_u16.abi_encode()).is_err());
+        assert_eq!(i8::abi_decode(&(-128_i8).abi_encode()), Ok(-128));
+        assert_eq!(i64::abi_decode(&(-1_i64).abi_encode()), Ok(-1));
+        assert!(i8::abi_decode(&128_i16.abi_encode()).is_err());
+        assert_eq!((-2_i32).abi_encode(), I256::from(-2_i64).abi_encode());
+    }
+
+    #[test]
+    fn collection_test() {
+        assert_eq!(<Vec<u8>>::param_type(), ParamType::Bytes);
+        assert_eq!(<Vec<u16>>::param_type().name_as_str(), "uint16[]");
+        assert_eq!(<[[u8; 4]; 2]>::param_type().name_as_str(), "bytes4[2]");
+        assert_eq!(
+            <Vec<[Address; 2]>>::param_type().name_as_str(),
+            "address[2][]"
+        );
+
+        let nested = vec![vec![1_u32, 2], vec![], vec![3]];
+        assert_eq!(
+            <Vec<Vec<u32>>>::abi_decode(&nested.clone().abi_encode()),
+            Ok(nested)
+        );
+        let strings = [String::from("a"), String::from("bc")];
+        assert_eq!(
+            <[String; 2]>::abi_decode(&strings.clone().abi_encode()),
+            Ok(strings)
+        );
+        let blobs = vec![vec![0xab; 40], vec![]];
+        assert_eq!(
+            <Vec<Vec<u8>>>::abi_decode(&blobs.clone().abi_encode()),
+            Ok(blobs)
+        );
+
+        // a value is a list of one parameter
+        assert_eq!(true.into_tokens(), vec![EthereumTypes::Bool(true)]);
+        assert!(bool::from_tokens(vec![]).is_err());
+        assert!(<[u8; 2]>::abi_decode(&[0x12; 32]).is_err());
+    }
+
+    #[test]
+    fn tuple_test() {
+        assert_eq!(
+            <(Address, Vec<(u8, String)>)>::param_type().name_as_str(),
+            "(address,(uint8,string)[])"
+        );
+        let value = (
+            Address([0x11; 20]),
+            -5_i16,
+            vec![true, false],
+            String::from("x"),
+        );
+        assert_eq!(
+            <(Address, i16, Vec<bool>, String)>::abi_decode(&value.clone().abi_encode()),
+            Ok(value.clone())
+        );
+        // a tuple as a value is a single parameter
+        let nested = (value.clone(),);
+        assert_eq!(nested.clone().into_tokens().len(), 1);
+        assert_eq!(
+            <((Address, i16, Vec<bool>, String),)>::abi_decode(&nested.abi_encode()),
+            Ok((value,))
+        );
+        assert!(<(bool, bool)>::from_tokens(vec![EthereumTypes::Bool(true)]).is_err());
+        assert_eq!(<()>::abi_decode(&[]), Ok(()));
+    }
+
+    #[test]
+    fn encode_call_test() {
+        let calldata = encode_call(
+            "transfer(address,uint256)",
+            (Address([0x22; 20]), U256::from(1000_u64)),
+        )
+        .unwrap();
+        assert_eq!(&calldata[..4], [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(
+            calldata[4..],
+            encode(&[Address([0x22; 20]).into(), U256::from(1000_u64).into()])[..]
+        );
+        assert_eq!(encode_call("pause()", ()).unwrap(), selector("pause()").0);
+        assert!(encode_call(
+            "permit(address,uint8,(uint256,bytes))",
+            (Address::ZERO, 27_u8, (U256::ONE, vec![0xab_u8]))
+        )
+        .is_ok());
+
+        assert!(encode_call("transfer(address,uint256)", (Address::ZERO,)).is_err());
+        assert!(encode_call("transfer(address,uint256)", (Address::ZERO, true)).is_err());
+        // the value doesn't fit into the declared width
+        assert!(encode_call("approve(uint8)", (U256::from(256_u64),)).is_err());
+        assert!(encode_call("transfer", ()).is_err());
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Price(u128);
+
+    impl AbiValueType for Price {
+        type Repr = u128;
+
+        fn into_repr(self) -> u128 {
+            self.0
+        }
+
+        fn from_repr(repr: u128) -> Result<Self, String> {
+            match repr {
+                0 => Err("A price is never zero.".to_owned()),
+                repr => Ok(Price(repr)),
+            }
+        }
+    }
+
+    #[test]
+    fn value_type_test() {
+        assert_eq!(Price::param_type().name_as_str(), "uint128");
+        assert_eq!(Price(7).abi_encode(), 7_u128.abi_encode());
+        assert_eq!(Price::abi_decode(&7_u128.abi_encode()), Ok(Price(7)));
+        assert_eq!(
+            Price::abi_decode(&0_u128.abi_encode()),
+            Err("A price is never zero.".to_owned())
+        );
+
+        // in arrays and tuples like any other type
+        assert_eq!(<Vec<Price>>::param_type().name_as_str(), "uint128[]");
+        let data = (Address::ZERO, vec![Price(1), Price(2)]).abi_encode();
+        assert_eq!(
+            <(Address, Vec<Price>)>::abi_decode(&data),
+            Ok((Address::ZERO, vec![Price(1), Price(2)]))
+        );
+        assert_eq!(
+            encode_call("quote(uint128)", (Price(5),)),
+            encode_call("quote(uint128)", (5_u128,))
+        );
+    }
+}
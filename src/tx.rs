@@ -0,0 +1,257 @@
+use crate::{hex, parse_address, rlp, AccessList, Address, ChainId, Signature, U256};
+
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+
+// the EIP-2718 type of EIP-1559 transactions
+const EIP1559_TYPE: u8 = 0x02;
+
+/// An unsigned EIP-1559 transaction, for signing setups where the key never sees the network.
+///
+/// The online side exports the transaction with [`to_json`](Self::to_json), the signer checks it
+/// and signs its [`signing_hash`](Self::signing_hash), e.g. on an air-gapped machine or an HSM,
+/// and the signature is combined into the raw transaction with
+/// [`encode_signed`](Self::encode_signed), ready for `eth_sendRawTransaction`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsignedTransaction {
+    pub chain_id: ChainId,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: u64,
+    /// The called account, `None` to deploy a contract.
+    pub to: Option<Address>,
+    pub value: U256,
+    /// Calldata, or the creation code of a deployment.
+    pub data: Vec<u8>,
+    pub access_list: AccessList,
+}
+
+impl UnsignedTransaction {
+    /// The typed transaction envelope signed over, `0x02 ++ rlp(fields)`.
+    pub fn encode_unsigned(&self) -> Vec<u8> {
+        self.envelope(&self.fields())
+    }
+
+    /// The hash the sender signs, the keccak hash of the unsigned envelope.
+    pub fn signing_hash(&self) -> [u8; 32] {
+        Keccak256::digest(&self.encode_unsigned()).into()
+    }
+
+    /// The raw signed transaction, `0x02 ++ rlp(fields ++ [yParity, r, s])`.
+    ///
+    /// Signatures with a high `s`, which some HSMs produce, are normalized as nodes only accept
+    /// low `s` values.
+    pub fn encode_signed(&self, signature: &Signature) -> Result<Vec<u8>, String> {
+        if !signature.is_valid() {
+            return Err("Invalid signature, r and s have to be in [1, n - 1].".to_owned());
+        }
+        let signature = signature.normalize_s();
+        let mut fields = self.fields();
+        fields.extend(rlp::uint(&[u8::from(signature.y_parity)]));
+        fields.extend(rlp::uint(&signature.r.0));
+        fields.extend(rlp::uint(&signature.s.0));
+        Ok(self.envelope(&fields))
+    }
+
+    /// The hash of the signed transaction, its id once it is sent.
+    pub fn transaction_hash(&self, signature: &Signature) -> Result<[u8; 32], String> {
+        Ok(Keccak256::digest(&self.encode_signed(signature)?).into())
+    }
+
+    /// The transaction in the field names of JSON-RPC, along with the unsigned envelope and its
+    /// signing hash for the signer to check.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "type": format!("{:#x}", EIP1559_TYPE),
+            "chainId": format!("{:#x}", self.chain_id.0),
+            "nonce": format!("{:#x}", self.nonce),
+            "maxPriorityFeePerGas": format!("{:#x}", self.max_priority_fee_per_gas),
+            "maxFeePerGas": format!("{:#x}", self.max_fee_per_gas),
+            "gas": format!("{:#x}", self.gas_limit),
+            "to": self.to.map(hex::encode_prefixed),
+            "value": format!("{:#x}", self.value),
+            "input": hex::encode_prefixed(&self.data),
+            "accessList": self.access_list.to_json(),
+            "unsigned": hex::encode_prefixed(self.encode_unsigned()),
+            "signingHash": hex::encode_prefixed(self.signing_hash()),
+        })
+    }
+
+    /// Parses the output of [`to_json`](Self::to_json).
+    ///
+    /// Fails if the exported `signingHash` doesn't match the fields, so a signer never signs
+    /// something else than what it displays.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let string = |field: &str| {
+            value
+                .get(field)
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("Transaction without {}.", field))
+        };
+        let kind = quantity_u64(string("type")?)?;
+        if kind != u64::from(EIP1559_TYPE) {
+            return Err(format!(
+                "Unsupported transaction type {:#x}, only EIP-1559 transactions are.",
+                kind
+            ));
+        }
+        let to = match value.get("to") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(to)) => Some(parse_address(to)?),
+            Some(to) => return Err(format!("Invalid recipient {}.", to)),
+        };
+        let transaction = Self {
+            chain_id: ChainId(quantity_u64(string("chainId")?)?),
+            nonce: quantity_u64(string("nonce")?)?,
+            max_priority_fee_per_gas: quantity(string("maxPriorityFeePerGas")?)?,
+            max_fee_per_gas: quantity(string("maxFeePerGas")?)?,
+            gas_limit: quantity_u64(string("gas")?)?,
+            to,
+            value: quantity(string("value")?)?,
+            data: hex::decode(string("input")?).map_err(|e| format!("Invalid input: {}", e))?,
+            access_list: match value.get("accessList") {
+                Some(access_list) => AccessList::from_json(access_list)?,
+                None => AccessList::new(),
+            },
+        };
+        if let Ok(signing_hash) = string("signingHash") {
+            if signing_hash != hex::encode_prefixed(transaction.signing_hash()) {
+                return Err(format!(
+                    "The signing hash {} doesn't match the transaction.",
+                    signing_hash
+                ));
+            }
+        }
+        Ok(transaction)
+    }
+
+    fn fields(&self) -> Vec<u8> {
+        let mut fields = rlp::uint(&self.chain_id.0.to_be_bytes());
+        fields.extend(rlp::uint(&self.nonce.to_be_bytes()));
+        fields.extend(rlp::uint(&self.max_priority_fee_per_gas.0));
+        fields.extend(rlp::uint(&self.max_fee_per_gas.0));
+        fields.extend(rlp::uint(&self.gas_limit.to_be_bytes()));
+        match &self.to {
+            Some(to) => fields.extend(rlp::string(to.as_bytes())),
+            None => fields.extend(rlp::string(&[])),
+        }
+        fields.extend(rlp::uint(&self.value.0));
+        fields.extend(rlp::string(&self.data));
+        fields.extend(self.access_list.rlp_encode());
+        fields
+    }
+
+    fn envelope(&self, fields: &[u8]) -> Vec<u8> {
+        let mut envelope = vec![EIP1559_TYPE];
+        envelope.extend(rlp::list(fields));
+        envelope
+    }
+}
+
+// a JSON-RPC quantity, `0x` prefixed hex without leading zeros
+fn quantity(s: &str) -> Result<U256, String> {
+    let digits = s
+        .strip_prefix("0x")
+        .filter(|digits| !digits.is_empty() && digits.len() <= 64)
+        .ok_or_else(|| format!("Invalid quantity {}.", s))?;
+    let padded = format!("{:0>64}", digits);
+    hex::decode_to_array(&padded)
+        .map(U256)
+        .map_err(|e| format!("Invalid quantity {}: {}", s, e))
+}
+
+fn quantity_u64(s: &str) -> Result<u64, String> {
+    let value = quantity(s)?;
+    if value.leading_zeros() < 192 {
+        return Err(format!("Quantity {} doesn't fit into 64 bits.", s));
+    }
+    let mut bytes = [0_u8; 8];
+    bytes.copy_from_slice(&value.0[24..]);
+    Ok(u64::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature::SECP256K1_N;
+
+    fn transfer() -> UnsignedTransaction {
+        UnsignedTransaction {
+            chain_id: ChainId::MAINNET,
+            nonce: 0,
+            max_priority_fee_per_gas: U256::ZERO,
+            max_fee_per_gas: U256::ZERO,
+            gas_limit: 21_000,
+            to: Some(Address([0x11; 20])),
+            value: U256::ONE,
+            data: Vec::new(),
+            access_list: AccessList::new(),
+        }
+    }
+
+    #[test]
+    fn encode_test() {
+        let unsigned = transfer().encode_unsigned();
+        let mut expected = vec![0x02, 0xdf, 0x01, 0x80, 0x80, 0x80, 0x82, 0x52, 0x08, 0x94];
+        expected.extend_from_slice(&[0x11; 20]);
+        expected.extend_from_slice(&[0x01, 0x80, 0xc0]);
+        assert_eq!(unsigned, expected);
+        let hash: [u8; 32] = Keccak256::digest(&unsigned).into();
+        assert_eq!(transfer().signing_hash(), hash);
+
+        let signature = Signature {
+            r: U256::ONE,
+            s: U256::from(2_u64),
+            y_parity: true,
+        };
+        let signed = transfer().encode_signed(&signature).unwrap();
+        assert_eq!(signed[..2], [0x02, 0xe2]);
+        assert_eq!(signed[2..33], unsigned[2..]);
+        assert_eq!(signed[33..], [0x01, 0x01, 0x02]);
+
+        // high s values are normalized, invalid signatures rejected
+        let high = Signature {
+            s: SECP256K1_N.checked_sub(U256::from(2_u64)).unwrap(),
+            y_parity: false,
+            ..signature
+        };
+        assert_eq!(transfer().encode_signed(&high), Ok(signed));
+        let zero = Signature {
+            r: U256::ZERO,
+            ..signature
+        };
+        assert!(transfer().encode_signed(&zero).is_err());
+    }
+
+    #[test]
+    fn json_test() {
+        let transaction = UnsignedTransaction {
+            to: None,
+            data: vec![0x60, 0x80],
+            max_fee_per_gas: U256::from(30_000_000_000_u64),
+            access_list: AccessList::new().slot(Address([0xaa; 20]), U256::ONE),
+            ..transfer()
+        };
+        let json = transaction.to_json();
+        assert_eq!(json["type"], "0x2");
+        assert_eq!(json["gas"], "0x5208");
+        assert_eq!(json["maxFeePerGas"], "0x6fc23ac00");
+        assert_eq!(json["to"], Value::Null);
+        assert_eq!(
+            json["signingHash"],
+            hex::encode_prefixed(transaction.signing_hash())
+        );
+        assert_eq!(UnsignedTransaction::from_json(&json), Ok(transaction));
+
+        // a signer must not sign fields that differ from the exported hash
+        let mut tampered = json.clone();
+        tampered["value"] = json!("0xde0b6b3a7640000");
+        assert!(UnsignedTransaction::from_json(&tampered)
+            .unwrap_err()
+            .contains("doesn't match"));
+        let mut legacy = json;
+        legacy["type"] = json!("0x0");
+        assert!(UnsignedTransaction::from_json(&legacy).is_err());
+    }
+}
@@ -0,0 +1,433 @@
+use crate::eip712::{hash_words, keccak256};
+use crate::{
+    encode_member, hash_struct, hex, parse_address, type_hash, typed_data_hash, ChainId,
+    Eip712Domain, EthereumTypes, ParamType, I256, U256,
+};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+/// A member of a struct type of [`TypedData`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct TypedDataField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// An `eth_signTypedData_v4` payload, parsed from the json wallets receive.
+///
+/// ```ignore
+/// let typed_data: TypedData = payload.parse()?;
+/// let signer = signature.recover(&typed_data.signing_hash()?)?;
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedData {
+    /// The struct types by name, `EIP712Domain` included.
+    pub types: BTreeMap<String, Vec<TypedDataField>>,
+    pub primary_type: String,
+    pub domain: Eip712Domain,
+    pub message: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Payload {
+    types: BTreeMap<String, Vec<TypedDataField>>,
+    primary_type: String,
+    #[serde(default)]
+    domain: serde_json::Map<String, Value>,
+    #[serde(default)]
+    message: Value,
+}
+
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+impl TypedData {
+    /// Parses an already deserialized payload, e.g. the params of a JSON-RPC request.
+    pub fn from_json(payload: Value) -> Result<Self, String> {
+        let payload: Payload =
+            serde_json::from_value(payload).map_err(|e| format!("Invalid typed data: {}", e))?;
+        let domain = parse_domain(&payload.domain)?;
+
+        // the domain separator hashes the fields in their canonical order
+        if let Some(fields) = payload.types.get(DOMAIN_TYPE) {
+            let declared: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+            let present: Vec<&str> = ["name", "version", "chainId", "verifyingContract", "salt"]
+                .iter()
+                .copied()
+                .filter(|field| payload.domain.contains_key(*field))
+                .collect();
+            if declared != present {
+                return Err(format!(
+                    "The {} type declares {:?}, the domain has {:?}.",
+                    DOMAIN_TYPE, declared, present
+                ));
+            }
+        }
+        if payload.primary_type != DOMAIN_TYPE && !payload.types.contains_key(&payload.primary_type)
+        {
+            return Err(format!("Unknown primary type {}.", payload.primary_type));
+        }
+        Ok(Self {
+            types: payload.types,
+            primary_type: payload.primary_type,
+            domain,
+            message: payload.message,
+        })
+    }
+
+    /// The encoded type, e.g. `Mail(Person from,Person to,string contents)Person(string name,address wallet)`,
+    /// the type itself followed by the struct types it references in alphabetical order.
+    pub fn encode_type(&self, name: &str) -> Result<String, String> {
+        let mut dependencies = BTreeSet::new();
+        self.dependencies(name, &mut dependencies)?;
+        dependencies.remove(name);
+        let mut encoded = String::new();
+        for name in std::iter::once(name).chain(dependencies.iter().map(String::as_str)) {
+            let members: Vec<String> = self.types[name]
+                .iter()
+                .map(|field| format!("{} {}", field.kind, field.name))
+                .collect();
+            encoded.push_str(&format!("{}({})", name, members.join(",")));
+        }
+        Ok(encoded)
+    }
+
+    fn dependencies(&self, name: &str, found: &mut BTreeSet<String>) -> Result<(), String> {
+        let fields = self
+            .types
+            .get(name)
+            .ok_or_else(|| format!("Unknown struct type {}.", name))?;
+        if !found.insert(name.to_owned()) {
+            return Ok(());
+        }
+        for field in fields {
+            let base = base_type(&field.kind);
+            if self.types.contains_key(base) {
+                self.dependencies(base, found)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The `hashStruct` of a value of one of the struct types.
+    pub fn struct_hash(&self, name: &str, value: &Value) -> Result<[u8; 32], String> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| format!("A {} value is a json object.", name))?;
+        let type_hash = type_hash(&self.encode_type(name)?);
+        let members = self.types[name]
+            .iter()
+            .map(|field| {
+                let value = object
+                    .get(&field.name)
+                    .ok_or_else(|| format!("Missing field {}.{}.", name, field.name))?;
+                self.encode_value(&field.kind, value)
+                    .map_err(|e| format!("{}.{}: {}", name, field.name, e))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(hash_struct(&type_hash, &members))
+    }
+
+    /// The `hashStruct` of the message.
+    pub fn message_hash(&self) -> Result<[u8; 32], String> {
+        self.struct_hash(&self.primary_type, &self.message)
+    }
+
+    /// The hash the wallet signs.
+    ///
+    /// A payload signing the domain itself, with `EIP712Domain` as its primary type, hashes only
+    /// the domain separator like MetaMask does.
+    pub fn signing_hash(&self) -> Result<[u8; 32], String> {
+        let separator = self.domain.separator();
+        if self.primary_type == DOMAIN_TYPE {
+            let mut data = vec![0x19, 0x01];
+            data.extend_from_slice(&separator);
+            return Ok(keccak256(&data));
+        }
+        Ok(typed_data_hash(&separator, &self.message_hash()?))
+    }
+
+    // the word a member contributes to `encodeData`
+    fn encode_value(&self, kind: &str, value: &Value) -> Result<[u8; 32], String> {
+        if let Some(rest) = kind.strip_suffix(']') {
+            let open = rest
+                .rfind('[')
+                .ok_or_else(|| format!("Invalid type {}.", kind))?;
+            let elements = value
+                .as_array()
+                .ok_or_else(|| format!("A {} value is a json array.", kind))?;
+            let size = &rest[open + 1..];
+            if !size.is_empty() && size.parse() != Ok(elements.len()) {
+                return Err(format!(
+                    "Expected {} elements, found {}.",
+                    size,
+                    elements.len()
+                ));
+            }
+            let words = elements
+                .iter()
+                .map(|element| self.encode_value(&rest[..open], element))
+                .collect::<Result<Vec<_>, String>>()?;
+            return Ok(hash_words(&words));
+        }
+        if self.types.contains_key(kind) {
+            return self.struct_hash(kind, value);
+        }
+        Ok(encode_member(&atomic_value(kind, value)?))
+    }
+}
+
+impl FromStr for TypedData {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let payload = serde_json::from_str(s).map_err(|e| format!("Invalid typed data: {}", e))?;
+        Self::from_json(payload)
+    }
+}
+
+// the struct or elementary type of possibly nested array types
+fn base_type(kind: &str) -> &str {
+    kind.find('[').map_or(kind, |open| &kind[..open])
+}
+
+fn parse_domain(domain: &serde_json::Map<String, Value>) -> Result<Eip712Domain, String> {
+    let string = |field: &str| -> Result<Option<String>, String> {
+        domain
+            .get(field)
+            .map(|value| match value {
+                Value::String(s) => Ok(s.clone()),
+                _ => Err(format!("The domain {} is a string.", field)),
+            })
+            .transpose()
+    };
+    let chain_id = domain
+        .get("chainId")
+        .map(|value| {
+            let id = parse_uint(value)?;
+            if id.leading_zeros() < 192 {
+                return Err(format!("Chain id {} doesn't fit into 64 bits.", value));
+            }
+            let mut bytes = [0_u8; 8];
+            bytes.copy_from_slice(&id.0[24..]);
+            Ok(ChainId::from(u64::from_be_bytes(bytes)))
+        })
+        .transpose()?;
+    let verifying_contract = domain
+        .get("verifyingContract")
+        .map(|value| match value {
+            Value::String(s) => parse_address(s),
+            _ => Err("The domain verifyingContract is an address string.".to_owned()),
+        })
+        .transpose()?;
+    let salt = domain
+        .get("salt")
+        .map(|value| match value {
+            Value::String(s) => hex::decode_to_array(s).map_err(|e| e.to_string()),
+            _ => Err("The domain salt is a hex string.".to_owned()),
+        })
+        .transpose()?;
+    Ok(Eip712Domain {
+        name: string("name")?,
+        version: string("version")?,
+        chain_id,
+        verifying_contract,
+        salt,
+    })
+}
+
+// numbers as json numbers, decimal strings or 0x prefixed hex strings
+fn parse_uint(value: &Value) -> Result<U256, String> {
+    match value {
+        Value::Number(n) => n
+            .as_u64()
+            .map(U256::from)
+            .ok_or_else(|| format!("Expected an unsigned integer, found {}.", n)),
+        Value::String(s) if s.starts_with("0x") => {
+            let bytes = hex::decode(s).map_err(|e| e.to_string())?;
+            if bytes.len() > 32 {
+                return Err(format!("Number {} doesn't fit into 256 bits.", s));
+            }
+            let mut word = [0_u8; 32];
+            word[32 - bytes.len()..].copy_from_slice(&bytes);
+            Ok(U256(word))
+        }
+        Value::String(s) => s.parse(),
+        value => Err(format!("Expected a number, found {}.", value)),
+    }
+}
+
+fn parse_int(value: &Value) -> Result<I256, String> {
+    match value {
+        Value::Number(n) => n
+            .as_i64()
+            .map(I256::from)
+            .ok_or_else(|| format!("Expected an integer, found {}.", n)),
+        // hex strings are the two's complement word
+        Value::String(s) if s.starts_with("0x") => parse_uint(value).map(|word| I256(word.0)),
+        Value::String(s) => s.parse(),
+        value => Err(format!("Expected a number, found {}.", value)),
+    }
+}
+
+fn atomic_value(kind: &str, value: &Value) -> Result<EthereumTypes, String> {
+    let param = ParamType::from_name(kind).ok_or_else(|| format!("Unknown type {}.", kind))?;
+    let string = || {
+        value
+            .as_str()
+            .ok_or_else(|| format!("Expected a string, found {}.", value))
+    };
+    let token = match param {
+        ParamType::Address => parse_address(string()?)?.into(),
+        ParamType::Bool => EthereumTypes::Bool(
+            value
+                .as_bool()
+                .ok_or_else(|| format!("Expected a boolean, found {}.", value))?,
+        ),
+        ParamType::U256 | ParamType::Uint(_) => parse_uint(value)?.into(),
+        ParamType::Int(_) => parse_int(value)?.into(),
+        ParamType::FixedBytes(_) => EthereumTypes::FixedBytes(
+            hex::decode_whole_bytes(string()?).map_err(|e| e.to_string())?,
+        ),
+        ParamType::Bytes => {
+            EthereumTypes::Bytes(hex::decode(string()?).map_err(|e| e.to_string())?)
+        }
+        ParamType::String => EthereumTypes::String(string()?.to_owned()),
+        _ => return Err(format!("Unknown struct type {}.", kind)),
+    };
+    if !token.type_check(&param) {
+        return Err(format!("Value {} is out of range of {}.", value, kind));
+    }
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    // the `Mail` example of EIP-712 as sent by dapps
+    fn mail() -> Value {
+        json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "Person": [
+                    {"name": "name", "type": "string"},
+                    {"name": "wallet", "type": "address"}
+                ],
+                "Mail": [
+                    {"name": "from", "type": "Person"},
+                    {"name": "to", "type": "Person"},
+                    {"name": "contents", "type": "string"}
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+                "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+                "contents": "Hello, Bob!"
+            }
+        })
+    }
+
+    #[test]
+    fn mail_test() {
+        let typed_data: TypedData = mail().to_string().parse().unwrap();
+        assert_eq!(typed_data.domain.chain_id, Some(ChainId::MAINNET));
+        assert_eq!(
+            typed_data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            hex::encode(typed_data.message_hash().unwrap()),
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+        );
+        assert_eq!(
+            hex::encode(typed_data.signing_hash().unwrap()),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+
+    #[test]
+    fn values_test() {
+        let mut payload = mail();
+        payload["types"]["Mail"] = json!([
+            {"name": "to", "type": "Person[]"},
+            {"name": "amount", "type": "uint8"},
+            {"name": "delta", "type": "int256"},
+            {"name": "tag", "type": "bytes4"}
+        ]);
+        payload["message"] = json!({
+            "to": [{"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"}],
+            "amount": "0xff",
+            "delta": "-1",
+            "tag": "0x12345678"
+        });
+        let typed_data = TypedData::from_json(payload.clone()).unwrap();
+        let person = typed_data
+            .struct_hash("Person", &payload["message"]["to"][0])
+            .unwrap();
+        let expected = hash_struct(
+            &type_hash(&typed_data.encode_type("Mail").unwrap()),
+            &[
+                hash_words(&[person]),
+                U256::from(255_u64).0,
+                [0xff; 32],
+                encode_member(&EthereumTypes::FixedBytes(vec![0x12, 0x34, 0x56, 0x78])),
+            ],
+        );
+        assert_eq!(typed_data.message_hash(), Ok(expected));
+
+        payload["message"]["amount"] = json!(256);
+        let typed_data = TypedData::from_json(payload.clone()).unwrap();
+        assert_eq!(
+            typed_data.message_hash(),
+            Err("Mail.amount: Value 256 is out of range of uint8.".to_owned())
+        );
+        payload["message"]["amount"] = json!(255);
+        payload["message"]["tag"] = json!("0x1234567");
+        let typed_data = TypedData::from_json(payload.clone()).unwrap();
+        assert!(typed_data.message_hash().is_err());
+        payload["message"].as_object_mut().unwrap().remove("tag");
+        let typed_data = TypedData::from_json(payload).unwrap();
+        assert_eq!(
+            typed_data.message_hash(),
+            Err("Missing field Mail.tag.".to_owned())
+        );
+    }
+
+    #[test]
+    fn invalid_payload_test() {
+        let mut payload = mail();
+        payload["primaryType"] = json!("Letter");
+        assert!(TypedData::from_json(payload).is_err());
+
+        // the declared domain fields have to match the domain
+        let mut payload = mail();
+        payload["domain"]["salt"] = json!(hex::encode_prefixed([1; 32]));
+        assert!(TypedData::from_json(payload).is_err());
+
+        let mut payload = mail();
+        payload["domain"]["chainId"] = json!("0x2105");
+        let typed_data = TypedData::from_json(payload).unwrap();
+        assert_eq!(typed_data.domain.chain_id, Some(ChainId::BASE));
+        assert!("[]".parse::<TypedData>().is_err());
+    }
+}
@@ -1,32 +1,278 @@
+use crate::U256;
+
+use std::borrow::Cow;
+
 /// Some Ethereum types represented as an array of bytes.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum EthereumTypes {
     /// U160 - unsigned 160 bit number
     Address([u8; 20]),
     /// U256 - unsigned 256 bit number
     U256([u8; 32]),
+    /// I256 - signed 256 bit number in two's complement
+    I256([u8; 32]),
+    Bool(bool),
+    /// `bytesN`, holding the N bytes
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+    /// `T[]`, all elements have to be of the same type
+    Array(Vec<EthereumTypes>),
+    /// `T[N]`, all elements have to be of the same type
+    FixedArray(Vec<EthereumTypes>),
+    /// `(T1,T2,...)`, i.e. a struct
+    Tuple(Vec<EthereumTypes>),
+}
+
+/// The type of an [`EthereumTypes`] value, without the value itself.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ParamType {
+    Address,
+    U256,
+    /// `uintN` with `8 <= N < 256` and `N % 8 == 0`, values are U256 words that fit into N bits
+    Uint(usize),
+    /// `intN` with `8 <= N <= 256` and `N % 8 == 0`, values are I256 words that fit into N bits
+    Int(usize),
+    Bool,
+    /// `bytesN` with `1 <= N <= 32`
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(Box<ParamType>),
+    FixedArray(Box<ParamType>, usize),
+    Tuple(Vec<ParamType>),
+}
+
+impl ParamType {
+    /// Parses a canonical ABI type name, e.g. `address`, `bytes32[]` or `(address,uint256)[2]`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(rest) = name.strip_suffix(']') {
+            let open = rest.rfind('[')?;
+            let element = Box::new(Self::from_name(&rest[..open])?);
+            return match &rest[open + 1..] {
+                "" => Some(Self::Array(element)),
+                size => match size.parse() {
+                    // the size of static arrays has to be addressable
+                    Ok(size) if size > 0 => Some(Self::FixedArray(element, size))
+                        .filter(|kind| kind.head_size().is_some()),
+                    _ => None,
+                },
+            };
+        }
+        if let Some(components) = name.strip_prefix('(') {
+            return split_components(components.strip_suffix(')')?)?
+                .into_iter()
+                .map(Self::from_name)
+                .collect::<Option<Vec<_>>>()
+                .map(Self::Tuple)
+                .filter(|kind| kind.head_size().is_some());
+        }
+        match name {
+            "address" => Some(Self::Address),
+            "uint256" => Some(Self::U256),
+            "bool" => Some(Self::Bool),
+            "bytes" => Some(Self::Bytes),
+            "string" => Some(Self::String),
+            _ => {
+                if let Some(bits) = name.strip_prefix("uint") {
+                    return match bits.parse::<usize>() {
+                        Ok(bits) if (8..256).contains(&bits) && bits.is_multiple_of(8) => {
+                            Some(Self::Uint(bits))
+                        }
+                        _ => None,
+                    };
+                }
+                if let Some(bits) = name.strip_prefix("int") {
+                    return match bits.parse::<usize>() {
+                        Ok(bits) if (8..=256).contains(&bits) && bits.is_multiple_of(8) => {
+                            Some(Self::Int(bits))
+                        }
+                        _ => None,
+                    };
+                }
+                match name.strip_prefix("bytes")?.parse() {
+                    Ok(size) if (1..=32).contains(&size) => Some(Self::FixedBytes(size)),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// The canonical ABI type name, e.g. `address` or `(address,uint256)[]`.
+    pub fn name_as_str(&self) -> Cow<'static, str> {
+        match self {
+            Self::Address => Cow::Borrowed("address"),
+            Self::U256 => Cow::Borrowed("uint256"),
+            Self::Uint(bits) => Cow::Owned(format!("uint{}", bits)),
+            Self::Int(bits) => Cow::Owned(format!("int{}", bits)),
+            Self::Bool => Cow::Borrowed("bool"),
+            Self::FixedBytes(size) => Cow::Owned(format!("bytes{}", size)),
+            Self::Bytes => Cow::Borrowed("bytes"),
+            Self::String => Cow::Borrowed("string"),
+            Self::Array(element) => Cow::Owned(format!("{}[]", element.name_as_str())),
+            Self::FixedArray(element, size) => {
+                Cow::Owned(format!("{}[{}]", element.name_as_str(), size))
+            }
+            Self::Tuple(components) => {
+                let names: Vec<Cow<str>> = components.iter().map(Self::name_as_str).collect();
+                Cow::Owned(format!("({})", names.join(",")))
+            }
+        }
+    }
+
+    /// Whether values of the type are encoded in the tail section, behind an offset.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            Self::Bytes | Self::String | Self::Array(_) => true,
+            Self::FixedArray(element, _) => element.is_dynamic(),
+            Self::Tuple(components) => components.iter().any(Self::is_dynamic),
+            _ => false,
+        }
+    }
+
+    // size of the type in the head section of an encoding, none if it overflows
+    pub(crate) fn head_size(&self) -> Option<usize> {
+        match self {
+            kind if kind.is_dynamic() => Some(32),
+            Self::FixedArray(element, size) => element.head_size()?.checked_mul(*size),
+            Self::Tuple(components) => components.iter().try_fold(0_usize, |sum, component| {
+                sum.checked_add(component.head_size()?)
+            }),
+            _ => Some(32),
+        }
+    }
+}
+
+// splits tuple components at the top level commas, e.g. `address,(uint256,bool)[]`
+pub(crate) fn split_components(components: &str) -> Option<Vec<&str>> {
+    if components.is_empty() {
+        return Some(Vec::new());
+    }
+    let mut parts = Vec::new();
+    let mut depth = 0_usize;
+    let mut start = 0;
+    for (i, c) in components.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                parts.push(&components[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&components[start..]);
+    Some(parts)
+}
+
+// whether the two's complement word is the sign extension of an `intN` value
+pub(crate) fn fits_int(word: &[u8; 32], bits: usize) -> bool {
+    let padding = 32 - bits / 8;
+    let sign = if word[padding] & 0x80 == 0 { 0 } else { 0xff };
+    word[..padding].iter().all(|b| *b == sign)
 }
 
 impl EthereumTypes {
     #[inline]
-    pub fn name_as_str(&self) -> &str {
+    pub fn name_as_str(&self) -> Cow<'static, str> {
+        self.param_type().name_as_str()
+    }
+
+    /// The type of the value.
+    ///
+    /// The element type of an empty dynamic array can't be told from the value, it's reported as
+    /// `uint256[]`. Use [`EthereumTypes::type_check`] to validate values against a declared type.
+    pub fn param_type(&self) -> ParamType {
+        match self {
+            Self::Address(_) => ParamType::Address,
+            Self::U256(_) => ParamType::U256,
+            Self::I256(_) => ParamType::Int(256),
+            Self::Bool(_) => ParamType::Bool,
+            Self::FixedBytes(bytes) => ParamType::FixedBytes(bytes.len()),
+            Self::Bytes(_) => ParamType::Bytes,
+            Self::String(_) => ParamType::String,
+            Self::Array(elements) => ParamType::Array(Box::new(
+                elements
+                    .first()
+                    .map_or(ParamType::U256, EthereumTypes::param_type),
+            )),
+            Self::FixedArray(elements) => ParamType::FixedArray(
+                Box::new(
+                    elements
+                        .first()
+                        .map_or(ParamType::U256, EthereumTypes::param_type),
+                ),
+                elements.len(),
+            ),
+            Self::Tuple(components) => {
+                ParamType::Tuple(components.iter().map(EthereumTypes::param_type).collect())
+            }
+        }
+    }
+
+    /// Whether the value can be encoded as the given type.
+    pub fn type_check(&self, kind: &ParamType) -> bool {
+        match (self, kind) {
+            (Self::Address(_), ParamType::Address)
+            | (Self::U256(_), ParamType::U256)
+            | (Self::Bool(_), ParamType::Bool)
+            | (Self::Bytes(_), ParamType::Bytes)
+            | (Self::String(_), ParamType::String) => true,
+            (Self::U256(word), ParamType::Uint(bits)) => {
+                U256(*word).leading_zeros() as usize >= 256 - bits
+            }
+            (Self::I256(word), ParamType::Int(bits)) => fits_int(word, *bits),
+            (Self::FixedBytes(bytes), ParamType::FixedBytes(size)) => bytes.len() == *size,
+            (Self::Array(elements), ParamType::Array(element)) => {
+                elements.iter().all(|e| e.type_check(element))
+            }
+            (Self::FixedArray(elements), ParamType::FixedArray(element, size)) => {
+                elements.len() == *size && elements.iter().all(|e| e.type_check(element))
+            }
+            (Self::Tuple(values), ParamType::Tuple(components)) => {
+                values.len() == components.len()
+                    && values.iter().zip(components).all(|(v, c)| v.type_check(c))
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the value is encoded in the tail section, behind an offset.
+    pub fn is_dynamic(&self) -> bool {
         match self {
-            Self::Address(_) => "address",
-            Self::U256(_) => "uint256",
+            Self::Bytes(_) | Self::String(_) | Self::Array(_) => true,
+            Self::FixedArray(values) | Self::Tuple(values) => values.iter().any(Self::is_dynamic),
+            _ => false,
         }
     }
 
+    /// The 32 byte word of a value that is encoded into a single word.
+    ///
+    /// Panics for dynamic values, arrays and tuples.
     #[inline]
     pub fn value_as_u256(&self) -> [u8; 32] {
         match self {
             Self::Address(val) => {
                 let mut extended = [0_u8; 32];
                 // extend the 20 byte address by writing it to a 32 byte zero array
-                for i in 12..32 {
-                    extended[i] = val[i - 12];
-                }
+                extended[12..].copy_from_slice(val);
                 extended
             }
-            Self::U256(val) => *val,
+            Self::U256(val) | Self::I256(val) => *val,
+            Self::Bool(val) => {
+                let mut word = [0_u8; 32];
+                word[31] = u8::from(*val);
+                word
+            }
+            Self::FixedBytes(bytes) => {
+                assert!(bytes.len() <= 32, "Byte array doesn't fit into 256 bits");
+                // fixed size byte arrays are right padded
+                let mut word = [0_u8; 32];
+                word[..bytes.len()].copy_from_slice(bytes);
+                word
+            }
+            _ => panic!("{} doesn't encode into a single word", self.name_as_str()),
         }
     }
 
@@ -35,9 +281,7 @@ impl EthereumTypes {
         assert!(bytes.len() <= 20, "Byte array doesn't fit into 160 bits");
         let mut val = [0_u8; 20];
         let diff = 20 - bytes.len();
-        for i in diff..20 {
-            val[i] = bytes[i - diff];
-        }
+        val[diff..].copy_from_slice(bytes);
         Self::Address(val)
     }
 
@@ -46,9 +290,87 @@ impl EthereumTypes {
         assert!(bytes.len() <= 32, "Byte array doesn't fit into 160 bits");
         let mut val = [0_u8; 32];
         let diff = 32 - bytes.len();
-        for i in diff..32 {
-            val[i] = bytes[i - diff];
-        }
+        val[diff..].copy_from_slice(bytes);
         Self::U256(val)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn param_type_names_test() {
+        for name in [
+            "address",
+            "bytes4",
+            "string[]",
+            "uint256[2][]",
+            "(address,(bool,bytes)[])[3]",
+            "uint96",
+            "int8",
+            "int256",
+            "()",
+        ] {
+            assert_eq!(ParamType::from_name(name).unwrap().name_as_str(), name);
+        }
+        for name in [
+            "bytes0",
+            "bytes33",
+            "uint256[0]",
+            "(address",
+            "address]",
+            "int",
+            "uint7",
+            "uint264",
+            "int0",
+            "int",
+        ] {
+            assert_eq!(ParamType::from_name(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn dynamic_test() {
+        let kind = ParamType::from_name("(address,uint256)[2]").unwrap();
+        assert!(!kind.is_dynamic());
+        assert_eq!(kind.head_size(), Some(128));
+        // sizes overflowing the head size are rejected
+        assert_eq!(ParamType::from_name("uint256[1152921504606846976]"), None);
+        assert_eq!(ParamType::from_name("uint256[576460752303423488][]"), None);
+        let half = "uint256[288230376151711744]";
+        assert!(ParamType::from_name(half).is_some());
+        assert_eq!(ParamType::from_name(&format!("({},{})", half, half)), None);
+        assert_eq!(
+            ParamType::FixedArray(Box::new(ParamType::U256), usize::MAX).head_size(),
+            None
+        );
+        assert!(ParamType::from_name("(address,string)[2]")
+            .unwrap()
+            .is_dynamic());
+        assert!(EthereumTypes::Tuple(vec![EthereumTypes::Bytes(vec![])]).is_dynamic());
+    }
+
+    #[test]
+    fn type_check_test() {
+        let addresses = ParamType::from_name("address[]").unwrap();
+        assert!(EthereumTypes::Array(vec![]).type_check(&addresses));
+        assert!(EthereumTypes::Array(vec![EthereumTypes::Address([0; 20])]).type_check(&addresses));
+        assert!(!EthereumTypes::Array(vec![EthereumTypes::U256([0; 32])]).type_check(&addresses));
+        assert!(!EthereumTypes::FixedBytes(vec![0; 3]).type_check(&ParamType::FixedBytes(4)));
+        let mut word = [0; 32];
+        word[24] = 1;
+        assert!(EthereumTypes::U256(word).type_check(&ParamType::Uint(64)));
+        assert!(!EthereumTypes::U256(word).type_check(&ParamType::Uint(56)));
+        assert!(!EthereumTypes::U256(word).type_check(&ParamType::Int(256)));
+        // -128 fits into an int8, 128 doesn't
+        let mut word = [0xff; 32];
+        word[31] = 0x80;
+        assert!(EthereumTypes::I256(word).type_check(&ParamType::Int(8)));
+        assert!(!EthereumTypes::I256([0; 32]).type_check(&ParamType::Uint(8)));
+        word = [0; 32];
+        word[31] = 0x80;
+        assert!(!EthereumTypes::I256(word).type_check(&ParamType::Int(8)));
+        assert!(EthereumTypes::I256(word).type_check(&ParamType::Int(16)));
+    }
+}
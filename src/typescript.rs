@@ -0,0 +1,157 @@
+use crate::hex;
+use crate::{Abi, Param};
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Emits TypeScript interfaces for the inputs and outputs of every function and the parameters of
+/// every event, followed by `as const` maps from canonical signatures to selectors and topics.
+///
+/// Generated names are prefixed with `contract_name`, overloaded functions get a numeric suffix.
+pub fn to_typescript(abi: &Abi, contract_name: &str) -> String {
+    let mut ts = format!(
+        "// Generated by zgen-abi from the {} ABI, do not edit.\n",
+        contract_name
+    );
+
+    let mut overloads = HashMap::<&str, usize>::new();
+    for function in abi.functions.iter() {
+        let count = overloads.entry(&function.name).or_insert(0);
+        *count += 1;
+        let mut name = format!("{}{}", contract_name, pascal_case(&function.name));
+        if *count > 1 {
+            name += &count.to_string();
+        }
+        interface(&mut ts, &format!("{}Call", name), &function.inputs);
+        match function.outputs.as_slice() {
+            [] => {}
+            [output] => {
+                let _ = write!(
+                    ts,
+                    "\nexport type {}Result = {};\n",
+                    name,
+                    ts_type(&output.kind, &output.components)
+                );
+            }
+            outputs => interface(&mut ts, &format!("{}Result", name), outputs),
+        }
+    }
+
+    let mut overloads = HashMap::<&str, usize>::new();
+    for event in abi.events.iter() {
+        let count = overloads.entry(&event.name).or_insert(0);
+        *count += 1;
+        let mut name = format!("{}{}", contract_name, pascal_case(&event.name));
+        if *count > 1 {
+            name += &count.to_string();
+        }
+        interface(&mut ts, &format!("{}Event", name), &event.inputs);
+    }
+
+    let _ = write!(ts, "\nexport const {}Selectors = {{\n", contract_name);
+    for function in abi.functions.iter() {
+        let _ = writeln!(
+            ts,
+            "  \"{}\": \"{}\",",
+            function.signature(),
+            hex::encode_prefixed(function.selector())
+        );
+    }
+    ts.push_str("} as const;\n");
+
+    let _ = write!(ts, "\nexport const {}Topics = {{\n", contract_name);
+    for event in abi.events.iter().filter(|e| !e.anonymous) {
+        let _ = writeln!(
+            ts,
+            "  \"{}\": \"{}\",",
+            event.signature(),
+            hex::encode_prefixed(event.topic())
+        );
+    }
+    ts.push_str("} as const;\n");
+
+    ts
+}
+
+fn interface(ts: &mut String, name: &str, params: &[Param]) {
+    let _ = write!(ts, "\nexport interface {} {{\n", name);
+    for (i, param) in params.iter().enumerate() {
+        let _ = writeln!(
+            ts,
+            "  {}: {};",
+            field_name(param, i),
+            ts_type(&param.kind, &param.components)
+        );
+    }
+    ts.push_str("}\n");
+}
+
+// TypeScript type of an ABI type, values are represented the way viem and ethers v6 do
+fn ts_type(kind: &str, components: &[Param]) -> String {
+    if let Some(open) = kind.rfind('[') {
+        let inner = ts_type(&kind[..open], components);
+        // nested arrays need parentheses, `readonly readonly T[][]` is not valid TypeScript
+        return if inner.starts_with("readonly") {
+            format!("readonly ({})[]", inner)
+        } else {
+            format!("readonly {}[]", inner)
+        };
+    }
+    match kind {
+        "tuple" => {
+            let fields: Vec<String> = components
+                .iter()
+                .enumerate()
+                .map(|(i, c)| format!("{}: {}", field_name(c, i), ts_type(&c.kind, &c.components)))
+                .collect();
+            format!("{{ {} }}", fields.join("; "))
+        }
+        "bool" => "boolean".to_owned(),
+        "string" => "string".to_owned(),
+        k if k.starts_with("uint") || k.starts_with("int") => "bigint".to_owned(),
+        // address, bytes, bytesN and function are all hex strings
+        _ => "`0x${string}`".to_owned(),
+    }
+}
+
+fn field_name(param: &Param, index: usize) -> String {
+    if param.name.is_empty() {
+        format!("arg{}", index)
+    } else {
+        param.name.to_string()
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typescript_test() {
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        let abi: Abi = serde_json::from_str(&json).unwrap();
+        let ts = to_typescript(&abi, "Erc20");
+
+        assert!(ts.contains(
+            "export interface Erc20TransferCall {\n  _to: `0x${string}`;\n  _value: bigint;\n}\n"
+        ));
+        assert!(ts.contains("export type Erc20BalanceOfResult = bigint;\n"));
+        assert!(ts.contains("export interface Erc20TransferEvent {\n  from: `0x${string}`;"));
+        assert!(ts.contains("  \"transfer(address,uint256)\": \"0xa9059cbb\",\n"));
+        assert!(ts.contains("} as const;\n\nexport const Erc20Topics = {\n"));
+    }
+
+    #[test]
+    fn ts_type_test() {
+        assert_eq!(ts_type("uint8[2][]", &[]), "readonly (readonly bigint[])[]");
+        assert_eq!(ts_type("bytes32", &[]), "`0x${string}`");
+    }
+}
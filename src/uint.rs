@@ -0,0 +1,613 @@
+use crate::{EthereumTypes, ParamType};
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, ShlAssign, Shr,
+    ShrAssign,
+};
+use std::str::FromStr;
+
+/// An unsigned 256 bit number stored as 32 big-endian bytes, exactly like an ABI encoded word.
+///
+/// Comparing the big-endian bytes lexicographically is the same as comparing the numbers, so the
+/// ordering is derived.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct U256(pub [u8; 32]);
+
+impl U256 {
+    pub const ZERO: U256 = U256([0_u8; 32]);
+    pub const MAX: U256 = U256([0xff_u8; 32]);
+
+    pub const ONE: U256 = {
+        let mut bytes = [0_u8; 32];
+        bytes[31] = 1;
+        U256(bytes)
+    };
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    /// Number of leading zero bits, 256 for zero.
+    pub fn leading_zeros(&self) -> u32 {
+        match self.0.iter().position(|b| *b != 0) {
+            Some(i) => 8 * i as u32 + self.0[i].leading_zeros(),
+            None => 256,
+        }
+    }
+
+    #[inline]
+    pub const fn from_be_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    #[inline]
+    pub const fn to_be_bytes(self) -> [u8; 32] {
+        self.0
+    }
+
+    /// Adds the numbers, returning whether the addition overflowed and wrapped around.
+    pub fn overflowing_add(self, rhs: U256) -> (U256, bool) {
+        let (a, b) = (self.to_limbs(), rhs.to_limbs());
+        let mut sum = [0_u64; 4];
+        let mut carry = false;
+        for i in 0..4 {
+            let (s, c1) = a[i].overflowing_add(b[i]);
+            let (s, c2) = s.overflowing_add(u64::from(carry));
+            sum[i] = s;
+            carry = c1 || c2;
+        }
+        (U256::from_limbs(sum), carry)
+    }
+
+    /// Subtracts the numbers, returning whether the subtraction underflowed and wrapped around.
+    pub fn overflowing_sub(self, rhs: U256) -> (U256, bool) {
+        let (a, b) = (self.to_limbs(), rhs.to_limbs());
+        let mut difference = [0_u64; 4];
+        let mut borrow = false;
+        for i in 0..4 {
+            let (d, b1) = a[i].overflowing_sub(b[i]);
+            let (d, b2) = d.overflowing_sub(u64::from(borrow));
+            difference[i] = d;
+            borrow = b1 || b2;
+        }
+        (U256::from_limbs(difference), borrow)
+    }
+
+    #[inline]
+    pub fn checked_add(self, rhs: U256) -> Option<U256> {
+        match self.overflowing_add(rhs) {
+            (sum, false) => Some(sum),
+            (_, true) => None,
+        }
+    }
+
+    #[inline]
+    pub fn checked_sub(self, rhs: U256) -> Option<U256> {
+        match self.overflowing_sub(rhs) {
+            (difference, false) => Some(difference),
+            (_, true) => None,
+        }
+    }
+
+    /// Multiplies the numbers, returning whether the multiplication overflowed and wrapped around.
+    pub fn overflowing_mul(self, rhs: U256) -> (U256, bool) {
+        let (a, b) = (self.to_limbs(), rhs.to_limbs());
+        let mut product = [0_u64; 8];
+        for i in 0..4 {
+            let mut carry = 0_u128;
+            for j in 0..4 {
+                let t = u128::from(a[i]) * u128::from(b[j]) + u128::from(product[i + j]) + carry;
+                product[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            product[i + 4] = carry as u64;
+        }
+        let low = [product[0], product[1], product[2], product[3]];
+        (U256::from_limbs(low), product[4..].iter().any(|l| *l != 0))
+    }
+
+    #[inline]
+    pub fn checked_mul(self, rhs: U256) -> Option<U256> {
+        match self.overflowing_mul(rhs) {
+            (product, false) => Some(product),
+            (_, true) => None,
+        }
+    }
+
+    // divides by a small non-zero divisor, e.g. 10 for decimal formatting
+    pub(crate) fn div_rem_u64(self, divisor: u64) -> (U256, u64) {
+        let mut limbs = self.to_limbs();
+        let mut remainder = 0_u128;
+        for limb in limbs.iter_mut().rev() {
+            let current = (remainder << 64) | u128::from(*limb);
+            *limb = (current / u128::from(divisor)) as u64;
+            remainder = current % u128::from(divisor);
+        }
+        (U256::from_limbs(limbs), remainder as u64)
+    }
+
+    // little-endian 64 bit limbs, which are easier to do bit and arithmetic operations on
+    #[inline]
+    pub(crate) fn to_limbs(self) -> [u64; 4] {
+        let mut limbs = [0_u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let mut bytes = [0_u8; 8];
+            bytes.copy_from_slice(&self.0[24 - 8 * i..32 - 8 * i]);
+            *limb = u64::from_be_bytes(bytes);
+        }
+        limbs
+    }
+
+    #[inline]
+    pub(crate) fn from_limbs(limbs: [u64; 4]) -> Self {
+        let mut bytes = [0_u8; 32];
+        for (i, limb) in limbs.iter().enumerate() {
+            bytes[24 - 8 * i..32 - 8 * i].copy_from_slice(&limb.to_be_bytes());
+        }
+        Self(bytes)
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(value: u64) -> Self {
+        Self::from_limbs([value, 0, 0, 0])
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        Self::from_limbs([value as u64, (value >> 64) as u64, 0, 0])
+    }
+}
+
+impl From<[u8; 32]> for U256 {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<U256> for EthereumTypes {
+    fn from(value: U256) -> Self {
+        EthereumTypes::U256(value.0)
+    }
+}
+
+/// Formats the number in decimal.
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = Vec::new();
+        let mut rest = *self;
+        loop {
+            let (quotient, digit) = rest.div_rem_u64(10);
+            digits.push(b'0' + digit as u8);
+            rest = quotient;
+            if rest.is_zero() {
+                break;
+            }
+        }
+        digits.reverse();
+        // only ASCII digits were pushed
+        f.pad_integral(true, "", std::str::from_utf8(&digits).unwrap())
+    }
+}
+
+/// Decimal if the number fits into 128 bits, `0x` prefixed hex of the word otherwise.
+#[cfg(feature = "defmt")]
+impl defmt::Format for U256 {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        if self.leading_zeros() >= 128 {
+            let mut low = [0_u8; 16];
+            low.copy_from_slice(&self.0[16..]);
+            defmt::write!(f, "{=u128}", u128::from_be_bytes(low))
+        } else {
+            let mut hex = [0_u8; 66];
+            defmt::write!(
+                f,
+                "{=str}",
+                crate::hex::encode_prefixed_into(&self.0, &mut hex)
+            )
+        }
+    }
+}
+
+/// Hex digits without leading zeros, `{:#x}` is the `0x` prefixed JSON-RPC quantity encoding.
+impl fmt::LowerHex for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = crate::hex::encode(self.0);
+        let digits = hex.trim_start_matches('0');
+        f.pad_integral(true, "0x", if digits.is_empty() { "0" } else { digits })
+    }
+}
+
+/// Parses a decimal number like `1000000`, optionally with `_` separators like `1_000_000` or in
+/// scientific notation like `1e18` or `2.5e9`, as long as the result is an integer.
+impl FromStr for U256 {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("Cannot parse a number from an empty string.".to_owned());
+        }
+        let invalid = || format!("Invalid number {}: unexpected character.", s);
+        let overflow = || format!("Number {} doesn't fit into 256 bits.", s);
+
+        let (mantissa, exponent) = match s.find(['e', 'E']) {
+            Some(i) => (&s[..i], digits(&s[i + 1..]).ok_or_else(invalid)?),
+            None => (s, "0".to_owned()),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, "0"));
+        let integer = digits(integer).ok_or_else(invalid)?;
+        let fraction = digits(fraction).ok_or_else(invalid)?;
+        // trailing zeros of the fraction don't need to be covered by the exponent
+        let fraction = fraction.trim_end_matches('0');
+
+        let exponent: u32 = exponent.parse().map_err(|_| overflow())?;
+        let shift = exponent
+            .checked_sub(fraction.len() as u32)
+            .ok_or_else(|| format!("Number {} is not an integer.", s))?;
+        let value = from_digits(&(integer + fraction)).ok_or_else(overflow)?;
+        if value.is_zero() {
+            return Ok(value);
+        }
+        (0..shift)
+            .try_fold(value, |value, _| value.checked_mul(U256::from(10_u64)))
+            .ok_or_else(overflow)
+    }
+}
+
+// the digits of a non-empty part of a number, without `_` separators between them
+fn digits(part: &str) -> Option<String> {
+    let valid = !part.is_empty()
+        && !part.starts_with('_')
+        && !part.ends_with('_')
+        && !part.contains("__")
+        && part.bytes().all(|c| c.is_ascii_digit() || c == b'_');
+    if valid {
+        Some(part.replace('_', ""))
+    } else {
+        None
+    }
+}
+
+fn from_digits(digits: &str) -> Option<U256> {
+    digits.bytes().try_fold(U256::ZERO, |value, digit| {
+        value
+            .checked_mul(U256::from(10_u64))?
+            .checked_add(U256::from(u64::from(digit - b'0')))
+    })
+}
+
+/// An unsigned number of `BITS` bits, i.e. the value of a `uintBITS` ABI type.
+///
+/// `BITS` has to be a multiple of 8 between 8 and 256, which is checked at compile time, and the
+/// value is range checked at construction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uint<const BITS: usize>(U256);
+
+pub type U8 = Uint<8>;
+pub type U16 = Uint<16>;
+pub type U32 = Uint<32>;
+pub type U64 = Uint<64>;
+pub type U96 = Uint<96>;
+pub type U128 = Uint<128>;
+pub type U160 = Uint<160>;
+
+impl<const BITS: usize> Uint<BITS> {
+    pub const ZERO: Self = Self(U256::ZERO);
+
+    pub const MAX: Self = {
+        assert!(
+            BITS.is_multiple_of(8) && BITS >= 8 && BITS <= 256,
+            "Integer types have a multiple of 8 bits between 8 and 256"
+        );
+        let mut bytes = [0_u8; 32];
+        let mut i = 32 - BITS / 8;
+        while i < 32 {
+            bytes[i] = 0xff;
+            i += 1;
+        }
+        Self(U256(bytes))
+    };
+
+    /// Fails if the value doesn't fit into `BITS` bits.
+    pub fn new(value: U256) -> Result<Self, String> {
+        if value > Self::MAX.0 {
+            return Err(format!("Value doesn't fit into a uint{}.", BITS));
+        }
+        Ok(Self(value))
+    }
+
+    #[inline]
+    pub fn value(self) -> U256 {
+        self.0
+    }
+
+    /// The ABI type of the values, e.g. `uint96` for `Uint<96>`.
+    pub fn param_type() -> ParamType {
+        match Self::MAX.0 {
+            U256::MAX => ParamType::U256,
+            _ => ParamType::Uint(BITS),
+        }
+    }
+}
+
+impl<const BITS: usize> TryFrom<U256> for Uint<BITS> {
+    type Error = String;
+
+    fn try_from(value: U256) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl<const BITS: usize> TryFrom<u64> for Uint<BITS> {
+    type Error = String;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        Self::new(U256::from(value))
+    }
+}
+
+impl<const BITS: usize> From<Uint<BITS>> for U256 {
+    fn from(value: Uint<BITS>) -> Self {
+        value.0
+    }
+}
+
+impl<const BITS: usize> From<Uint<BITS>> for EthereumTypes {
+    fn from(value: Uint<BITS>) -> Self {
+        EthereumTypes::U256(value.0 .0)
+    }
+}
+
+macro_rules! bitwise_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl $trait for U256 {
+            type Output = U256;
+
+            #[inline]
+            fn $method(mut self, rhs: U256) -> U256 {
+                self.$assign_method(rhs);
+                self
+            }
+        }
+
+        impl $assign_trait for U256 {
+            #[inline]
+            fn $assign_method(&mut self, rhs: U256) {
+                for (byte, other) in self.0.iter_mut().zip(rhs.0.iter()) {
+                    *byte $op *other;
+                }
+            }
+        }
+    };
+}
+
+bitwise_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &=);
+bitwise_op!(BitOr, bitor, BitOrAssign, bitor_assign, |=);
+bitwise_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^=);
+
+impl Not for U256 {
+    type Output = U256;
+
+    #[inline]
+    fn not(self) -> U256 {
+        let mut bytes = self.0;
+        for byte in bytes.iter_mut() {
+            *byte = !*byte;
+        }
+        U256(bytes)
+    }
+}
+
+/// Shifting by 256 or more bits results in zero, like the EVM's `SHL` instruction.
+impl Shl<u32> for U256 {
+    type Output = U256;
+
+    fn shl(self, shift: u32) -> U256 {
+        if shift >= 256 {
+            return U256::ZERO;
+        }
+        let limbs = self.to_limbs();
+        let (limb_shift, bit_shift) = ((shift / 64) as usize, shift % 64);
+        let mut shifted = [0_u64; 4];
+        for i in limb_shift..4 {
+            shifted[i] = limbs[i - limb_shift] << bit_shift;
+            if bit_shift > 0 && i > limb_shift {
+                shifted[i] |= limbs[i - limb_shift - 1] >> (64 - bit_shift);
+            }
+        }
+        U256::from_limbs(shifted)
+    }
+}
+
+/// Shifting by 256 or more bits results in zero, like the EVM's `SHR` instruction.
+impl Shr<u32> for U256 {
+    type Output = U256;
+
+    fn shr(self, shift: u32) -> U256 {
+        if shift >= 256 {
+            return U256::ZERO;
+        }
+        let limbs = self.to_limbs();
+        let (limb_shift, bit_shift) = ((shift / 64) as usize, shift % 64);
+        let mut shifted = [0_u64; 4];
+        for i in 0..4 - limb_shift {
+            shifted[i] = limbs[i + limb_shift] >> bit_shift;
+            if bit_shift > 0 && i + limb_shift < 3 {
+                shifted[i] |= limbs[i + limb_shift + 1] << (64 - bit_shift);
+            }
+        }
+        U256::from_limbs(shifted)
+    }
+}
+
+impl ShlAssign<u32> for U256 {
+    #[inline]
+    fn shl_assign(&mut self, shift: u32) {
+        *self = *self << shift;
+    }
+}
+
+impl ShrAssign<u32> for U256 {
+    #[inline]
+    fn shr_assign(&mut self, shift: u32) {
+        *self = *self >> shift;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bitwise_test() {
+        let a = U256::from(0b1100_u64);
+        let b = U256::from(0b1010_u64);
+        assert_eq!(a & b, U256::from(0b1000_u64));
+        assert_eq!(a | b, U256::from(0b1110_u64));
+        assert_eq!(a ^ b, U256::from(0b0110_u64));
+        assert_eq!(!U256::ZERO, U256::MAX);
+        assert_eq!(!a & a, U256::ZERO);
+    }
+
+    #[test]
+    fn ordering_test() {
+        let small = U256::from(u64::MAX);
+        let large = U256::ONE << 64;
+        assert!(small < large);
+        assert!(U256::MAX > large);
+        assert_eq!(small.max(large), large);
+        assert_eq!(small.min(large), small);
+        assert!(U256::ZERO.is_zero());
+        assert!(!U256::ONE.is_zero());
+
+        // slippage check: the received amount has to be at least the minimum
+        let minimum = U256::from(995_u64);
+        assert!(U256::from(1000_u64) >= minimum);
+        assert!(U256::from(990_u64) < minimum);
+    }
+
+    #[test]
+    fn add_sub_test() {
+        let a = U256::from(u64::MAX);
+        assert_eq!(a.checked_add(U256::ONE), Some(U256::ONE << 64));
+        assert_eq!((U256::ONE << 64).checked_sub(U256::ONE), Some(a));
+        assert_eq!(U256::MAX.checked_add(U256::ONE), None);
+        assert_eq!(U256::ZERO.checked_sub(U256::ONE), None);
+        assert_eq!(U256::ZERO.overflowing_sub(U256::ONE), (U256::MAX, true));
+    }
+
+    #[test]
+    fn mul_test() {
+        let a = U256::from(u64::MAX);
+        assert_eq!(
+            a.checked_mul(a),
+            Some(U256::from(u128::from(u64::MAX) * u128::from(u64::MAX)))
+        );
+        assert_eq!(
+            (U256::ONE << 128).checked_mul(U256::ONE << 127),
+            Some(U256::ONE << 255)
+        );
+        assert_eq!((U256::ONE << 128).checked_mul(U256::ONE << 128), None);
+        assert_eq!(U256::MAX.overflowing_mul(U256::MAX), (U256::ONE, true));
+    }
+
+    #[test]
+    fn decimal_test() {
+        assert_eq!(U256::ZERO.to_string(), "0");
+        assert_eq!(U256::from(1234567890_u64).to_string(), "1234567890");
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(U256::MAX.to_string(), max);
+        assert_eq!(max.parse::<U256>(), Ok(U256::MAX));
+        assert_eq!("007".parse::<U256>(), Ok(U256::from(7_u64)));
+        assert!(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+                .parse::<U256>()
+                .is_err()
+        );
+        assert!("".parse::<U256>().is_err());
+        assert!("-1".parse::<U256>().is_err());
+        assert!("0x10".parse::<U256>().is_err());
+    }
+
+    #[test]
+    fn lower_hex_test() {
+        assert_eq!(format!("{:#x}", U256::ZERO), "0x0");
+        assert_eq!(format!("{:#x}", U256::from(0x1000_u64)), "0x1000");
+        assert_eq!(format!("{:x}", U256::MAX), "f".repeat(64));
+    }
+
+    #[test]
+    fn scientific_notation_test() {
+        let wei = U256::from(10_u64.pow(18));
+        assert_eq!("1e18".parse::<U256>(), Ok(wei));
+        assert_eq!("1E18".parse::<U256>(), Ok(wei));
+        assert_eq!("2.5e9".parse::<U256>(), Ok(U256::from(2_500_000_000_u64)));
+        assert_eq!("1.50e1".parse::<U256>(), Ok(U256::from(15_u64)));
+        assert_eq!("1_000_000".parse::<U256>(), Ok(U256::from(1_000_000_u64)));
+        assert_eq!(
+            "1_000.000_1e4".parse::<U256>(),
+            Ok(U256::from(10_000_001_u64))
+        );
+        assert_eq!("0e1000".parse::<U256>(), Ok(U256::ZERO));
+        assert_eq!("1.0".parse::<U256>(), Ok(U256::ONE));
+        assert_eq!(
+            "1e77".parse::<U256>().unwrap().to_string(),
+            format!("1{}", "0".repeat(77))
+        );
+
+        assert!("1e78".parse::<U256>().is_err());
+        assert!("2.5e0".parse::<U256>().is_err());
+        assert!("1.5".parse::<U256>().is_err());
+        for invalid in [
+            "_1", "1_", "1__0", "1e", "e5", ".5", "1.", "1e-3", "1e1.5", "1_e3",
+        ] {
+            assert!(invalid.parse::<U256>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn leading_zeros_test() {
+        assert_eq!(U256::ZERO.leading_zeros(), 256);
+        assert_eq!(U256::ONE.leading_zeros(), 255);
+        assert_eq!((U256::ONE << 100).leading_zeros(), 155);
+        assert_eq!(U256::MAX.leading_zeros(), 0);
+    }
+
+    #[test]
+    fn sized_uint_test() {
+        assert_eq!(U64::MAX.value(), U256::from(u64::MAX));
+        assert_eq!(Uint::<256>::MAX.value(), U256::MAX);
+        assert!(U64::try_from(U256::from(u128::from(u64::MAX) + 1)).is_err());
+        assert_eq!(U8::try_from(255_u64).unwrap().value(), U256::from(255_u64));
+        assert!(U8::try_from(256_u64).is_err());
+
+        assert_eq!(U96::param_type().name_as_str(), "uint96");
+        assert_eq!(U160::param_type(), ParamType::Uint(160));
+        assert_eq!(Uint::<256>::param_type(), ParamType::U256);
+        let token = EthereumTypes::from(U96::try_from(7_u64).unwrap());
+        assert!(token.type_check(&U96::param_type()));
+    }
+
+    #[test]
+    fn shift_test() {
+        let one = U256::ONE;
+        assert_eq!(one << 64, U256::from(1_u128 << 64));
+        assert_eq!((one << 255).0[0], 0x80);
+        assert_eq!(one << 256, U256::ZERO);
+        assert_eq!((one << 255) >> 255, one);
+        assert_eq!(U256::MAX >> 252, U256::from(0xf_u64));
+        assert_eq!(U256::from(u128::MAX) << 4 >> 4, U256::from(u128::MAX));
+
+        // a tick bitmap style mask: bit 70 of a word
+        let mut word = U256::ZERO;
+        word |= one << 70;
+        assert!(word & (one << 70) != U256::ZERO);
+        word >>= 70;
+        assert_eq!(word, one);
+    }
+}
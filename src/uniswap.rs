@@ -0,0 +1,64 @@
+use crate::{encode_packed, Address, EthereumTypes, ParamType, U256};
+
+/// Largest pool fee, fees are `uint24` values in hundredths of a basis point.
+pub const MAX_UNISWAP_V3_FEE: u32 = (1 << 24) - 1;
+
+/// Packs a Uniswap V3 route into the `path` of `exactInput` calls, i.e.
+/// `tokenIn ++ fee ++ token ++ fee ++ … ++ tokenOut` with 3 byte fees.
+///
+/// `fees[i]` is the fee of the pool between `tokens[i]` and `tokens[i + 1]`. `exactOutput` takes
+/// the path of the reversed route, starting at the output token.
+pub fn uniswap_v3_path(tokens: &[Address], fees: &[u32]) -> Result<Vec<u8>, String> {
+    if tokens.len() < 2 || fees.len() != tokens.len() - 1 {
+        return Err(format!(
+            "A path of {} tokens needs {} fees, found {}.",
+            tokens.len(),
+            tokens.len().saturating_sub(1),
+            fees.len()
+        ));
+    }
+    let mut types = Vec::with_capacity(2 * tokens.len() - 1);
+    let mut packed = Vec::with_capacity(2 * tokens.len() - 1);
+    for (token, fee) in tokens.iter().zip(fees) {
+        if *fee > MAX_UNISWAP_V3_FEE {
+            return Err(format!("Pool fee {} doesn't fit into a uint24.", fee));
+        }
+        types.extend_from_slice(&[ParamType::Address, ParamType::Uint(24)]);
+        packed.push(EthereumTypes::from(*token));
+        packed.push(EthereumTypes::from(U256::from(u64::from(*fee))));
+    }
+    types.push(ParamType::Address);
+    packed.push(EthereumTypes::from(tokens[tokens.len() - 1]));
+    encode_packed(&types, &packed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    #[test]
+    fn path_test() {
+        let path = uniswap_v3_path(
+            &[
+                Address([0xaa; 20]),
+                Address([0xbb; 20]),
+                Address([0xcc; 20]),
+            ],
+            &[500, 3000],
+        )
+        .unwrap();
+        assert_eq!(path.len(), 20 + 3 + 20 + 3 + 20);
+        assert_eq!(hex::encode(&path[20..23]), "0001f4");
+        assert_eq!(path[23..43], [0xbb; 20]);
+        assert_eq!(hex::encode(&path[43..46]), "000bb8");
+        assert_eq!(path[46..], [0xcc; 20]);
+    }
+
+    #[test]
+    fn invalid_path_test() {
+        assert!(uniswap_v3_path(&[Address([0xaa; 20])], &[]).is_err());
+        assert!(uniswap_v3_path(&[Address([0xaa; 20]), Address([0xbb; 20])], &[]).is_err());
+        assert!(uniswap_v3_path(&[Address([0xaa; 20]), Address([0xbb; 20])], &[1 << 24]).is_err());
+    }
+}
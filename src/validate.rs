@@ -0,0 +1,404 @@
+use crate::{Abi, ParamType};
+
+use serde_json::{Map, Value};
+
+use std::fmt;
+
+/// A violation of the ABI specification found by [`validate_abi`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Line of the json where the offending entry starts, 1 based.
+    pub line: usize,
+    /// Path of the offending field, e.g. `[3].inputs[1].type`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, {}: {}", self.line, self.path, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Validates ABI json against the specification, reporting every violation instead of skipping
+/// what the lenient parser doesn't understand.
+///
+/// Checks the entry types, the fields required by each kind of entry, the parameter types
+/// including tuple components, state mutabilities and the number of indexed event parameters.
+pub fn validate_abi(json: &str) -> Result<(), Vec<ValidationError>> {
+    let value: Value = serde_json::from_str(json).map_err(|e| {
+        vec![ValidationError {
+            line: e.line(),
+            path: String::new(),
+            message: format!("Invalid json: {}", e),
+        }]
+    })?;
+    let entries = match &value {
+        Value::Array(entries) => entries,
+        _ => {
+            return Err(vec![error(1, String::new(), "An ABI is a json array.")]);
+        }
+    };
+
+    let lines = entry_lines(json);
+    let mut errors = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let mut validator = Validator {
+            line: lines.get(i).copied().unwrap_or(1),
+            errors: &mut errors,
+        };
+        validator.entry(&format!("[{}]", i), entry);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses the ABI after validating it with [`validate_abi`].
+pub fn parse_abi_strict(json: &str) -> Result<Abi<'_>, Vec<ValidationError>> {
+    validate_abi(json)?;
+    serde_json::from_str(json).map_err(|e| {
+        vec![ValidationError {
+            line: e.line(),
+            path: String::new(),
+            message: e.to_string(),
+        }]
+    })
+}
+
+fn error(line: usize, path: String, message: &str) -> ValidationError {
+    ValidationError {
+        line,
+        path,
+        message: message.to_owned(),
+    }
+}
+
+struct Validator<'e> {
+    line: usize,
+    errors: &'e mut Vec<ValidationError>,
+}
+
+impl Validator<'_> {
+    fn report(&mut self, path: &str, message: String) {
+        self.errors.push(ValidationError {
+            line: self.line,
+            path: path.to_owned(),
+            message,
+        });
+    }
+
+    fn entry(&mut self, path: &str, entry: &Value) {
+        let entry = match entry {
+            Value::Object(entry) => entry,
+            _ => return self.report(path, "An ABI entry is a json object.".to_owned()),
+        };
+        let kind = match entry.get("type") {
+            None => "function",
+            Some(Value::String(kind)) => kind,
+            Some(_) => return self.report(&format!("{}.type", path), "Expected a string.".into()),
+        };
+        let (name, inputs, outputs) = match kind {
+            "function" => (true, true, true),
+            "event" | "error" => (true, true, false),
+            "constructor" => (false, true, false),
+            "receive" | "fallback" => (false, false, false),
+            kind => {
+                return self.report(
+                    &format!("{}.type", path),
+                    format!("Unknown entry type {}.", kind),
+                )
+            }
+        };
+
+        if name {
+            match entry.get("name") {
+                Some(Value::String(name)) if !name.is_empty() => {}
+                Some(Value::String(_)) => {
+                    self.report(&format!("{}.name", path), "Empty name.".to_owned())
+                }
+                Some(_) => self.report(&format!("{}.name", path), "Expected a string.".to_owned()),
+                None => self.report(path, format!("A {} entry requires a name.", kind)),
+            }
+        }
+        let is_event = kind == "event";
+        for (field, required) in [("inputs", inputs), ("outputs", outputs)] {
+            match entry.get(field) {
+                Some(Value::Array(params)) => {
+                    self.params(&format!("{}.{}", path, field), params, is_event)
+                }
+                Some(_) => self.report(
+                    &format!("{}.{}", path, field),
+                    "Expected an array.".to_owned(),
+                ),
+                None if required => {
+                    self.report(path, format!("A {} entry requires {}.", kind, field))
+                }
+                None => {}
+            }
+        }
+        if is_event {
+            self.indexed(path, entry);
+        } else {
+            self.state_mutability(path, kind, entry);
+        }
+    }
+
+    fn params(&mut self, path: &str, params: &[Value], is_event: bool) {
+        for (i, param) in params.iter().enumerate() {
+            let path = format!("{}[{}]", path, i);
+            match param {
+                Value::Object(param) => {
+                    if let Some(canonical) = self.param(&path, param) {
+                        if ParamType::from_name(&canonical).is_none() {
+                            self.report(
+                                &format!("{}.type", path),
+                                format!("Invalid type {}.", canonical),
+                            );
+                        }
+                    }
+                    if !matches!(param.get("name"), None | Some(Value::String(_))) {
+                        self.report(&format!("{}.name", path), "Expected a string.".to_owned());
+                    }
+                    match param.get("indexed") {
+                        None | Some(Value::Bool(false)) => {}
+                        Some(Value::Bool(true)) if is_event => {}
+                        Some(Value::Bool(true)) => self.report(
+                            &format!("{}.indexed", path),
+                            "Only event parameters can be indexed.".to_owned(),
+                        ),
+                        Some(_) => self.report(
+                            &format!("{}.indexed", path),
+                            "Expected a boolean.".to_owned(),
+                        ),
+                    }
+                }
+                _ => self.report(&path, "A parameter is a json object.".to_owned()),
+            }
+        }
+    }
+
+    // the canonical type of the parameter, tuples expanded, if its structure is valid
+    fn param(&mut self, path: &str, param: &Map<String, Value>) -> Option<String> {
+        let kind = match param.get("type") {
+            Some(Value::String(kind)) => kind,
+            Some(_) => {
+                self.report(&format!("{}.type", path), "Expected a string.".to_owned());
+                return None;
+            }
+            None => {
+                self.report(path, "A parameter requires a type.".to_owned());
+                return None;
+            }
+        };
+        let components = param.get("components");
+        let suffix = match kind.strip_prefix("tuple") {
+            Some(suffix) => suffix,
+            None => {
+                if components.is_some() {
+                    self.report(
+                        &format!("{}.components", path),
+                        "Only tuple parameters have components.".to_owned(),
+                    );
+                }
+                return Some(kind.clone());
+            }
+        };
+        let components = match components {
+            Some(Value::Array(components)) if !components.is_empty() => components,
+            Some(Value::Array(_)) => {
+                self.report(
+                    &format!("{}.components", path),
+                    "A tuple has at least one component.".to_owned(),
+                );
+                return None;
+            }
+            _ => {
+                self.report(path, "A tuple parameter requires components.".to_owned());
+                return None;
+            }
+        };
+        let mut types = Vec::with_capacity(components.len());
+        for (i, component) in components.iter().enumerate() {
+            let path = format!("{}.components[{}]", path, i);
+            match component {
+                Value::Object(component) => types.push(self.param(&path, component)),
+                _ => {
+                    self.report(&path, "A parameter is a json object.".to_owned());
+                    types.push(None);
+                }
+            }
+        }
+        let types: Option<Vec<String>> = types.into_iter().collect();
+        Some(format!("({}){}", types?.join(","), suffix))
+    }
+
+    fn indexed(&mut self, path: &str, entry: &Map<String, Value>) {
+        let anonymous = match entry.get("anonymous") {
+            None | Some(Value::Bool(false)) => false,
+            Some(Value::Bool(true)) => true,
+            Some(_) => {
+                self.report(
+                    &format!("{}.anonymous", path),
+                    "Expected a boolean.".to_owned(),
+                );
+                false
+            }
+        };
+        let indexed = entry
+            .get("inputs")
+            .and_then(Value::as_array)
+            .map_or(0, |inputs| {
+                inputs
+                    .iter()
+                    .filter(|input| input.get("indexed") == Some(&Value::Bool(true)))
+                    .count()
+            });
+        // anonymous events don't spend a topic on the event signature
+        let max = if anonymous { 4 } else { 3 };
+        if indexed > max {
+            self.report(
+                &format!("{}.inputs", path),
+                format!(
+                    "{} indexed parameters, at most {} are allowed.",
+                    indexed, max
+                ),
+            );
+        }
+    }
+
+    fn state_mutability(&mut self, path: &str, kind: &str, entry: &Map<String, Value>) {
+        let path = format!("{}.stateMutability", path);
+        match entry.get("stateMutability") {
+            Some(Value::String(mutability)) => {
+                let allowed = match kind {
+                    "receive" => &["payable"][..],
+                    "constructor" | "fallback" => &["nonpayable", "payable"][..],
+                    _ => &["pure", "view", "nonpayable", "payable"][..],
+                };
+                if !allowed.contains(&mutability.as_str()) {
+                    self.report(
+                        &path,
+                        format!("Invalid state mutability {} of a {}.", mutability, kind),
+                    );
+                }
+            }
+            Some(_) => self.report(&path, "Expected a string.".to_owned()),
+            // pre 0.4.16 compilers only emit `constant` and `payable`
+            None => {}
+        }
+    }
+}
+
+// 1 based lines where the elements of the top level json array start, the json being valid
+fn entry_lines(json: &str) -> Vec<usize> {
+    let mut lines = Vec::new();
+    let (mut line, mut depth) = (1, 0_usize);
+    let (mut in_string, mut escaped, mut expecting) = (false, false, false);
+    for c in json.chars() {
+        if c == '\n' {
+            line += 1;
+        }
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        if expecting && !c.is_whitespace() && c != ']' {
+            lines.push(line);
+            expecting = false;
+        }
+        match c {
+            '"' => in_string = true,
+            '[' | '{' => {
+                depth += 1;
+                expecting = depth == 1;
+            }
+            ']' | '}' => depth -= 1,
+            ',' if depth == 1 => expecting = true,
+            _ => {}
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_abi_test() {
+        let json = std::fs::read_to_string("src/rust_abi.json").unwrap();
+        assert_eq!(validate_abi(&json), Ok(()));
+        assert_eq!(parse_abi_strict(&json).unwrap().functions.len(), 9);
+        let json = r#"[
+            {"type":"constructor","inputs":[],"stateMutability":"nonpayable"},
+            {"type":"receive","stateMutability":"payable"},
+            {"type":"error","name":"Unauthorized","inputs":[{"name":"who","type":"address"}]},
+            {"type":"function","name":"multicall","inputs":[{"name":"calls","type":"tuple[]",
+             "components":[{"name":"target","type":"address"},{"name":"data","type":"bytes"}]}],
+             "outputs":[],"stateMutability":"payable"}
+        ]"#;
+        assert_eq!(validate_abi(json), Ok(()));
+    }
+
+    #[test]
+    fn invalid_abi_test() {
+        let json = r#"[
+            {"type":"function","name":"transfer","inputs":[{"name":"to","type":"adress"}],
+             "outputs":[]},
+            {"type":"event","name":"Transfer","inputs":[
+                {"type":"address","indexed":true},{"type":"address","indexed":true},
+                {"type":"uint256","indexed":true},{"type":"uint256","indexed":true}]},
+            {"type":"function","inputs":[],"outputs":[],"stateMutability":"constant"},
+            {"type":"modifier"},
+            {"type":"function","name":"f","inputs":[{"name":"t","type":"tuple"}],"outputs":[]}
+        ]"#;
+        let errors = validate_abi(json).unwrap_err();
+        let reported: Vec<(usize, &str)> =
+            errors.iter().map(|e| (e.line, e.path.as_str())).collect();
+        assert_eq!(
+            reported,
+            [
+                (2, "[0].inputs[0].type"),
+                (4, "[1].inputs"),
+                (7, "[2]"),
+                (7, "[2].stateMutability"),
+                (8, "[3].type"),
+                (9, "[4].inputs[0]"),
+            ]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "line 2, [0].inputs[0].type: Invalid type adress."
+        );
+        assert!(parse_abi_strict(json).is_err());
+    }
+
+    #[test]
+    fn invalid_json_test() {
+        let errors = validate_abi("[\n{\"type\": }]").unwrap_err();
+        assert_eq!(errors[0].line, 2);
+        assert_eq!(
+            validate_abi("{}").unwrap_err()[0].message,
+            "An ABI is a json array."
+        );
+    }
+
+    #[test]
+    fn entry_lines_test() {
+        assert_eq!(
+            entry_lines("[\n{\"a\": \"[,\"},\n\n {\"b\": [1, 2]}\n]"),
+            [2, 4]
+        );
+        assert!(entry_lines("[]").is_empty());
+    }
+}